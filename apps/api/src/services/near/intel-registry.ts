@@ -24,7 +24,7 @@ export type ProofType =
   | 'FinancialThreshold'
   | 'GenericCommitment';
 
-export type VerificationStatus = 'Pending' | 'Verified' | 'Contested' | 'Refuted';
+export type VerificationStatus = 'Pending' | 'Verified' | 'Contested' | 'Refuted' | 'UnderDispute';
 
 export interface ProofCommitment {
   proof_id: string;
@@ -39,6 +39,8 @@ export interface ProofCommitment {
   status: VerificationStatus;
   attestation_count: number;
   avg_confidence: number;
+  expires_at: string | null;
+  corroboration_count: number;
 }
 
 export interface Attestation {
@@ -73,6 +75,7 @@ interface IntelRegistryContract extends Contract {
     intel_hash: string;
     public_inputs_hash: string;
     metadata?: string;
+    expires_at?: string;
   }) => Promise<ProofCommitment>;
   
   attest: (args: {
@@ -240,7 +243,7 @@ export class IntelRegistryClient {
   }
 
   /**
-   * Refute a proof (admin only)
+   * Open a dispute against a proof (admin); refuted once finalized after the window
    */
   async refuteProof(proofId: string, reason: string): Promise<void> {
     await this.connect();
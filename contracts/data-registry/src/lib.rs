@@ -1,7 +1,9 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::LookupMap;
-use near_sdk::json_types::U64;
-use near_sdk::{env, near_bindgen, AccountId, PanicOnDefault};
+use near_sdk::collections::{LookupMap, UnorderedMap, UnorderedSet, Vector};
+use near_sdk::json_types::{Base64VecU8, U64};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::{self, json};
+use near_sdk::{env, near_bindgen, require, AccountId, PanicOnDefault};
 
 /// User data registry - maps NEAR accounts to their IPFS data CIDs
 /// 
@@ -10,13 +12,54 @@ use near_sdk::{env, near_bindgen, AccountId, PanicOnDefault};
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct DataRegistry {
-    /// Maps account_id -> DataEntry
-    entries: LookupMap<AccountId, DataEntry>,
+    /// Maps account_id -> DataEntry in the default namespace
+    entries: UnorderedMap<AccountId, DataEntry>,
+    /// Maps account_id -> namespace -> DataEntry for every other namespace
+    namespaced_entries: LookupMap<AccountId, UnorderedMap<String, DataEntry>>,
     /// Total number of registered users
     total_users: u64,
+    /// Read grants: owner -> grantee -> expiry timestamp (u64::MAX = never)
+    grants: LookupMap<AccountId, UnorderedMap<AccountId, U64>>,
+    /// Recent versions per account and namespace, oldest first
+    history: LookupMap<String, Vector<DataVersion>>,
+    /// Number of versions kept in each account's history
+    max_history: u64,
+    /// Accounts allowed to update an owner's entry if the owner loses their key
+    recovery_delegates: LookupMap<AccountId, UnorderedSet<AccountId>>,
 }
 
-#[derive(BorshDeserialize, BorshSerialize, Clone)]
+/// Default number of versions kept per account
+const DEFAULT_MAX_HISTORY: u64 = 10;
+
+/// Namespace used when none is given; stored in `entries`
+const DEFAULT_NAMESPACE: &str = "default";
+const MAX_NAMESPACE_LEN: usize = 64;
+
+/// Length in bytes of an encryption public key
+const PUBLIC_KEY_LEN: usize = 32;
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE32_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz234567";
+
+/// State layout before entries became enumerable, used by `migrate`
+#[derive(BorshDeserialize)]
+struct OldDataRegistry {
+    entries: LookupMap<AccountId, OldDataEntry>,
+    total_users: u64,
+}
+
+/// Entry layout before `last_editor` was tracked, used by `migrate`
+#[derive(BorshDeserialize, BorshSerialize)]
+struct OldDataEntry {
+    cid: String,
+    version: u64,
+    updated_at: U64,
+    data_hash: String,
+    public_key: String,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
 pub struct DataEntry {
     /// IPFS CID of the UserDataStore
     pub cid: String,
@@ -28,6 +71,18 @@ pub struct DataEntry {
     pub data_hash: String,
     /// Public key for encryption (base64 encoded)
     pub public_key: String,
+    /// Account that made the last change (the owner or a recovery delegate)
+    pub last_editor: AccountId,
+}
+
+/// A past (or the current) version of an account's data pointer
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DataVersion {
+    pub version: u64,
+    pub cid: String,
+    pub data_hash: String,
+    pub updated_at: U64,
 }
 
 #[near_bindgen]
@@ -35,80 +90,497 @@ impl DataRegistry {
     #[init]
     pub fn new() -> Self {
         Self {
-            entries: LookupMap::new(b"e"),
+            entries: UnorderedMap::new(b"u"),
+            namespaced_entries: LookupMap::new(b"n"),
             total_users: 0,
+            grants: LookupMap::new(b"g"),
+            history: LookupMap::new(b"h"),
+            max_history: DEFAULT_MAX_HISTORY,
+            recovery_delegates: LookupMap::new(b"r"),
         }
     }
 
-    /// Register or update user's data CID
+    /// Move entries from the old `LookupMap` layout into the enumerable map.
+    /// `LookupMap` can't be iterated, so `accounts` must list every registered
+    /// account (e.g. collected from the "Data updated for" logs).
+    #[init(ignore_state)]
+    #[private]
+    pub fn migrate(accounts: Vec<AccountId>) -> Self {
+        let mut old: OldDataRegistry = env::state_read().expect("No state to migrate");
+        let mut entries = UnorderedMap::new(b"u");
+
+        for account_id in accounts {
+            if let Some(old_entry) = old.entries.remove(&account_id) {
+                let entry = DataEntry {
+                    cid: old_entry.cid,
+                    version: old_entry.version,
+                    updated_at: old_entry.updated_at,
+                    data_hash: old_entry.data_hash,
+                    public_key: old_entry.public_key,
+                    last_editor: account_id.clone(),
+                };
+                entries.insert(&account_id, &entry);
+            }
+        }
+
+        require!(
+            entries.len() == old.total_users,
+            "Account list does not cover every registered user"
+        );
+
+        Self {
+            entries,
+            namespaced_entries: LookupMap::new(b"n"),
+            total_users: old.total_users,
+            grants: LookupMap::new(b"g"),
+            history: LookupMap::new(b"h"),
+            max_history: DEFAULT_MAX_HISTORY,
+            recovery_delegates: LookupMap::new(b"r"),
+        }
+    }
+
+    /// Register or update user's data CID in a namespace (default: "default")
     /// Only the account owner can update their entry
     pub fn set_data(
         &mut self,
         cid: String,
         data_hash: String,
         public_key: String,
+        namespace: Option<String>,
     ) {
         let account_id = env::predecessor_account_id();
-        
-        let entry = if let Some(existing) = self.entries.get(&account_id) {
+        let namespace = Self::validated_namespace(namespace);
+        self.internal_set_data(account_id.clone(), account_id, namespace, cid, data_hash, public_key);
+    }
+
+    /// Update another account's entry as one of its recovery delegates
+    pub fn set_data_for(
+        &mut self,
+        owner: AccountId,
+        cid: String,
+        data_hash: String,
+        public_key: String,
+        namespace: Option<String>,
+    ) {
+        let delegate = env::predecessor_account_id();
+        require!(
+            self.is_recovery_delegate(owner.clone(), delegate.clone()),
+            "Not a recovery delegate for this account"
+        );
+        let namespace = Self::validated_namespace(namespace);
+        self.internal_set_data(owner, delegate, namespace, cid, data_hash, public_key);
+    }
+
+    fn internal_set_data(
+        &mut self,
+        account_id: AccountId,
+        editor: AccountId,
+        namespace: String,
+        cid: String,
+        data_hash: String,
+        public_key: String,
+    ) {
+        Self::assert_valid_data(&cid, &data_hash, &public_key);
+
+        let entry = if let Some(existing) = self.get_entry(&account_id, &namespace) {
             DataEntry {
                 cid,
                 version: existing.version + 1,
                 updated_at: U64(env::block_timestamp()),
                 data_hash,
                 public_key,
+                last_editor: editor,
             }
         } else {
-            self.total_users += 1;
+            if !self.has_any_entry(&account_id) {
+                self.total_users += 1;
+            }
             DataEntry {
                 cid,
                 version: 1,
                 updated_at: U64(env::block_timestamp()),
                 data_hash,
                 public_key,
+                last_editor: editor,
             }
         };
         
-        self.entries.insert(&account_id, &entry);
+        self.insert_entry(&account_id, &namespace, &entry);
+        self.record_version(&Self::history_key(&account_id, &namespace), &entry);
         
         env::log_str(&format!(
-            "Data updated for {} - CID: {}, Version: {}",
-            account_id, entry.cid, entry.version
+            "Data updated for {}/{} by {} - CID: {}, Version: {}",
+            account_id, namespace, entry.last_editor, entry.cid, entry.version
         ));
+        Self::emit_data_set(&account_id, &namespace, &entry);
+    }
+
+    /// Allow `delegate` to update the caller's entry via `set_data_for`
+    pub fn add_recovery_delegate(&mut self, delegate: AccountId) {
+        let owner = env::predecessor_account_id();
+        require!(delegate != owner, "Cannot add yourself as a delegate");
+
+        let mut delegates = self.recovery_delegates.get(&owner).unwrap_or_else(|| {
+            let prefix = [b"r".as_slice(), &env::sha256(owner.as_bytes())].concat();
+            UnorderedSet::new(prefix)
+        });
+        delegates.insert(&delegate);
+        self.recovery_delegates.insert(&owner, &delegates);
+
+        env::log_str(&format!("Recovery delegate {} added by {}", delegate, owner));
+    }
+
+    /// Remove one of the caller's recovery delegates
+    pub fn remove_recovery_delegate(&mut self, delegate: AccountId) -> bool {
+        let owner = env::predecessor_account_id();
+        let mut delegates = match self.recovery_delegates.get(&owner) {
+            Some(delegates) => delegates,
+            None => return false,
+        };
+
+        if !delegates.remove(&delegate) {
+            return false;
+        }
+
+        if delegates.is_empty() {
+            self.recovery_delegates.remove(&owner);
+        } else {
+            self.recovery_delegates.insert(&owner, &delegates);
+        }
+
+        env::log_str(&format!("Recovery delegate {} removed by {}", delegate, owner));
+        true
+    }
+
+    /// Check if `delegate` may update `owner`'s entry
+    pub fn is_recovery_delegate(&self, owner: AccountId, delegate: AccountId) -> bool {
+        self.recovery_delegates
+            .get(&owner)
+            .map(|delegates| delegates.contains(&delegate))
+            .unwrap_or(false)
     }
 
     /// Get user's data entry
-    pub fn get_data(&self, account_id: AccountId) -> Option<DataEntry> {
-        self.entries.get(&account_id)
+    pub fn get_data(&self, account_id: AccountId, namespace: Option<String>) -> Option<DataEntry> {
+        self.get_entry(&account_id, &Self::namespace_or_default(namespace))
     }
 
     /// Get just the CID (convenience method)
-    pub fn get_cid(&self, account_id: AccountId) -> Option<String> {
-        self.entries.get(&account_id).map(|e| e.cid)
+    pub fn get_cid(&self, account_id: AccountId, namespace: Option<String>) -> Option<String> {
+        self.get_data(account_id, namespace).map(|e| e.cid)
     }
 
     /// Get user's public key for encryption
-    pub fn get_public_key(&self, account_id: AccountId) -> Option<String> {
-        self.entries.get(&account_id).map(|e| e.public_key)
+    pub fn get_public_key(&self, account_id: AccountId, namespace: Option<String>) -> Option<String> {
+        self.get_data(account_id, namespace).map(|e| e.public_key)
+    }
+
+    /// List the namespaces an account has data in, default first
+    pub fn list_namespaces(&self, account_id: AccountId) -> Vec<String> {
+        let mut namespaces = Vec::new();
+        if self.entries.get(&account_id).is_some() {
+            namespaces.push(DEFAULT_NAMESPACE.to_string());
+        }
+        if let Some(named) = self.namespaced_entries.get(&account_id) {
+            namespaces.extend(named.keys());
+        }
+        namespaces
     }
 
-    /// Delete user's data entry (revoke)
+    /// Delete user's data entry in a namespace (revoke)
     /// Only the account owner can delete their entry
-    pub fn delete_data(&mut self) -> bool {
+    pub fn delete_data(&mut self, namespace: Option<String>) -> bool {
         let account_id = env::predecessor_account_id();
+        let namespace = Self::namespace_or_default(namespace);
         
-        if self.entries.remove(&account_id).is_some() {
-            self.total_users = self.total_users.saturating_sub(1);
-            env::log_str(&format!("Data deleted for {}", account_id));
+        if self.remove_entry(&account_id, &namespace).is_some() {
+            if !self.has_any_entry(&account_id) {
+                self.total_users = self.total_users.saturating_sub(1);
+            }
+            if let Some(mut history) = self.history.remove(&Self::history_key(&account_id, &namespace)) {
+                history.clear();
+            }
+            env::log_str(&format!("Data deleted for {}/{}", account_id, namespace));
+            Self::emit_event(
+                "data_deleted",
+                json!({ "account": account_id, "namespace": namespace }),
+            );
             true
         } else {
             false
         }
     }
 
+    /// Re-point the caller's entry at a CID from its history.
+    /// The version number still increments, so the rollback is itself a new version.
+    pub fn rollback(&mut self, to_version: u64, namespace: Option<String>) {
+        let account_id = env::predecessor_account_id();
+        let namespace = Self::namespace_or_default(namespace);
+        let history_key = Self::history_key(&account_id, &namespace);
+        let mut entry = self.get_entry(&account_id, &namespace).expect("No data registered");
+
+        let target = self
+            .history
+            .get(&history_key)
+            .and_then(|history| history.iter().find(|v| v.version == to_version))
+            .expect("Version not in history");
+
+        entry.cid = target.cid;
+        entry.data_hash = target.data_hash;
+        entry.version += 1;
+        entry.updated_at = U64(env::block_timestamp());
+        entry.last_editor = account_id.clone();
+
+        self.insert_entry(&account_id, &namespace, &entry);
+        self.record_version(&history_key, &entry);
+
+        env::log_str(&format!(
+            "Data for {}/{} rolled back to version {} - CID: {}, Version: {}",
+            account_id, namespace, to_version, entry.cid, entry.version
+        ));
+        Self::emit_data_set(&account_id, &namespace, &entry);
+    }
+
+    /// Get an account's recent versions in a namespace, oldest first (paginated)
+    pub fn get_history(
+        &self,
+        account_id: AccountId,
+        from: u64,
+        limit: u64,
+        namespace: Option<String>,
+    ) -> Vec<DataVersion> {
+        let namespace = Self::namespace_or_default(namespace);
+        self.history
+            .get(&Self::history_key(&account_id, &namespace))
+            .map(|history| {
+                history
+                    .iter()
+                    .skip(from as usize)
+                    .take(limit as usize)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Change how many versions are kept per account (contract account only).
+    /// Longer histories are trimmed on their next update.
+    #[private]
+    pub fn set_max_history(&mut self, max_history: u64) {
+        require!(max_history > 0, "History must keep at least one version");
+        self.max_history = max_history;
+    }
+
+    /// Get the number of versions kept per account
+    pub fn get_max_history(&self) -> u64 {
+        self.max_history
+    }
+
+    /// Let `grantee` read the caller's data pointer until `expires_at`
+    /// (nanosecond timestamp), or indefinitely if no expiry is given
+    pub fn grant_access(&mut self, grantee: AccountId, expires_at: Option<U64>) {
+        let owner = env::predecessor_account_id();
+        require!(grantee != owner, "Cannot grant access to yourself");
+        let expiry = expires_at.unwrap_or(U64(u64::MAX));
+        require!(expiry.0 > env::block_timestamp(), "Expiry must be in the future");
+
+        let mut owner_grants = self.grants.get(&owner).unwrap_or_else(|| {
+            let prefix = [b"g".as_slice(), &env::sha256(owner.as_bytes())].concat();
+            UnorderedMap::new(prefix)
+        });
+        owner_grants.insert(&grantee, &expiry);
+        self.grants.insert(&owner, &owner_grants);
+
+        env::log_str(&format!("Access granted by {} to {}", owner, grantee));
+    }
+
+    /// Revoke a previously granted read access
+    pub fn revoke_access(&mut self, grantee: AccountId) -> bool {
+        let owner = env::predecessor_account_id();
+        let mut owner_grants = match self.grants.get(&owner) {
+            Some(grants) => grants,
+            None => return false,
+        };
+
+        if owner_grants.remove(&grantee).is_none() {
+            return false;
+        }
+
+        if owner_grants.is_empty() {
+            self.grants.remove(&owner);
+        } else {
+            self.grants.insert(&owner, &owner_grants);
+        }
+
+        env::log_str(&format!("Access revoked by {} from {}", owner, grantee));
+        true
+    }
+
+    /// Check if `grantee` may read `owner`'s data pointer
+    pub fn can_read(&self, owner: AccountId, grantee: AccountId) -> bool {
+        if owner == grantee {
+            return true;
+        }
+
+        self.grants
+            .get(&owner)
+            .and_then(|grants| grants.get(&grantee))
+            .map(|expiry| env::block_timestamp() < expiry.0)
+            .unwrap_or(false)
+    }
+
+    fn assert_valid_data(cid: &str, data_hash: &str, public_key: &str) {
+        require!(!cid.is_empty(), "CID must not be empty");
+        let is_cid_v0 = cid.len() == 46
+            && cid.starts_with("Qm")
+            && cid.chars().all(|c| BASE58_ALPHABET.contains(c));
+        let is_cid_v1 = cid.len() > 1
+            && cid.starts_with('b')
+            && cid[1..].chars().all(|c| BASE32_ALPHABET.contains(c));
+        require!(
+            is_cid_v0 || is_cid_v1,
+            "CID must be a CIDv0 (Qm..., 46 chars) or base32 CIDv1 (b...)"
+        );
+
+        require!(
+            data_hash.len() == 64 && data_hash.chars().all(|c| c.is_ascii_hexdigit()),
+            "data_hash must be a 64-character hex SHA-256 digest"
+        );
+
+        let key: Base64VecU8 = serde_json::from_value(json!(public_key))
+            .expect("public_key must be valid base64");
+        require!(
+            key.0.len() == PUBLIC_KEY_LEN,
+            format!("public_key must decode to {} bytes", PUBLIC_KEY_LEN)
+        );
+    }
+
+    fn namespace_or_default(namespace: Option<String>) -> String {
+        namespace.unwrap_or_else(|| DEFAULT_NAMESPACE.to_string())
+    }
+
+    fn validated_namespace(namespace: Option<String>) -> String {
+        let namespace = Self::namespace_or_default(namespace);
+        require!(
+            !namespace.is_empty()
+                && namespace.len() <= MAX_NAMESPACE_LEN
+                && namespace
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'),
+            "Namespace must be 1-64 characters of [A-Za-z0-9._-]"
+        );
+        namespace
+    }
+
+    /// History is keyed by the bare account for the default namespace so that
+    /// pre-namespace histories stay readable
+    fn history_key(account_id: &AccountId, namespace: &str) -> String {
+        if namespace == DEFAULT_NAMESPACE {
+            account_id.to_string()
+        } else {
+            format!("{}#{}", account_id, namespace)
+        }
+    }
+
+    fn get_entry(&self, account_id: &AccountId, namespace: &str) -> Option<DataEntry> {
+        if namespace == DEFAULT_NAMESPACE {
+            self.entries.get(account_id)
+        } else {
+            self.namespaced_entries
+                .get(account_id)
+                .and_then(|named| named.get(&namespace.to_string()))
+        }
+    }
+
+    fn insert_entry(&mut self, account_id: &AccountId, namespace: &str, entry: &DataEntry) {
+        if namespace == DEFAULT_NAMESPACE {
+            self.entries.insert(account_id, entry);
+        } else {
+            let mut named = self.namespaced_entries.get(account_id).unwrap_or_else(|| {
+                let prefix = [b"n".as_slice(), &env::sha256(account_id.as_bytes())].concat();
+                UnorderedMap::new(prefix)
+            });
+            named.insert(&namespace.to_string(), entry);
+            self.namespaced_entries.insert(account_id, &named);
+        }
+    }
+
+    fn remove_entry(&mut self, account_id: &AccountId, namespace: &str) -> Option<DataEntry> {
+        if namespace == DEFAULT_NAMESPACE {
+            return self.entries.remove(account_id);
+        }
+
+        let mut named = self.namespaced_entries.get(account_id)?;
+        let removed = named.remove(&namespace.to_string());
+        if named.is_empty() {
+            self.namespaced_entries.remove(account_id);
+        } else {
+            self.namespaced_entries.insert(account_id, &named);
+        }
+        removed
+    }
+
+    fn has_any_entry(&self, account_id: &AccountId) -> bool {
+        self.entries.get(account_id).is_some()
+            || self
+                .namespaced_entries
+                .get(account_id)
+                .map(|named| !named.is_empty())
+                .unwrap_or(false)
+    }
+
+    fn emit_data_set(account_id: &AccountId, namespace: &str, entry: &DataEntry) {
+        Self::emit_event(
+            "data_set",
+            json!({
+                "account": account_id,
+                "namespace": namespace,
+                "cid": entry.cid,
+                "version": entry.version,
+                "data_hash": entry.data_hash,
+            }),
+        );
+    }
+
+    fn emit_event(event: &str, data: serde_json::Value) {
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"argus-data-registry\",\"version\":\"1.0.0\",\"event\":\"{}\",\"data\":[{}]}}",
+            event, data
+        ));
+    }
+
+    /// Append a version to the account's history, dropping the oldest
+    /// versions beyond `max_history`
+    fn record_version(&mut self, history_key: &String, entry: &DataEntry) {
+        let mut history = self.history.get(history_key).unwrap_or_else(|| {
+            let prefix = [b"h".as_slice(), &env::sha256(history_key.as_bytes())].concat();
+            Vector::new(prefix)
+        });
+
+        history.push(&DataVersion {
+            version: entry.version,
+            cid: entry.cid.clone(),
+            data_hash: entry.data_hash.clone(),
+            updated_at: entry.updated_at,
+        });
+
+        // Shift the retained versions to the front and truncate
+        let excess = history.len().saturating_sub(self.max_history);
+        if excess > 0 {
+            for index in excess..history.len() {
+                let version = history.get(index).unwrap();
+                history.replace(index - excess, &version);
+            }
+            for _ in 0..excess {
+                history.pop();
+            }
+        }
+
+        self.history.insert(history_key, &history);
+    }
+
     /// Check if user has registered data
-    pub fn has_data(&self, account_id: AccountId) -> bool {
-        self.entries.contains_key(&account_id)
+    pub fn has_data(&self, account_id: AccountId, namespace: Option<String>) -> bool {
+        self.get_data(account_id, namespace).is_some()
     }
 
     /// Get total registered users
@@ -116,16 +588,34 @@ impl DataRegistry {
         self.total_users
     }
 
+    /// List accounts with data in the default namespace (paginated)
+    pub fn list_accounts(&self, from_index: u64, limit: u64) -> Vec<AccountId> {
+        self.entries
+            .keys()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// List default-namespace entries with their accounts (paginated)
+    pub fn list_entries(&self, from_index: u64, limit: u64) -> Vec<(AccountId, DataEntry)> {
+        self.entries
+            .iter()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
     /// Get data version for an account
-    pub fn get_version(&self, account_id: AccountId) -> u64 {
-        self.entries.get(&account_id).map(|e| e.version).unwrap_or(0)
+    pub fn get_version(&self, account_id: AccountId, namespace: Option<String>) -> u64 {
+        self.get_data(account_id, namespace).map(|e| e.version).unwrap_or(0)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::test_utils::{get_logs, VMContextBuilder};
     use near_sdk::testing_env;
 
     fn get_context(predecessor: AccountId) -> VMContextBuilder {
@@ -134,6 +624,21 @@ mod tests {
         builder
     }
 
+    /// A CIDv0-shaped string ("Qm" + 44 base58 chars)
+    fn cid(tag: &str) -> String {
+        format!("Qm{:1<44}", tag)
+    }
+
+    /// A SHA-256-shaped hex digest
+    fn hash(byte: u8) -> String {
+        format!("{:02x}", byte).repeat(32)
+    }
+
+    /// Base64 of a 32-byte key
+    fn pk(c: char) -> String {
+        format!("{}A=", c.to_string().repeat(42))
+    }
+
     #[test]
     fn test_set_and_get_data() {
         let alice: AccountId = "alice.near".parse().unwrap();
@@ -143,15 +648,16 @@ mod tests {
         let mut contract = DataRegistry::new();
         
         contract.set_data(
-            "QmTest123".to_string(),
-            "abc123hash".to_string(),
-            "pubkey123".to_string(),
+            cid("Test123"),
+            hash(1),
+            pk('A'),
+            None,
         );
 
-        let entry = contract.get_data(alice.clone()).unwrap();
-        assert_eq!(entry.cid, "QmTest123");
+        let entry = contract.get_data(alice.clone(), None).unwrap();
+        assert_eq!(entry.cid, cid("Test123"));
         assert_eq!(entry.version, 1);
-        assert_eq!(entry.data_hash, "abc123hash");
+        assert_eq!(entry.data_hash, hash(1));
         assert_eq!(contract.get_total_users(), 1);
     }
 
@@ -163,11 +669,11 @@ mod tests {
 
         let mut contract = DataRegistry::new();
         
-        contract.set_data("QmFirst".to_string(), "hash1".to_string(), "pk1".to_string());
-        contract.set_data("QmSecond".to_string(), "hash2".to_string(), "pk2".to_string());
+        contract.set_data(cid("First"), hash(1), pk('A'), None);
+        contract.set_data(cid("Second"), hash(2), pk('B'), None);
 
-        let entry = contract.get_data(alice).unwrap();
-        assert_eq!(entry.cid, "QmSecond");
+        let entry = contract.get_data(alice, None).unwrap();
+        assert_eq!(entry.cid, cid("Second"));
         assert_eq!(entry.version, 2);
     }
 
@@ -179,11 +685,428 @@ mod tests {
 
         let mut contract = DataRegistry::new();
         
-        contract.set_data("QmTest".to_string(), "hash".to_string(), "pk".to_string());
-        assert!(contract.has_data(alice.clone()));
+        contract.set_data(cid("Test"), hash(0), pk('A'), None);
+        assert!(contract.has_data(alice.clone(), None));
         
-        contract.delete_data();
-        assert!(!contract.has_data(alice));
+        contract.delete_data(None);
+        assert!(!contract.has_data(alice, None));
         assert_eq!(contract.get_total_users(), 0);
     }
+
+    #[test]
+    fn test_list_accounts_pagination() {
+        let mut contract = DataRegistry::new();
+        for name in ["alice.near", "bob.near", "carol.near"] {
+            testing_env!(get_context(name.parse().unwrap()).build());
+            contract.set_data(cid("Test"), hash(0), pk('A'), None);
+        }
+
+        let first: Vec<String> = contract.list_accounts(0, 2).iter().map(|a| a.to_string()).collect();
+        assert_eq!(first, vec!["alice.near", "bob.near"]);
+        let rest: Vec<String> = contract.list_accounts(2, 2).iter().map(|a| a.to_string()).collect();
+        assert_eq!(rest, vec!["carol.near"]);
+        assert!(contract.list_accounts(3, 2).is_empty());
+        assert_eq!(contract.get_total_users(), contract.list_accounts(0, 10).len() as u64);
+    }
+
+    #[test]
+    fn test_list_entries_pagination() {
+        let mut contract = DataRegistry::new();
+        for (name, tag) in [("alice.near", "A"), ("bob.near", "B")] {
+            testing_env!(get_context(name.parse().unwrap()).build());
+            contract.set_data(cid(tag), hash(0), pk('A'), None);
+        }
+
+        // Deleting swaps the last entry into the removed slot
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        contract.delete_data(None);
+
+        let entries = contract.list_entries(0, 10);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, "bob.near".parse::<AccountId>().unwrap());
+        assert_eq!(entries[0].1.cid, cid("B"));
+    }
+
+    fn write_old_state(accounts: &[&str]) {
+        let mut entries = LookupMap::new(b"e");
+        for name in accounts {
+            let entry = OldDataEntry {
+                cid: format!("Qm{}", name),
+                version: 1,
+                updated_at: U64(0),
+                data_hash: "hash".to_string(),
+                public_key: "pk".to_string(),
+            };
+            entries.insert(&name.parse::<AccountId>().unwrap(), &entry);
+        }
+
+        #[derive(BorshSerialize)]
+        struct OldState {
+            entries: LookupMap<AccountId, OldDataEntry>,
+            total_users: u64,
+        }
+        env::state_write(&OldState { entries, total_users: accounts.len() as u64 });
+    }
+
+    #[test]
+    fn test_migrate_from_lookup_map() {
+        testing_env!(get_context("data-registry.near".parse().unwrap()).build());
+        write_old_state(&["alice.near", "bob.near"]);
+
+        let contract = DataRegistry::migrate(vec![
+            "alice.near".parse().unwrap(),
+            "bob.near".parse().unwrap(),
+        ]);
+
+        assert_eq!(contract.get_total_users(), 2);
+        assert_eq!(contract.list_accounts(0, 10).len(), 2);
+        let bob = contract.get_data("bob.near".parse().unwrap(), None).unwrap();
+        assert_eq!(bob.cid, "Qmbob.near");
+        assert_eq!(bob.last_editor, "bob.near".parse::<AccountId>().unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "Account list does not cover every registered user")]
+    fn test_migrate_requires_every_account() {
+        testing_env!(get_context("data-registry.near".parse().unwrap()).build());
+        write_old_state(&["alice.near", "bob.near"]);
+
+        DataRegistry::migrate(vec!["alice.near".parse().unwrap()]);
+    }
+
+    #[test]
+    fn test_grant_access() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        testing_env!(get_context(alice.clone()).build());
+
+        let mut contract = DataRegistry::new();
+        assert!(contract.can_read(alice.clone(), alice.clone()));
+        assert!(!contract.can_read(alice.clone(), bob.clone()));
+
+        contract.grant_access(bob.clone(), None);
+        assert!(contract.can_read(alice.clone(), bob.clone()));
+        assert!(!contract.can_read(bob, alice));
+    }
+
+    #[test]
+    fn test_grant_expires() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        let mut context = get_context(alice.clone());
+        testing_env!(context.block_timestamp(1_000).build());
+
+        let mut contract = DataRegistry::new();
+        contract.grant_access(bob.clone(), Some(U64(2_000)));
+        assert!(contract.can_read(alice.clone(), bob.clone()));
+
+        testing_env!(context.block_timestamp(1_999).build());
+        assert!(contract.can_read(alice.clone(), bob.clone()));
+
+        testing_env!(context.block_timestamp(2_000).build());
+        assert!(!contract.can_read(alice, bob));
+    }
+
+    #[test]
+    fn test_revoke_access() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        let carol: AccountId = "carol.near".parse().unwrap();
+        testing_env!(get_context(alice.clone()).build());
+
+        let mut contract = DataRegistry::new();
+        contract.grant_access(bob.clone(), None);
+        contract.grant_access(carol.clone(), None);
+
+        assert!(contract.revoke_access(bob.clone()));
+        assert!(!contract.can_read(alice.clone(), bob.clone()));
+        assert!(contract.can_read(alice.clone(), carol.clone()));
+        assert!(!contract.revoke_access(bob));
+
+        // Re-granting after every grant is revoked starts a fresh map
+        assert!(contract.revoke_access(carol.clone()));
+        contract.grant_access(carol.clone(), None);
+        assert!(contract.can_read(alice, carol));
+    }
+
+    #[test]
+    #[should_panic(expected = "Expiry must be in the future")]
+    fn test_grant_with_past_expiry() {
+        let mut context = get_context("alice.near".parse().unwrap());
+        testing_env!(context.block_timestamp(1_000).build());
+
+        let mut contract = DataRegistry::new();
+        contract.grant_access("bob.near".parse().unwrap(), Some(U64(500)));
+    }
+
+    #[test]
+    fn test_history_and_rollback() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        testing_env!(get_context(alice.clone()).build());
+
+        let mut contract = DataRegistry::new();
+        contract.set_data(cid("Uno"), hash(1), pk('A'), None);
+        contract.set_data(cid("Two"), hash(2), pk('A'), None);
+        contract.set_data(cid("Bad"), hash(3), pk('A'), None);
+
+        let history = contract.get_history(alice.clone(), 0, 10, None);
+        let cids: Vec<String> = history.iter().map(|v| v.cid.clone()).collect();
+        assert_eq!(cids, vec![cid("Uno"), cid("Two"), cid("Bad")]);
+        assert_eq!(contract.get_history(alice.clone(), 1, 1, None)[0].version, 2);
+
+        contract.rollback(2, None);
+        let entry = contract.get_data(alice.clone(), None).unwrap();
+        assert_eq!(entry.cid, cid("Two"));
+        assert_eq!(entry.data_hash, hash(2));
+        assert_eq!(entry.version, 4);
+
+        let latest = contract.get_history(alice, 3, 10, None);
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].version, 4);
+        assert_eq!(latest[0].cid, cid("Two"));
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        testing_env!(get_context(alice.clone()).build());
+
+        let mut contract = DataRegistry::new();
+        contract.set_max_history(3);
+        for i in 1..=5 {
+            contract.set_data(cid(&i.to_string()), hash(i), pk('A'), None);
+        }
+
+        let versions: Vec<u64> = contract
+            .get_history(alice, 0, 10, None)
+            .iter()
+            .map(|v| v.version)
+            .collect();
+        assert_eq!(versions, vec![3, 4, 5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Version not in history")]
+    fn test_rollback_to_dropped_version() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+
+        let mut contract = DataRegistry::new();
+        contract.set_max_history(2);
+        for i in 1..=3 {
+            contract.set_data(cid(&i.to_string()), hash(i), pk('A'), None);
+        }
+
+        contract.rollback(1, None);
+    }
+
+    #[test]
+    fn test_delete_clears_history() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        testing_env!(get_context(alice.clone()).build());
+
+        let mut contract = DataRegistry::new();
+        contract.set_data(cid("Uno"), hash(1), pk('A'), None);
+        contract.delete_data(None);
+        assert!(contract.get_history(alice.clone(), 0, 10, None).is_empty());
+
+        contract.set_data(cid("New"), hash(0), pk('A'), None);
+        assert_eq!(contract.get_history(alice, 0, 10, None).len(), 1);
+    }
+
+    #[test]
+    fn test_recovery_delegate_update() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        testing_env!(get_context(alice.clone()).build());
+
+        let mut contract = DataRegistry::new();
+        contract.set_data(cid("Prior"), hash(1), pk('A'), None);
+        assert_eq!(contract.get_data(alice.clone(), None).unwrap().last_editor, alice);
+        contract.add_recovery_delegate(bob.clone());
+        assert!(contract.is_recovery_delegate(alice.clone(), bob.clone()));
+
+        testing_env!(get_context(bob.clone()).build());
+        contract.set_data_for(alice.clone(), cid("Recovered"), hash(2), pk('B'), None);
+
+        let entry = contract.get_data(alice.clone(), None).unwrap();
+        assert_eq!(entry.cid, cid("Recovered"));
+        assert_eq!(entry.public_key, pk('B'));
+        assert_eq!(entry.version, 2);
+        assert_eq!(entry.last_editor, bob);
+        assert!(!contract.has_data(bob, None));
+        assert_eq!(contract.get_total_users(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not a recovery delegate for this account")]
+    fn test_unauthorized_delegate_rejected() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        testing_env!(get_context(alice.clone()).build());
+
+        let mut contract = DataRegistry::new();
+        contract.set_data(cid("Prior"), hash(1), pk('A'), None);
+
+        testing_env!(get_context("mallory.near".parse().unwrap()).build());
+        contract.set_data_for(alice, cid("Bogus"), hash(0), pk('A'), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not a recovery delegate for this account")]
+    fn test_removed_delegate_rejected() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        testing_env!(get_context(alice.clone()).build());
+
+        let mut contract = DataRegistry::new();
+        contract.set_data(cid("Prior"), hash(1), pk('A'), None);
+        contract.add_recovery_delegate(bob.clone());
+        assert!(contract.remove_recovery_delegate(bob.clone()));
+        assert!(!contract.remove_recovery_delegate(bob.clone()));
+
+        testing_env!(get_context(bob).build());
+        contract.set_data_for(alice, cid("Bogus"), hash(0), pk('A'), None);
+    }
+
+    #[test]
+    fn test_data_events() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        testing_env!(get_context(alice.clone()).build());
+
+        let mut contract = DataRegistry::new();
+        contract.set_data(cid("Test"), hash(0xab), pk('A'), None);
+        assert_eq!(
+            get_logs().last().unwrap(),
+            &format!(
+                "EVENT_JSON:{{\"standard\":\"argus-data-registry\",\"version\":\"1.0.0\",\"event\":\"data_set\",\"data\":[{{\"account\":\"alice.near\",\"cid\":\"{}\",\"data_hash\":\"{}\",\"namespace\":\"default\",\"version\":1}}]}}",
+                cid("Test"),
+                hash(0xab)
+            )
+        );
+
+        contract.set_data(cid("Next"), hash(0xde), pk('A'), None);
+        contract.rollback(1, None);
+        assert_eq!(
+            get_logs().last().unwrap(),
+            &format!(
+                "EVENT_JSON:{{\"standard\":\"argus-data-registry\",\"version\":\"1.0.0\",\"event\":\"data_set\",\"data\":[{{\"account\":\"alice.near\",\"cid\":\"{}\",\"data_hash\":\"{}\",\"namespace\":\"default\",\"version\":3}}]}}",
+                cid("Test"),
+                hash(0xab)
+            )
+        );
+
+        contract.delete_data(None);
+        assert_eq!(
+            get_logs().last().unwrap(),
+            "EVENT_JSON:{\"standard\":\"argus-data-registry\",\"version\":\"1.0.0\",\"event\":\"data_deleted\",\"data\":[{\"account\":\"alice.near\",\"namespace\":\"default\"}]}"
+        );
+    }
+
+    #[test]
+    fn test_valid_cid_v1_accepted() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        testing_env!(get_context(alice.clone()).build());
+
+        let mut contract = DataRegistry::new();
+        let cid_v1 = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string();
+        contract.set_data(cid_v1.clone(), hash(0xAB).to_uppercase(), pk('Z'), None);
+        assert_eq!(contract.get_cid(alice, None), Some(cid_v1));
+    }
+
+    #[test]
+    #[should_panic(expected = "CID must not be empty")]
+    fn test_empty_cid_rejected() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        DataRegistry::new().set_data(String::new(), hash(1), pk('A'), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "CID must be a CIDv0 (Qm..., 46 chars) or base32 CIDv1 (b...)")]
+    fn test_short_cid_v0_rejected() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        DataRegistry::new().set_data("QmTooShort".to_string(), hash(1), pk('A'), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "CID must be a CIDv0 (Qm..., 46 chars) or base32 CIDv1 (b...)")]
+    fn test_non_base32_cid_v1_rejected() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        DataRegistry::new().set_data("bAFY0".to_string(), hash(1), pk('A'), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "data_hash must be a 64-character hex SHA-256 digest")]
+    fn test_short_data_hash_rejected() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        DataRegistry::new().set_data(cid("Test"), "abc123".to_string(), pk('A'), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "data_hash must be a 64-character hex SHA-256 digest")]
+    fn test_non_hex_data_hash_rejected() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        DataRegistry::new().set_data(cid("Test"), "zz".repeat(32), pk('A'), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "public_key must be valid base64")]
+    fn test_invalid_base64_public_key_rejected() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        DataRegistry::new().set_data(cid("Test"), hash(1), "not base64!".to_string(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "public_key must decode to 32 bytes")]
+    fn test_wrong_length_public_key_rejected() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        DataRegistry::new().set_data(cid("Test"), hash(1), "AAAA".to_string(), None);
+    }
+
+    #[test]
+    fn test_namespaced_entries() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        testing_env!(get_context(alice.clone()).build());
+
+        let mut contract = DataRegistry::new();
+        contract.set_data(cid("Contacts"), hash(1), pk('A'), Some("contacts".to_string()));
+        contract.set_data(cid("Documents"), hash(2), pk('A'), Some("documents".to_string()));
+        contract.set_data(cid("Documents2"), hash(3), pk('A'), Some("documents".to_string()));
+
+        assert_eq!(contract.get_total_users(), 1);
+        assert!(!contract.has_data(alice.clone(), None));
+        assert_eq!(
+            contract.get_cid(alice.clone(), Some("contacts".to_string())),
+            Some(cid("Contacts"))
+        );
+        assert_eq!(contract.get_version(alice.clone(), Some("contacts".to_string())), 1);
+        assert_eq!(contract.get_version(alice.clone(), Some("documents".to_string())), 2);
+        assert_eq!(
+            contract.get_history(alice.clone(), 0, 10, Some("documents".to_string())).len(),
+            2
+        );
+
+        // The default namespace is independent of named ones
+        contract.set_data(cid("Main"), hash(4), pk('A'), None);
+        assert_eq!(contract.get_version(alice.clone(), None), 1);
+        assert_eq!(contract.get_data(alice.clone(), Some("default".to_string())).unwrap().cid, cid("Main"));
+        assert_eq!(
+            contract.list_namespaces(alice.clone()),
+            vec!["default".to_string(), "contacts".to_string(), "documents".to_string()]
+        );
+
+        assert!(contract.delete_data(Some("contacts".to_string())));
+        assert!(contract.delete_data(None));
+        assert_eq!(contract.list_namespaces(alice.clone()), vec!["documents".to_string()]);
+        assert_eq!(contract.get_total_users(), 1);
+
+        assert!(contract.delete_data(Some("documents".to_string())));
+        assert!(contract.list_namespaces(alice).is_empty());
+        assert_eq!(contract.get_total_users(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Namespace must be 1-64 characters of [A-Za-z0-9._-]")]
+    fn test_invalid_namespace_rejected() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        DataRegistry::new().set_data(cid("Test"), hash(1), pk('A'), Some("has space".to_string()));
+    }
 }
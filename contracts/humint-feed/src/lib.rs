@@ -1,7 +1,8 @@
-use near_sdk::store::{LookupMap, UnorderedMap, UnorderedSet, LazyOption};
+use near_sdk::store::{LookupMap, UnorderedMap, UnorderedSet, LazyOption, Vector};
+use std::collections::HashMap;
 use near_sdk::json_types::{U128, U64};
 use near_sdk::serde_json;
-use near_sdk::{env, near, require, AccountId, BorshStorageKey, NearToken, PanicOnDefault, Promise};
+use near_sdk::{env, near, require, AccountId, BorshStorageKey, Gas, NearToken, PanicOnDefault, Promise, PromiseResult};
 
 /// HUMINT Feed Contract
 /// 
@@ -10,9 +11,44 @@ use near_sdk::{env, near, require, AccountId, BorshStorageKey, NearToken, PanicO
 /// - Post anchoring (encrypted content on IPFS, hash on-chain)
 /// - Access Pass NFTs (subscription-based access to source content)
 /// - Package management (custom pricing by sources)
+/// - Platform fee bookkeeping (USDC is settled off-chain by the relayer;
+///   the contract only tracks what the platform is owed)
 
 pub type TokenId = String;
 
+/// Proof types accepted on posts (mirrors intel-registry `ProofType`)
+const ZK_PROOF_TYPES: [&str; 10] = [
+    "LocationProximity",
+    "TimestampRange",
+    "DocumentContains",
+    "ImageMetadata",
+    "MultiSourceCorroboration",
+    "VerifiableCredential",
+    "SatelliteImagery",
+    "NetworkMembership",
+    "FinancialThreshold",
+    "GenericCommitment",
+];
+
+const GAS_FOR_NFT_ON_APPROVE: Gas = Gas::from_tgas(10);
+const GAS_FOR_NFT_ON_TRANSFER: Gas = Gas::from_tgas(25);
+const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas::from_tgas(10);
+const GAS_FOR_GET_PROOF: Gas = Gas::from_tgas(10);
+const GAS_FOR_RESOLVE_VERIFIED_ANCHOR: Gas = Gas::from_tgas(30);
+
+/// Most intel-registry proofs a single verified anchor may reference
+const MAX_VERIFIED_PROOFS: usize = 10;
+
+/// Highest secondary-sale royalty a source may set (25%)
+const MAX_SOURCE_ROYALTY_BPS: u16 = 2_500;
+
+/// Profile limits
+const MAX_DISPLAY_NAME_BYTES: usize = 64;
+const MAX_PROFILE_BIO_CHARS: usize = 280;
+const MAX_AVATAR_CID_BYTES: usize = 128;
+const MAX_PROFILE_CATEGORIES: usize = 10;
+const MAX_CATEGORY_BYTES: usize = 32;
+
 #[derive(BorshStorageKey)]
 #[near]
 pub enum StorageKey {
@@ -30,6 +66,25 @@ pub enum StorageKey {
     // Exclusions
     PostExclusions,
     PostExclusionsInner { post_id_hash: Vec<u8> },
+    // Approvals (NEP-178)
+    ApprovedAccounts,
+    ApprovedAccountsInner { token_id_hash: Vec<u8> },
+    // Passes by (owner, source)
+    SourcePasses,
+    SourcePassesInner { key_hash: Vec<u8> },
+    // Storage management (NEP-145)
+    StorageBalances,
+    PostCids,
+    PostOrder,
+    ProofTypePosts,
+    ProofTypePostsInner { proof_type_hash: Vec<u8> },
+    PostReaders,
+    PostReadersInner { post_id_hash: Vec<u8> },
+    SourceExclusions,
+    SourceExclusionsInner { source_hash: Vec<u8> },
+    SourceSubscribers,
+    SourceSubscribersInner { source_hash: Vec<u8> },
+    PackageMinted,
 }
 
 /// NFT Contract Metadata (NEP-177)
@@ -68,6 +123,8 @@ pub struct TokenMetadata {
 #[derive(Clone)]
 pub struct Token {
     pub owner_id: AccountId,
+    /// Next approval ID to hand out (NEP-178)
+    pub next_approval_id: u64,
 }
 
 /// A HUMINT source's public profile
@@ -88,6 +145,33 @@ pub struct Source {
     pub created_at: U64,
     /// Whether source is accepting new subscribers
     pub is_active: bool,
+    /// Account allowed to manage this source (exclusions, settings)
+    pub controller: Option<AccountId>,
+    /// Retired public keys with the timestamp they were rotated out
+    pub key_history: Vec<(String, U64)>,
+    /// Account that receives tips
+    pub payout_account: Option<AccountId>,
+    /// Total tips received, after platform fee (yoctoNEAR)
+    pub total_tips_received: U128,
+    /// Friendly name shown on minted access passes and alongside the profile
+    pub display_name: Option<String>,
+    /// Share of secondary pass sales paid to `payout_account` (basis points)
+    pub royalty_bps: u16,
+    /// Public profile shown by marketplaces
+    pub profile: Option<SourceProfile>,
+}
+
+/// Human-readable source profile (never identifies the source)
+/// The source's name is `Source.display_name`, set with `set_display_name`
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct SourceProfile {
+    /// Short bio, at most 280 characters
+    pub bio: String,
+    /// IPFS CID of the avatar image
+    pub avatar_cid: Option<String>,
+    /// Topics the source covers (e.g. "cyber", "energy")
+    pub categories: Vec<String>,
 }
 
 /// Subscription package definition (source-defined, USDC pricing)
@@ -98,9 +182,21 @@ pub struct Package {
     pub name: String,
     /// Price in USDC cents (e.g., 500 = $5.00)
     pub price_usdc_cents: u32,
-    /// Duration in days
+    /// Duration in days (ignored for lifetime packages)
     pub duration_days: u32,
     pub description: String,
+    /// Whether passes sold under this package are non-transferable
+    #[serde(default)]
+    pub soulbound: bool,
+    /// Artwork URL for passes sold under this package
+    #[serde(default)]
+    pub media: Option<String>,
+    /// Passes never expire (must be set explicitly; a zero duration is rejected)
+    #[serde(default)]
+    pub is_lifetime: bool,
+    /// Most passes that can ever be minted under this package (None = unlimited)
+    #[serde(default)]
+    pub max_supply: Option<u32>,
 }
 
 /// Post anchor (actual content encrypted on IPFS)
@@ -122,6 +218,63 @@ pub struct PostAnchor {
     pub source_hash: String,
     /// ZK proof types attached
     pub zk_proofs: Vec<String>,
+    /// Whether the post has been retracted (CID cleared, hash kept for audit)
+    pub redacted: bool,
+    /// Why the post was retracted
+    pub redaction_reason: Option<String>,
+    /// intel-registry proof IDs checked before anchoring (empty for unverified anchors)
+    pub proof_ids: Vec<String>,
+    /// Distinct accounts that recorded a read
+    pub read_count: u64,
+}
+
+/// Storage balance (NEP-145)
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct StorageBalance {
+    pub total: U128,
+    pub available: U128,
+}
+
+/// Storage balance bounds (NEP-145)
+#[near(serializers = [json])]
+pub struct StorageBalanceBounds {
+    pub min: U128,
+    pub max: Option<U128>,
+}
+
+/// NEP-199 payout: account -> amount owed from a sale
+#[near(serializers = [json])]
+pub struct Payout {
+    pub payout: HashMap<AccountId, U128>,
+}
+
+/// Input for batch post anchoring (same fields as `anchor_post`)
+#[near(serializers = [json])]
+pub struct PostAnchorInput {
+    pub post_id: String,
+    pub codename_hash: String,
+    pub content_hash: String,
+    pub content_cid: String,
+    pub is_premium: bool,
+    pub epoch: String,
+    pub zk_proofs: Vec<String>,
+    /// Anchor even if another post already uses this CID
+    #[serde(default)]
+    pub allow_duplicate_cid: bool,
+    /// intel-registry proof IDs backing the post (set by `anchor_post_verified`)
+    #[serde(default)]
+    pub proof_ids: Vec<String>,
+}
+
+/// Outcome of `can_read_post`
+/// `reason` is one of "free", "subscribed", "excluded", "expired",
+/// "no_subscription", "redacted" or "not_found"
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReadDecision {
+    pub allowed: bool,
+    pub reason: String,
 }
 
 /// Access Pass NFT data (stored with token)
@@ -138,6 +291,8 @@ pub struct AccessPassData {
     pub expires_at: U64,
     /// Amount paid in USDC cents
     pub amount_paid_usdc_cents: u32,
+    /// Non-transferable (bound to the original receiver)
+    pub soulbound: bool,
 }
 
 #[near(contract_state)]
@@ -151,17 +306,49 @@ pub struct HumintFeed {
     sources: UnorderedMap<String, Source>,
     /// Post anchors
     posts: LookupMap<String, PostAnchor>,
+    /// Post IDs in anchor order (for the global feed)
+    post_order: Vector<String>,
+    /// Posts by attached ZK proof type
+    proof_type_posts: LookupMap<String, UnorderedSet<String>>,
+    /// Content CID -> first post ID anchoring it
+    post_cids: LookupMap<String, String>,
     /// Posts by source
     source_posts: LookupMap<String, UnorderedSet<String>>,
     /// Per-post exclusions (revoked access)
     post_exclusions: LookupMap<String, UnorderedSet<AccountId>>,
+    /// Accounts barred from every premium post of a source
+    source_exclusions: LookupMap<String, UnorderedSet<AccountId>>,
+    /// Accounts that recorded reading each post
+    post_readers: LookupMap<String, UnorderedSet<AccountId>>,
     // NFT storage
     tokens_per_owner: LookupMap<AccountId, UnorderedSet<TokenId>>,
     tokens_by_id: UnorderedMap<TokenId, Token>,
     token_metadata_by_id: UnorderedMap<TokenId, TokenMetadata>,
     access_pass_data: UnorderedMap<TokenId, AccessPassData>,
+    /// Passes indexed by (owner, source_hash) for fast access checks
+    source_passes: LookupMap<(AccountId, String), UnorderedSet<TokenId>>,
+    /// Accounts holding at least one pass for each source (expired or not)
+    source_subscribers: LookupMap<String, UnorderedSet<AccountId>>,
+    /// Passes minted per (source_hash, package_id), for `max_supply` caps
+    package_minted: LookupMap<(String, String), u32>,
+    /// Approved accounts per token (NEP-178)
+    approved_accounts: LookupMap<TokenId, UnorderedMap<AccountId, u64>>,
     metadata: LazyOption<NFTContractMetadata>,
     next_token_id: u64,
+    /// Prepaid storage per account (NEP-145)
+    storage_balances: LookupMap<AccountId, StorageBalance>,
+    /// Platform fees owed on access pass sales (USDC cents, bookkeeping only)
+    accrued_fees_usdc_cents: u128,
+    /// Platform share of tips held by the contract (yoctoNEAR)
+    accrued_tip_fees: u128,
+    /// intel-registry contract used to verify proofs referenced by posts
+    intel_registry: Option<AccountId>,
+    /// Freezes minting, anchoring, transfers and source changes
+    paused: bool,
+    /// How long after expiry a pass still grants access (ns)
+    grace_period_ns: u64,
+    /// Account that receives withdrawn platform fees (defaults to the owner)
+    fee_recipient: AccountId,
 }
 
 #[near]
@@ -182,18 +369,34 @@ impl HumintFeed {
         };
 
         Self {
+            fee_recipient: owner_id.clone(),
             owner_id,
             platform_fee_bps,
             sources: UnorderedMap::new(StorageKey::Sources),
             posts: LookupMap::new(StorageKey::Posts),
+            post_order: Vector::new(StorageKey::PostOrder),
+            proof_type_posts: LookupMap::new(StorageKey::ProofTypePosts),
+            post_cids: LookupMap::new(StorageKey::PostCids),
             source_posts: LookupMap::new(StorageKey::SourcePosts),
             post_exclusions: LookupMap::new(StorageKey::PostExclusions),
+            source_exclusions: LookupMap::new(StorageKey::SourceExclusions),
+            post_readers: LookupMap::new(StorageKey::PostReaders),
             tokens_per_owner: LookupMap::new(StorageKey::TokensPerOwner),
             tokens_by_id: UnorderedMap::new(StorageKey::TokensById),
             token_metadata_by_id: UnorderedMap::new(StorageKey::TokenMetadataById),
             access_pass_data: UnorderedMap::new(StorageKey::AccessPassData),
+            source_passes: LookupMap::new(StorageKey::SourcePasses),
+            source_subscribers: LookupMap::new(StorageKey::SourceSubscribers),
+            package_minted: LookupMap::new(StorageKey::PackageMinted),
+            approved_accounts: LookupMap::new(StorageKey::ApprovedAccounts),
             metadata: LazyOption::new(StorageKey::NFTContractMetadata, Some(metadata)),
             next_token_id: 1,
+            storage_balances: LookupMap::new(StorageKey::StorageBalances),
+            accrued_fees_usdc_cents: 0,
+            accrued_tip_fees: 0,
+            intel_registry: None,
+            paused: false,
+            grace_period_ns: 0,
         }
     }
 
@@ -207,7 +410,10 @@ impl HumintFeed {
         codename_hash: String,
         public_key: String,
         packages: Vec<Package>,
+        controller: Option<AccountId>,
+        profile: Option<SourceProfile>,
     ) {
+        self.assert_not_paused();
         require!(
             self.sources.get(&codename_hash).is_none(),
             "Source already registered"
@@ -221,9 +427,12 @@ impl HumintFeed {
         
         // Validate packages
         for pkg in &packages {
-            require!(pkg.duration_days > 0, "Package duration must be > 0");
+            require!(pkg.is_lifetime || pkg.duration_days > 0, "Package duration must be > 0");
             require!(pkg.name.len() <= 50, "Package name too long");
         }
+        if let Some(profile) = &profile {
+            Self::assert_valid_profile(profile);
+        }
         
         let source = Source {
             codename_hash: codename_hash.clone(),
@@ -233,6 +442,13 @@ impl HumintFeed {
             subscriber_count: 0,
             created_at: U64(env::block_timestamp()),
             is_active: true,
+            controller,
+            key_history: vec![],
+            payout_account: None,
+            total_tips_received: U128(0),
+            display_name: None,
+            royalty_bps: 0,
+            profile,
         };
         
         self.sources.insert(codename_hash.clone(), source);
@@ -248,32 +464,237 @@ impl HumintFeed {
 
     /// Update source packages
     pub fn update_packages(&mut self, codename_hash: String, packages: Vec<Package>) {
+        self.assert_not_paused();
         let mut source = self.sources.get(&codename_hash)
             .expect("Source not found")
             .clone();
         
         // Validate packages
         for pkg in &packages {
-            require!(pkg.duration_days > 0, "Package duration must be > 0");
+            require!(pkg.is_lifetime || pkg.duration_days > 0, "Package duration must be > 0");
         }
         
         source.packages = packages;
         self.sources.insert(codename_hash, source);
     }
 
+    /// Set or clear the account that controls a source
+    /// Callable by contract owner or the current controller
+    pub fn set_source_controller(&mut self, codename_hash: String, controller: Option<AccountId>) {
+        require!(
+            self.internal_is_source_admin(&codename_hash, &env::predecessor_account_id()),
+            "Only owner or source controller"
+        );
+        
+        let mut source = self.sources.get(&codename_hash)
+            .expect("Source not found")
+            .clone();
+        source.controller = controller;
+        self.sources.insert(codename_hash, source);
+    }
+
+    /// Claim control of a source by revealing the codename behind its hash
+    /// Only sources without a controller can be claimed. The preimage is visible in
+    /// the transaction arguments, so the first claim binds the source for good;
+    /// later changes go through `set_source_controller`.
+    pub fn claim_source_control(&mut self, codename_hash: String, codename_preimage: String) {
+        let computed: String = env::sha256(codename_preimage.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        require!(computed.eq_ignore_ascii_case(&codename_hash), "Preimage does not match codename hash");
+        
+        let mut source = self.sources.get(&codename_hash)
+            .expect("Source not found")
+            .clone();
+        require!(source.controller.is_none(), "Source already has a controller");
+        
+        let claimant = env::predecessor_account_id();
+        source.controller = Some(claimant.clone());
+        self.sources.insert(codename_hash.clone(), source);
+        
+        env::log_str(&format!("Source {} claimed by {}", &codename_hash[..12], claimant));
+    }
+
+    /// Rotate a source's public key, keeping the old key in history
+    /// Callable by contract owner or the source controller
+    pub fn rotate_source_key(&mut self, codename_hash: String, new_public_key: String) {
+        require!(
+            self.internal_is_source_admin(&codename_hash, &env::predecessor_account_id()),
+            "Only owner or source controller"
+        );
+        
+        let mut source = self.sources.get(&codename_hash)
+            .expect("Source not found")
+            .clone();
+        require!(source.public_key != new_public_key, "Key unchanged");
+        
+        let old_key = std::mem::replace(&mut source.public_key, new_public_key);
+        source.key_history.push((old_key, U64(env::block_timestamp())));
+        self.sources.insert(codename_hash.clone(), source);
+        
+        env::log_str(&format!("Source key rotated: {}", &codename_hash[..12.min(codename_hash.len())]));
+    }
+
+    /// Set the account that receives a source's tips
+    /// Callable by contract owner or the source controller
+    pub fn set_payout_account(&mut self, codename_hash: String, payout_account: AccountId) {
+        require!(
+            self.internal_is_source_admin(&codename_hash, &env::predecessor_account_id()),
+            "Only owner or source controller"
+        );
+        
+        let mut source = self.sources.get(&codename_hash)
+            .expect("Source not found")
+            .clone();
+        source.payout_account = Some(payout_account);
+        self.sources.insert(codename_hash, source);
+    }
+
+    /// Set the royalty a source takes on secondary pass sales (max 25%)
+    /// Royalties are paid to the source's payout account
+    /// Callable by contract owner or the source controller
+    pub fn set_source_royalty(&mut self, codename_hash: String, royalty_bps: u16) {
+        require!(
+            self.internal_is_source_admin(&codename_hash, &env::predecessor_account_id()),
+            "Only owner or source controller"
+        );
+        require!(royalty_bps <= MAX_SOURCE_ROYALTY_BPS, "Royalty cannot exceed 25%");
+        
+        let mut source = self.sources.get(&codename_hash)
+            .expect("Source not found")
+            .clone();
+        source.royalty_bps = royalty_bps;
+        self.sources.insert(codename_hash, source);
+    }
+
+    /// Set the name used in titles of newly minted access passes (None uses the hash prefix)
+    /// Callable by contract owner or the source controller
+    pub fn set_display_name(&mut self, codename_hash: String, display_name: Option<String>) {
+        require!(
+            self.internal_is_source_admin(&codename_hash, &env::predecessor_account_id()),
+            "Only owner or source controller"
+        );
+        if let Some(name) = &display_name {
+            require!(!name.is_empty() && name.len() <= MAX_DISPLAY_NAME_BYTES, "Display name must be 1-64 bytes");
+        }
+        
+        let mut source = self.sources.get(&codename_hash)
+            .expect("Source not found")
+            .clone();
+        source.display_name = display_name;
+        self.sources.insert(codename_hash, source);
+    }
+
+    /// Replace a source's public profile (None clears it)
+    /// Callable by contract owner or the source controller
+    pub fn update_source_profile(&mut self, codename_hash: String, profile: Option<SourceProfile>) {
+        self.assert_not_paused();
+        require!(
+            self.internal_is_source_admin(&codename_hash, &env::predecessor_account_id()),
+            "Only owner or source controller"
+        );
+        if let Some(profile) = &profile {
+            Self::assert_valid_profile(profile);
+        }
+        
+        let mut source = self.sources.get(&codename_hash)
+            .expect("Source not found")
+            .clone();
+        source.profile = profile;
+        self.sources.insert(codename_hash, source);
+    }
+
+    /// Tip a source in NEAR
+    /// The platform fee is retained by the contract; the rest goes to the source's payout account
+    #[payable]
+    pub fn tip_source(&mut self, codename_hash: String) -> Promise {
+        let deposit = env::attached_deposit().as_yoctonear();
+        require!(deposit > 0, "Tip must be greater than 0");
+        
+        let mut source = self.sources.get(&codename_hash)
+            .expect("Source not found")
+            .clone();
+        require!(source.is_active, "Source is not active");
+        let payout_account = source.payout_account.clone().expect("Source has no payout account");
+        
+        let fee = deposit * self.platform_fee_bps as u128 / 10_000;
+        let payout = deposit - fee;
+        
+        self.accrued_tip_fees += fee;
+        source.total_tips_received = U128(source.total_tips_received.0 + payout);
+        self.sources.insert(codename_hash.clone(), source);
+        
+        env::log_str(&format!(
+            "Tip to {}: {} yoctoNEAR (fee {})",
+            &codename_hash[..12.min(codename_hash.len())], payout, fee
+        ));
+        
+        Promise::new(payout_account).transfer(NearToken::from_yoctonear(payout))
+    }
+
+    /// Pause or resume a source (stops new passes and posts while inactive)
+    /// Callable by contract owner or the source controller
+    pub fn set_source_active(&mut self, codename_hash: String, active: bool) {
+        require!(
+            self.internal_is_source_admin(&codename_hash, &env::predecessor_account_id()),
+            "Only owner or source controller"
+        );
+        
+        let mut source = self.sources.get(&codename_hash)
+            .expect("Source not found")
+            .clone();
+        source.is_active = active;
+        self.sources.insert(codename_hash.clone(), source);
+        
+        env::log_str(&format!(
+            "Source {}: {}",
+            if active { "resumed" } else { "paused" },
+            &codename_hash[..12.min(codename_hash.len())]
+        ));
+    }
+
+    /// Get the public key that was active for a source at a given time
+    pub fn get_source_key_at(&self, codename_hash: String, timestamp_ns: U64) -> Option<String> {
+        let source = self.sources.get(&codename_hash)?;
+        
+        // History is in rotation order; the first key retired after the timestamp was active then
+        let key = source.key_history
+            .iter()
+            .find(|(_, rotated_at)| timestamp_ns.0 < rotated_at.0)
+            .map(|(key, _)| key)
+            .unwrap_or(&source.public_key);
+        
+        Some(key.clone())
+    }
+
     /// Get source info
     pub fn get_source(&self, codename_hash: String) -> Option<Source> {
         self.sources.get(&codename_hash).cloned()
     }
 
-    /// List active sources
-    pub fn list_sources(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<Source> {
+    /// List sources, active only unless `include_inactive` is set
+    /// `category` keeps sources whose profile lists it (case-insensitive)
+    pub fn list_sources(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        include_inactive: Option<bool>,
+        category: Option<String>,
+    ) -> Vec<Source> {
         let from = from_index.unwrap_or(0);
         let limit = limit.unwrap_or(50).min(100);
+        let include_inactive = include_inactive.unwrap_or(false);
         
         self.sources
             .iter()
-            .filter(|(_, s)| s.is_active)
+            .filter(|(_, s)| include_inactive || s.is_active)
+            .filter(|(_, s)| match &category {
+                Some(category) => s.profile.as_ref().is_some_and(|p| {
+                    p.categories.iter().any(|c| c.eq_ignore_ascii_case(category))
+                }),
+                None => true,
+            })
             .skip(from as usize)
             .take(limit as usize)
             .map(|(_, s)| s.clone())
@@ -285,6 +706,7 @@ impl HumintFeed {
     // ==========================================
 
     /// Anchor a post on-chain
+    /// Rejects a CID already anchored by another post unless `allow_duplicate_cid` is set
     pub fn anchor_post(
         &mut self,
         post_id: String,
@@ -294,41 +716,227 @@ impl HumintFeed {
         is_premium: bool,
         epoch: String,
         zk_proofs: Vec<String>,
+        allow_duplicate_cid: bool,
     ) {
+        self.assert_not_paused();
         let mut source = self.sources.get(&codename_hash)
             .expect("Source not found")
             .clone();
         require!(source.is_active, "Source is not active");
         require!(self.posts.get(&post_id).is_none(), "Post already anchored");
         
-        // Validate content hash (64 char hex SHA256)
+        self.internal_anchor_post(&mut source, PostAnchorInput {
+            post_id,
+            codename_hash: codename_hash.clone(),
+            content_hash,
+            content_cid,
+            is_premium,
+            epoch,
+            zk_proofs,
+            allow_duplicate_cid,
+            proof_ids: vec![],
+        });
+        self.sources.insert(codename_hash, source);
+    }
+
+    /// Anchor a post whose proofs are checked against the intel-registry first
+    /// Each proof ID must exist and belong to `codename_hash`; the post's proof types
+    /// are taken from the registry. Any attached deposit covers the anchor's storage
+    /// and is refunded if verification fails.
+    #[payable]
+    pub fn anchor_post_verified(
+        &mut self,
+        post_id: String,
+        codename_hash: String,
+        content_hash: String,
+        content_cid: String,
+        is_premium: bool,
+        epoch: String,
+        proof_ids: Vec<String>,
+        allow_duplicate_cid: bool,
+    ) -> Promise {
+        self.assert_not_paused();
+        let registry = self.intel_registry.clone().expect("Intel registry not configured");
+        require!(!proof_ids.is_empty(), "At least one proof ID required");
         require!(
-            content_hash.len() == 64 && content_hash.chars().all(|c| c.is_ascii_hexdigit()),
-            "Invalid content hash"
+            proof_ids.len() <= MAX_VERIFIED_PROOFS,
+            format!("At most {} proofs per post", MAX_VERIFIED_PROOFS)
         );
+        let source = self.sources.get(&codename_hash).expect("Source not found");
+        require!(source.is_active, "Source is not active");
+        require!(self.posts.get(&post_id).is_none(), "Post already anchored");
         
-        let anchor = PostAnchor {
-            post_id: post_id.clone(),
+        let input = PostAnchorInput {
+            post_id,
+            codename_hash,
             content_hash,
             content_cid,
             is_premium,
             epoch,
-            created_at: U64(env::block_timestamp()),
-            source_hash: codename_hash.clone(),
-            zk_proofs,
+            zk_proofs: vec![],
+            allow_duplicate_cid,
+            proof_ids,
         };
+        // Fail before spending gas on lookups for a post that can never be anchored
+        if let Some(reason) = self.anchor_rejection(&input) {
+            env::panic_str(&reason);
+        }
         
-        self.posts.insert(post_id.clone(), anchor);
+        let mut lookups: Option<Promise> = None;
+        for proof_id in &input.proof_ids {
+            let lookup = Promise::new(registry.clone()).function_call(
+                "get_proof".to_string(),
+                serde_json::json!({ "proof_id": proof_id }).to_string().into_bytes(),
+                NearToken::from_yoctonear(0),
+                GAS_FOR_GET_PROOF,
+            );
+            lookups = Some(match lookups {
+                Some(joined) => joined.and(lookup),
+                None => lookup,
+            });
+        }
         
-        // Add to source's posts
-        if let Some(posts) = self.source_posts.get_mut(&codename_hash) {
-            posts.insert(post_id.clone());
+        let resolve_args = serde_json::json!({
+            "input": input,
+            "caller_id": env::predecessor_account_id(),
+            "deposit": U128(env::attached_deposit().as_yoctonear()),
+        });
+        
+        lookups.unwrap().then(Promise::new(env::current_account_id()).function_call(
+            "resolve_verified_anchor".to_string(),
+            resolve_args.to_string().into_bytes(),
+            NearToken::from_yoctonear(0),
+            GAS_FOR_RESOLVE_VERIFIED_ANCHOR,
+        ))
+    }
+
+    /// Finish `anchor_post_verified` once every `get_proof` lookup has returned
+    /// Returns true if the post was anchored; every rejection refunds the deposit
+    #[private]
+    pub fn resolve_verified_anchor(
+        &mut self,
+        mut input: PostAnchorInput,
+        caller_id: AccountId,
+        deposit: U128,
+    ) -> bool {
+        let mut proof_types: Vec<String> = Vec::new();
+        let mut rejection: Option<String> = None;
+        
+        for (index, proof_id) in input.proof_ids.iter().enumerate() {
+            let proof = match env::promise_result(index as u64) {
+                PromiseResult::Successful(value) => {
+                    serde_json::from_slice::<Option<serde_json::Value>>(&value).ok().flatten()
+                }
+                _ => None,
+            };
+            let proof = match proof {
+                Some(proof) => proof,
+                None => {
+                    rejection = Some(format!("Proof {} not found in registry", proof_id));
+                    break;
+                }
+            };
+            if proof["source_hash"].as_str() != Some(input.codename_hash.as_str()) {
+                rejection = Some(format!("Proof {} belongs to another source", proof_id));
+                break;
+            }
+            if let Some(proof_type) = proof["proof_type"].as_str() {
+                if !proof_types.iter().any(|t| t == proof_type) {
+                    proof_types.push(proof_type.to_string());
+                }
+            }
         }
         
-        source.post_count += 1;
-        self.sources.insert(codename_hash.clone(), source);
+        // Source or post state may have changed while the lookups were in flight
+        let source = self.sources.get(&input.codename_hash).cloned();
+        if rejection.is_none() {
+            rejection = match &source {
+                None => Some("Source not found".to_string()),
+                Some(source) if !source.is_active => Some("Source is not active".to_string()),
+                _ if self.posts.get(&input.post_id).is_some() => Some("Post already anchored".to_string()),
+                _ => None,
+            };
+        }
+        if rejection.is_none() {
+            input.zk_proofs = proof_types;
+            rejection = self.anchor_rejection(&input);
+        }
         
-        env::log_str(&format!("Post anchored: {}", &post_id[..16.min(post_id.len())]));
+        if let Some(reason) = rejection {
+            env::log_str(&format!("Verified anchor rejected: {}", reason));
+            if deposit.0 > 0 {
+                Promise::new(caller_id).transfer(NearToken::from_yoctonear(deposit.0));
+            }
+            return false;
+        }
+        
+        let mut source = source.unwrap();
+        let codename_hash = input.codename_hash.clone();
+        self.internal_anchor_post(&mut source, input);
+        self.sources.insert(codename_hash, source);
+        true
+    }
+
+    /// Anchor up to 20 posts in one call
+    /// Posts already anchored are skipped; posts that would be rejected (unknown or
+    /// inactive source, bad hash or proof type, duplicate CID) are skipped and logged.
+    /// Returns IDs of newly anchored posts
+    pub fn anchor_posts_batch(&mut self, posts: Vec<PostAnchorInput>) -> Vec<String> {
+        self.assert_not_paused();
+        require!(posts.len() <= 20, "Cannot anchor more than 20 posts at once");
+        let mut sources: HashMap<String, Source> = HashMap::new();
+        let mut anchored = Vec::new();
+        
+        for input in posts {
+            if self.posts.get(&input.post_id).is_some() {
+                continue;
+            }
+            
+            if !sources.contains_key(&input.codename_hash) {
+                if let Some(source) = self.sources.get(&input.codename_hash) {
+                    sources.insert(input.codename_hash.clone(), source.clone());
+                }
+            }
+            let rejection = match sources.get(&input.codename_hash) {
+                None => Some("Source not found".to_string()),
+                Some(source) if !source.is_active => Some("Source is not active".to_string()),
+                _ => self.anchor_rejection(&input),
+            };
+            if let Some(reason) = rejection {
+                env::log_str(&format!("Post {} skipped: {}", input.post_id, reason));
+                continue;
+            }
+            
+            let source = sources.get_mut(&input.codename_hash).unwrap();
+            let post_id = input.post_id.clone();
+            self.internal_anchor_post(source, input);
+            anchored.push(post_id);
+        }
+        
+        for (codename_hash, source) in sources {
+            self.sources.insert(codename_hash, source);
+        }
+        
+        anchored
+    }
+
+    /// Retract a post (owner or source controller)
+    /// Clears the content CID but keeps the content hash for audit
+    pub fn redact_post(&mut self, post_id: String, reason: String) {
+        let mut post = self.posts.get(&post_id).expect("Post not found").clone();
+        require!(
+            self.internal_is_source_admin(&post.source_hash, &env::predecessor_account_id()),
+            "Only owner or source controller can redact posts"
+        );
+        require!(!post.redacted, "Post already redacted");
+        require!(reason.len() <= 500, "Reason too long");
+        
+        post.redacted = true;
+        post.redaction_reason = Some(reason.clone());
+        post.content_cid = String::new();
+        self.posts.insert(post_id.clone(), post);
+        
+        env::log_str(&format!("Post redacted: {} ({})", post_id, reason));
     }
 
     /// Get post anchor
@@ -336,12 +944,63 @@ impl HumintFeed {
         self.posts.get(&post_id).cloned()
     }
 
+    /// Get the most recent posts across all sources, newest first
+    pub fn get_recent_posts(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        premium_only: Option<bool>,
+    ) -> Vec<PostAnchor> {
+        let from = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(20).min(100);
+        
+        self.post_order
+            .iter()
+            .rev()
+            .filter_map(|id| self.posts.get(id))
+            .filter(|post| premium_only.map(|p| post.is_premium == p).unwrap_or(true))
+            .skip(from as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect()
+    }
+
+    /// Get posts carrying a given ZK proof type
+    pub fn get_posts_by_proof_type(
+        &self,
+        proof_type: String,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<PostAnchor> {
+        let from = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(20).min(100);
+        
+        match self.proof_type_posts.get(&proof_type) {
+            Some(post_ids) => post_ids
+                .iter()
+                .skip(from as usize)
+                .take(limit as usize)
+                .filter_map(|id| self.posts.get(id).cloned())
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Find the first post that anchored a content CID
+    pub fn find_post_by_cid(&self, content_cid: String) -> Option<PostAnchor> {
+        let post_id = self.post_cids.get(&content_cid)?;
+        self.posts.get(post_id).cloned()
+    }
+
     /// Get posts by source
+    /// `premium_only` selects premium (true) or free (false) posts; `epoch` matches exactly
     pub fn get_source_posts(
         &self,
         codename_hash: String,
         from_index: Option<u64>,
         limit: Option<u64>,
+        premium_only: Option<bool>,
+        epoch: Option<String>,
     ) -> Vec<PostAnchor> {
         let from = from_index.unwrap_or(0);
         let limit = limit.unwrap_or(20).min(100);
@@ -349,9 +1008,12 @@ impl HumintFeed {
         match self.source_posts.get(&codename_hash) {
             Some(post_ids) => post_ids
                 .iter()
+                .filter_map(|id| self.posts.get(id))
+                .filter(|post| premium_only.map(|p| post.is_premium == p).unwrap_or(true))
+                .filter(|post| epoch.as_ref().map(|e| &post.epoch == e).unwrap_or(true))
                 .skip(from as usize)
                 .take(limit as usize)
-                .filter_map(|id| self.posts.get(id).cloned())
+                .cloned()
                 .collect(),
             None => vec![],
         }
@@ -363,6 +1025,7 @@ impl HumintFeed {
 
     /// Mint an access pass NFT (called after payment verification)
     /// Only callable by contract owner (relayer)
+    /// Pass is soulbound if the package is, or if `soulbound` is set
     #[payable]
     pub fn mint_access_pass(
         &mut self,
@@ -370,15 +1033,19 @@ impl HumintFeed {
         source_hash: String,
         package_id: String,
         amount_paid_usdc_cents: u32,
+        soulbound: Option<bool>,
     ) -> TokenId {
+        self.assert_not_paused();
         require!(
             env::predecessor_account_id() == self.owner_id,
             "Only owner can mint access passes"
         );
+        let initial_storage = env::storage_usage();
         
         let mut source = self.sources.get(&source_hash)
             .expect("Source not found")
             .clone();
+        require!(source.is_active, "Source is not accepting new subscribers");
         
         // Find package
         let package = source.packages.iter()
@@ -386,23 +1053,32 @@ impl HumintFeed {
             .expect("Package not found")
             .clone();
         
+        let minted_key = (source_hash.clone(), package_id.clone());
+        let minted = self.package_minted.get(&minted_key).copied().unwrap_or(0);
+        if let Some(max_supply) = package.max_supply {
+            require!(minted < max_supply, "Package sold out");
+        }
+        self.package_minted.insert(minted_key, minted + 1);
+        
         let token_id = format!("ap-{}", self.next_token_id);
         self.next_token_id += 1;
         
         let now = env::block_timestamp();
         let duration_ns = package.duration_days as u64 * 24 * 60 * 60 * 1_000_000_000;
-        let expires_at = if package.duration_days == 0 { 0 } else { now + duration_ns };
+        let expires_at = if package.is_lifetime { 0 } else { now + duration_ns };
         
         // Create token
         let token = Token {
             owner_id: receiver_id.clone(),
+            next_approval_id: 0,
         };
         
         // Standard NFT metadata
+        let source_label = source.display_name.clone().unwrap_or_else(|| source_hash[..8].to_string());
         let token_metadata = TokenMetadata {
-            title: Some(format!("{} - {}", source_hash[..8].to_string(), package.name)),
+            title: Some(format!("{} - {}", source_label, package.name)),
             description: Some(format!("Access pass for {} content", package.name)),
-            media: None,
+            media: package.media.clone(),
             media_hash: None,
             copies: Some(1),
             issued_at: Some(now.to_string()),
@@ -421,6 +1097,7 @@ impl HumintFeed {
             started_at: U64(now),
             expires_at: U64(expires_at),
             amount_paid_usdc_cents,
+            soulbound: package.soulbound || soulbound.unwrap_or(false),
         };
         
         // Store token
@@ -438,62 +1115,226 @@ impl HumintFeed {
             new_set.insert(token_id.clone());
             self.tokens_per_owner.insert(receiver_id.clone(), new_set);
         }
+        self.internal_index_pass(&receiver_id, &source_hash, &token_id);
+        
+        // Charge the receiver's storage balance for the new pass
+        self.internal_charge_mint_storage(&receiver_id, &source_hash, initial_storage);
         
         // Update source subscriber count
         source.subscriber_count += 1;
         self.sources.insert(source_hash, source);
+        self.internal_accrue_fee(amount_paid_usdc_cents);
         
-        env::log_str(&format!("Access pass minted: {} for {}", token_id, receiver_id));
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"nep171\",\"version\":\"1.0.0\",\"event\":\"nft_mint\",\"data\":[{{\"owner_id\":\"{}\",\"token_ids\":[\"{}\"]}}]}}",
+            receiver_id, token_id
+        ));
         
         token_id
     }
 
-    /// Check if account has valid access to a source
-    pub fn has_access(&self, account_id: AccountId, source_hash: String) -> bool {
-        let now = env::block_timestamp();
-        
-        // Get all tokens owned by account
-        if let Some(tokens) = self.tokens_per_owner.get(&account_id) {
-            for token_id in tokens.iter() {
-                if let Some(pass_data) = self.access_pass_data.get(token_id) {
-                    // Check source match
-                    if pass_data.source_hash != source_hash {
-                        continue;
-                    }
-                    // Check expiry (0 = lifetime)
-                    if pass_data.expires_at.0 > 0 && pass_data.expires_at.0 < now {
-                        continue;
-                    }
-                    // Valid access!
-                    return true;
-                }
-            }
-        }
-        
-        false
+    /// Passes still available under a capped package (None if uncapped or unknown)
+    pub fn remaining_supply(&self, codename_hash: String, package_id: String) -> Option<u32> {
+        let source = self.sources.get(&codename_hash)?;
+        let max_supply = source.packages.iter().find(|p| p.id == package_id)?.max_supply?;
+        let minted = self.package_minted.get(&(codename_hash, package_id)).copied().unwrap_or(0);
+        Some(max_supply.saturating_sub(minted))
     }
 
-    /// Check if account has access to a specific post (considers exclusions)
-    pub fn has_post_access(&self, account_id: AccountId, post_id: String) -> bool {
-        let post = match self.posts.get(&post_id) {
-            Some(p) => p,
-            None => return false,
-        };
-        
-        // Free posts always accessible
-        if !post.is_premium {
-            return true;
-        }
-        
-        // Check exclusions
-        if let Some(exclusions) = self.post_exclusions.get(&post_id) {
-            if exclusions.contains(&account_id) {
-                return false; // Explicitly excluded
+    /// Mint an access pass and return the token JSON with its pass data
+    /// Same rules as `mint_access_pass`; saves the relayer a follow-up `nft_token` view
+    #[payable]
+    pub fn mint_access_pass_detailed(
+        &mut self,
+        receiver_id: AccountId,
+        source_hash: String,
+        package_id: String,
+        amount_paid_usdc_cents: u32,
+        soulbound: Option<bool>,
+    ) -> serde_json::Value {
+        let token_id = self.mint_access_pass(receiver_id, source_hash, package_id, amount_paid_usdc_cents, soulbound);
+        serde_json::json!({
+            "token": self.nft_token(token_id.clone()),
+            "access_pass": self.get_access_pass(token_id),
+        })
+    }
+
+    /// Burn an access pass (token owner or contract owner)
+    /// The freed storage is credited to the holder's storage balance
+    pub fn burn_access_pass(&mut self, token_id: TokenId) {
+        self.assert_not_paused();
+        let caller = env::predecessor_account_id();
+        let owner_id = self.tokens_by_id.get(&token_id).expect("Token not found").owner_id.clone();
+        require!(
+            caller == owner_id || caller == self.owner_id,
+            "Only token owner or contract owner"
+        );
+        self.internal_burn(&token_id);
+    }
+
+    /// Burn the given passes that are past expiry and grace (owner only)
+    /// Unknown, lifetime and still-valid passes are skipped; returns the burned IDs
+    pub fn burn_expired(&mut self, token_ids: Vec<TokenId>) -> Vec<TokenId> {
+        self.assert_not_paused();
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only owner"
+        );
+        require!(token_ids.len() <= 100, "Cannot burn more than 100 passes at once");
+        
+        let expired: Vec<TokenId> = token_ids
+            .into_iter()
+            .filter(|token_id| {
+                self.access_pass_data
+                    .get(token_id)
+                    .map(|p| !self.internal_pass_grants_access(p))
+                    .unwrap_or(false)
+            })
+            .collect();
+        for token_id in &expired {
+            self.internal_burn(token_id);
+        }
+        expired
+    }
+
+    /// Extend an existing access pass by its package duration
+    /// Only callable by contract owner (relayer)
+    pub fn renew_access_pass(&mut self, token_id: TokenId, amount_paid_usdc_cents: u32) {
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only owner can renew access passes"
+        );
+        
+        let mut pass_data = self.access_pass_data.get(&token_id)
+            .expect("Access pass not found")
+            .clone();
+        require!(pass_data.expires_at.0 > 0, "Lifetime pass cannot be renewed");
+        
+        let source = self.sources.get(&pass_data.source_hash).expect("Source not found");
+        let package = source.packages.iter()
+            .find(|p| p.id == pass_data.package_id)
+            .expect("Package not found");
+        
+        // Extend from whichever is later so early renewals don't lose time
+        let now = env::block_timestamp();
+        let duration_ns = package.duration_days as u64 * 24 * 60 * 60 * 1_000_000_000;
+        let expires_at = now.max(pass_data.expires_at.0) + duration_ns;
+        
+        pass_data.expires_at = U64(expires_at);
+        pass_data.amount_paid_usdc_cents += amount_paid_usdc_cents;
+        self.internal_accrue_fee(amount_paid_usdc_cents);
+        
+        let owner_id = self.tokens_by_id.get(&token_id).expect("Token not found").owner_id.clone();
+        self.internal_index_pass(&owner_id, &pass_data.source_hash, &token_id);
+        self.access_pass_data.insert(token_id.clone(), pass_data);
+        
+        if let Some(metadata) = self.token_metadata_by_id.get_mut(&token_id) {
+            metadata.expires_at = Some(expires_at.to_string());
+            metadata.updated_at = Some(now.to_string());
+        }
+        
+        env::log_str(&format!("Access pass renewed: {} until {}", token_id, expires_at));
+    }
+
+    /// Check if account has valid access to a source
+    /// Passes keep granting access for the grace period after they expire
+    /// Accounts excluded from the source never have access
+    pub fn has_access(&self, account_id: AccountId, source_hash: String) -> bool {
+        if self.is_source_excluded(source_hash.clone(), account_id.clone()) {
+            return false;
+        }
+        
+        // Only look at this account's passes for this source
+        match self.source_passes.get(&(account_id, source_hash)) {
+            Some(tokens) => tokens.iter().any(|token_id| {
+                self.access_pass_data
+                    .get(token_id)
+                    .map(|pass_data| self.internal_pass_grants_access(pass_data))
+                    .unwrap_or(false)
+            }),
+            None => false,
+        }
+    }
+
+    /// List accounts currently granted access to a source (owner or source controller only)
+    /// Holders whose passes have all expired, or who are excluded, are skipped
+    /// Reads the caller, so it must be called as a transaction rather than a view
+    pub fn get_source_subscribers(
+        &self,
+        codename_hash: String,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<AccountId> {
+        require!(
+            self.internal_is_source_admin(&codename_hash, &env::predecessor_account_id()),
+            "Only owner or source controller"
+        );
+        let from = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(50).min(100);
+        
+        match self.source_subscribers.get(&codename_hash) {
+            Some(subscribers) => subscribers
+                .iter()
+                .filter(|account_id| self.has_access((*account_id).clone(), codename_hash.clone()))
+                .skip(from as usize)
+                .take(limit as usize)
+                .cloned()
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Check if account has access to a specific post (considers exclusions)
+    pub fn has_post_access(&self, account_id: AccountId, post_id: String) -> bool {
+        self.can_read_post(account_id, post_id).allowed
+    }
+
+    /// Decide whether an account can read a post, with the reason for the outcome
+    /// Combines the premium flag, redaction, exclusions and pass expiry
+    pub fn can_read_post(&self, account_id: AccountId, post_id: String) -> ReadDecision {
+        let decision = |allowed: bool, reason: &str| ReadDecision {
+            allowed,
+            reason: reason.to_string(),
+        };
+
+        let post = match self.posts.get(&post_id) {
+            Some(p) => p,
+            None => return decision(false, "not_found"),
+        };
+        
+        // Free posts always accessible
+        if !post.is_premium {
+            return decision(true, "free");
+        }
+        
+        // Redacted premium content is no longer served
+        if post.redacted {
+            return decision(false, "redacted");
+        }
+        
+        // Check exclusions
+        if let Some(exclusions) = self.post_exclusions.get(&post_id) {
+            if exclusions.contains(&account_id) {
+                return decision(false, "excluded");
             }
         }
+        if self.is_source_excluded(post.source_hash.clone(), account_id.clone()) {
+            return decision(false, "excluded");
+        }
         
         // Check subscription
-        self.has_access(account_id, post.source_hash.clone())
+        if self.has_access(account_id.clone(), post.source_hash.clone()) {
+            return decision(true, "subscribed");
+        }
+        let held_pass = self.source_passes
+            .get(&(account_id, post.source_hash.clone()))
+            .map(|tokens| !tokens.is_empty())
+            .unwrap_or(false);
+        if held_pass {
+            decision(false, "expired")
+        } else {
+            decision(false, "no_subscription")
+        }
     }
 
     /// Get access pass data for a token
@@ -501,6 +1342,15 @@ impl HumintFeed {
         self.access_pass_data.get(&token_id).cloned()
     }
 
+    /// Whether a pass still grants access, grace period included
+    /// Lifetime passes always do; missing tokens never
+    pub fn is_pass_valid(&self, token_id: TokenId) -> bool {
+        self.access_pass_data
+            .get(&token_id)
+            .map(|data| self.internal_pass_grants_access(data))
+            .unwrap_or(false)
+    }
+
     /// Get all access passes owned by an account
     pub fn get_access_passes(&self, account_id: AccountId) -> Vec<(TokenId, AccessPassData)> {
         match self.tokens_per_owner.get(&account_id) {
@@ -516,44 +1366,74 @@ impl HumintFeed {
         }
     }
 
+    /// Get access passes owned by an account that still grant access (lifetime passes included)
+    pub fn get_active_access_passes(&self, account_id: AccountId) -> Vec<(TokenId, AccessPassData)> {
+        self.get_access_passes(account_id)
+            .into_iter()
+            .filter(|(_, data)| self.internal_pass_grants_access(data))
+            .collect()
+    }
+
+    /// Get access passes owned by an account that are past expiry and grace
+    pub fn get_expired_access_passes(&self, account_id: AccountId) -> Vec<(TokenId, AccessPassData)> {
+        self.get_access_passes(account_id)
+            .into_iter()
+            .filter(|(_, data)| !self.internal_pass_grants_access(data))
+            .collect()
+    }
+
     // ==========================================
     // EXCLUSIONS (per-post access revocation)
     // ==========================================
 
     /// Add exclusion to a post (source only)
     pub fn add_exclusion(&mut self, post_id: String, excluded_account: AccountId) {
+        self.add_exclusions(post_id, vec![excluded_account]);
+    }
+
+    /// Exclude up to 100 accounts from a post in one call (source only)
+    pub fn add_exclusions(&mut self, post_id: String, accounts: Vec<AccountId>) {
         // Verify post exists
-        require!(self.posts.get(&post_id).is_some(), "Post not found");
+        let source_hash = self.posts.get(&post_id).expect("Post not found").source_hash.clone();
         
-        // TODO: Verify caller is the source (would need source -> account mapping)
-        // For now, only owner can add exclusions
         require!(
-            env::predecessor_account_id() == self.owner_id,
-            "Only owner can add exclusions"
+            self.internal_is_source_admin(&source_hash, &env::predecessor_account_id()),
+            "Only owner or source controller can add exclusions"
         );
+        require!(accounts.len() <= 100, "Cannot change more than 100 exclusions at once");
         
-        if let Some(exclusions) = self.post_exclusions.get_mut(&post_id) {
-            exclusions.insert(excluded_account.clone());
-        } else {
-            let mut exclusions = UnorderedSet::new(StorageKey::PostExclusionsInner {
+        if self.post_exclusions.get(&post_id).is_none() {
+            let exclusions = UnorderedSet::new(StorageKey::PostExclusionsInner {
                 post_id_hash: env::sha256(post_id.as_bytes()).to_vec(),
             });
-            exclusions.insert(excluded_account.clone());
             self.post_exclusions.insert(post_id.clone(), exclusions);
         }
-        
-        env::log_str(&format!("Exclusion added: {} from {}", excluded_account, post_id));
+        let exclusions = self.post_exclusions.get_mut(&post_id).unwrap();
+        for account in accounts {
+            env::log_str(&format!("Exclusion added: {} from {}", account, post_id));
+            exclusions.insert(account);
+        }
     }
 
-    /// Remove exclusion from a post
+    /// Remove exclusion from a post (source only)
     pub fn remove_exclusion(&mut self, post_id: String, excluded_account: AccountId) {
+        self.remove_exclusions(post_id, vec![excluded_account]);
+    }
+
+    /// Lift exclusions for up to 100 accounts in one call (source only)
+    pub fn remove_exclusions(&mut self, post_id: String, accounts: Vec<AccountId>) {
+        let source_hash = self.posts.get(&post_id).expect("Post not found").source_hash.clone();
+        
         require!(
-            env::predecessor_account_id() == self.owner_id,
-            "Only owner can remove exclusions"
+            self.internal_is_source_admin(&source_hash, &env::predecessor_account_id()),
+            "Only owner or source controller can remove exclusions"
         );
+        require!(accounts.len() <= 100, "Cannot change more than 100 exclusions at once");
         
         if let Some(exclusions) = self.post_exclusions.get_mut(&post_id) {
-            exclusions.remove(&excluded_account);
+            for account in &accounts {
+                exclusions.remove(account);
+            }
         }
     }
 
@@ -565,6 +1445,92 @@ impl HumintFeed {
         }
     }
 
+    /// Bar an account from all of a source's premium posts (owner or source controller)
+    pub fn add_source_exclusion(&mut self, codename_hash: String, account_id: AccountId) {
+        require!(self.sources.get(&codename_hash).is_some(), "Source not found");
+        require!(
+            self.internal_is_source_admin(&codename_hash, &env::predecessor_account_id()),
+            "Only owner or source controller can add exclusions"
+        );
+        
+        if let Some(exclusions) = self.source_exclusions.get_mut(&codename_hash) {
+            exclusions.insert(account_id.clone());
+        } else {
+            let mut exclusions = UnorderedSet::new(StorageKey::SourceExclusionsInner {
+                source_hash: env::sha256(codename_hash.as_bytes()).to_vec(),
+            });
+            exclusions.insert(account_id.clone());
+            self.source_exclusions.insert(codename_hash.clone(), exclusions);
+        }
+        
+        env::log_str(&format!("Source exclusion added: {} from {}", account_id, &codename_hash[..12]));
+    }
+
+    /// Lift a source-wide exclusion (owner or source controller)
+    pub fn remove_source_exclusion(&mut self, codename_hash: String, account_id: AccountId) {
+        require!(
+            self.internal_is_source_admin(&codename_hash, &env::predecessor_account_id()),
+            "Only owner or source controller can remove exclusions"
+        );
+        
+        if let Some(exclusions) = self.source_exclusions.get_mut(&codename_hash) {
+            exclusions.remove(&account_id);
+        }
+    }
+
+    /// Check if account is excluded from an entire source
+    pub fn is_source_excluded(&self, codename_hash: String, account_id: AccountId) -> bool {
+        self.source_exclusions
+            .get(&codename_hash)
+            .map(|exclusions| exclusions.contains(&account_id))
+            .unwrap_or(false)
+    }
+
+    // ==========================================
+    // READ RECEIPTS
+    // ==========================================
+
+    /// Record that the caller read a post
+    /// Caller must have access to the post; repeat reads are counted once
+    pub fn record_read(&mut self, post_id: String) {
+        self.assert_not_paused();
+        let reader = env::predecessor_account_id();
+        require!(
+            self.has_post_access(reader.clone(), post_id.clone()),
+            "No access to post"
+        );
+
+        let newly_read = if let Some(readers) = self.post_readers.get_mut(&post_id) {
+            readers.insert(reader)
+        } else {
+            let mut readers = UnorderedSet::new(StorageKey::PostReadersInner {
+                post_id_hash: env::sha256(post_id.as_bytes()).to_vec(),
+            });
+            readers.insert(reader);
+            self.post_readers.insert(post_id.clone(), readers);
+            true
+        };
+
+        if newly_read {
+            if let Some(post) = self.posts.get_mut(&post_id) {
+                post.read_count += 1;
+            }
+        }
+    }
+
+    /// Number of distinct accounts that recorded reading a post
+    pub fn get_read_count(&self, post_id: String) -> u64 {
+        self.posts.get(&post_id).map(|p| p.read_count).unwrap_or(0)
+    }
+
+    /// Whether an account recorded reading a post
+    pub fn has_read(&self, post_id: String, account_id: AccountId) -> bool {
+        self.post_readers
+            .get(&post_id)
+            .map(|readers| readers.contains(&account_id))
+            .unwrap_or(false)
+    }
+
     // ==========================================
     // NFT STANDARD (NEP-171 Core)
     // ==========================================
@@ -575,41 +1541,109 @@ impl HumintFeed {
         &mut self,
         receiver_id: AccountId,
         token_id: TokenId,
+        approval_id: Option<u64>,
         memo: Option<String>,
     ) {
+        self.assert_not_paused();
         require!(
             env::attached_deposit() >= NearToken::from_yoctonear(1),
             "Requires 1 yoctoNEAR"
         );
         
         let sender_id = env::predecessor_account_id();
-        let token = self.tokens_by_id.get(&token_id).expect("Token not found");
+        self.internal_transfer(&sender_id, &receiver_id, &token_id, approval_id, memo);
+    }
+
+    /// Transfer NFT and notify the receiver (NEP-171)
+    /// The receiver's `nft_on_transfer` returns `true` to hand the token back
+    #[payable]
+    pub fn nft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> Promise {
+        self.assert_not_paused();
+        require!(
+            env::attached_deposit() >= NearToken::from_yoctonear(1),
+            "Requires 1 yoctoNEAR"
+        );
         
-        require!(token.owner_id == sender_id, "Not token owner");
+        let sender_id = env::predecessor_account_id();
+        // Kept so a reverted transfer hands the owner back their approvals
+        let approved_account_ids = self.internal_approvals(&token_id);
+        let previous = self.internal_transfer(&sender_id, &receiver_id, &token_id, approval_id, memo);
         
-        // Remove from sender
-        if let Some(sender_tokens) = self.tokens_per_owner.get_mut(&sender_id) {
-            sender_tokens.remove(&token_id);
-        }
+        let on_transfer_args = serde_json::json!({
+            "sender_id": sender_id,
+            "previous_owner_id": previous.owner_id,
+            "token_id": token_id,
+            "msg": msg,
+        });
+        let resolve_args = serde_json::json!({
+            "previous_owner_id": previous.owner_id,
+            "receiver_id": receiver_id,
+            "token_id": token_id,
+            "approved_account_ids": approved_account_ids,
+        });
         
-        // Add to receiver
-        if let Some(receiver_tokens) = self.tokens_per_owner.get_mut(&receiver_id) {
-            receiver_tokens.insert(token_id.clone());
-        } else {
-            let mut new_set = UnorderedSet::new(StorageKey::TokenPerOwnerInner {
-                account_id_hash: env::sha256(receiver_id.as_bytes()).to_vec(),
-            });
-            new_set.insert(token_id.clone());
-            self.tokens_per_owner.insert(receiver_id.clone(), new_set);
+        Promise::new(receiver_id)
+            .function_call(
+                "nft_on_transfer".to_string(),
+                on_transfer_args.to_string().into_bytes(),
+                NearToken::from_yoctonear(0),
+                GAS_FOR_NFT_ON_TRANSFER,
+            )
+            .then(Promise::new(env::current_account_id()).function_call(
+                "nft_resolve_transfer".to_string(),
+                resolve_args.to_string().into_bytes(),
+                NearToken::from_yoctonear(0),
+                GAS_FOR_RESOLVE_TRANSFER,
+            ))
+    }
+
+    /// Resolve an `nft_transfer_call` (NEP-171)
+    /// Returns true if the token stayed with the receiver; a returned token gets
+    /// back the approvals it had before the transfer
+    #[private]
+    pub fn nft_resolve_transfer(
+        &mut self,
+        previous_owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approved_account_ids: Option<HashMap<AccountId, u64>>,
+    ) -> bool {
+        // Failed or malformed receiver calls are treated as a request to return the token
+        let must_revert = match env::promise_result(0) {
+            PromiseResult::Successful(value) => serde_json::from_slice::<bool>(&value).unwrap_or(true),
+            _ => true,
+        };
+        if !must_revert {
+            return true;
         }
         
-        // Update token owner
-        let new_token = Token { owner_id: receiver_id.clone() };
-        self.tokens_by_id.insert(token_id.clone(), new_token);
+        // Receiver may have already moved or burned the token
+        let token = match self.tokens_by_id.get(&token_id) {
+            Some(t) if t.owner_id == receiver_id => t.clone(),
+            _ => return true,
+        };
         
-        if let Some(m) = memo {
-            env::log_str(&format!("Transfer {} to {}: {}", token_id, receiver_id, m));
+        self.internal_set_owner(&token_id, &receiver_id, &previous_owner_id, token.next_approval_id);
+        let approved_account_ids = approved_account_ids.unwrap_or_default();
+        if !approved_account_ids.is_empty() {
+            let mut approvals = Self::new_approvals(&token_id, token.next_approval_id);
+            approvals.extend(approved_account_ids);
+            self.approved_accounts.insert(token_id.clone(), approvals);
         }
+        
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"nep171\",\"version\":\"1.0.0\",\"event\":\"nft_transfer\",\"data\":[{{\"old_owner_id\":\"{}\",\"new_owner_id\":\"{}\",\"token_ids\":[\"{}\"]}}]}}",
+            receiver_id, previous_owner_id, token_id
+        ));
+        
+        false
     }
 
     /// Get token info (NEP-171)
@@ -623,71 +1657,2617 @@ impl HumintFeed {
             "metadata": {
                 "title": metadata.title,
                 "description": metadata.description,
+                "media": metadata.media,
                 "issued_at": metadata.issued_at,
                 "expires_at": metadata.expires_at,
-            }
+            },
+            "is_valid": self.is_pass_valid(token_id.clone()),
         }))
     }
 
     // ==========================================
-    // NFT ENUMERATION (NEP-181)
+    // NFT PAYOUTS (NEP-199)
     // ==========================================
 
-    /// Get total supply
-    pub fn nft_total_supply(&self) -> U128 {
-        U128(self.tokens_by_id.len() as u128)
-    }
-
-    /// Get tokens for owner
-    pub fn nft_tokens_for_owner(
-        &self,
-        account_id: AccountId,
-        from_index: Option<U128>,
-        limit: Option<u64>,
-    ) -> Vec<serde_json::Value> {
-        let from = from_index.map(|i| i.0 as usize).unwrap_or(0);
-        let limit = limit.unwrap_or(50) as usize;
+    /// Split `balance` between the pass's source (royalty) and the current owner
+    /// Sources without a payout account or royalty receive nothing
+    pub fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: u32) -> Payout {
+        let token = self.tokens_by_id.get(&token_id).expect("Token not found");
+        let pass_data = self.access_pass_data.get(&token_id).expect("Access pass not found");
+        let source = self.sources.get(&pass_data.source_hash).expect("Source not found");
         
-        match self.tokens_per_owner.get(&account_id) {
-            Some(tokens) => tokens
-                .iter()
-                .skip(from)
-                .take(limit)
-                .filter_map(|token_id| self.nft_token(token_id.clone()))
-                .collect(),
-            None => vec![],
+        let royalty_amount = balance.0 * source.royalty_bps as u128 / 10_000;
+        let mut payout = HashMap::new();
+        match &source.payout_account {
+            Some(payout_account) if royalty_amount > 0 && payout_account != &token.owner_id => {
+                payout.insert(payout_account.clone(), U128(royalty_amount));
+                payout.insert(token.owner_id.clone(), U128(balance.0 - royalty_amount));
+            }
+            _ => {
+                payout.insert(token.owner_id.clone(), balance);
+            }
         }
+        
+        require!(payout.len() as u32 <= max_len_payout, "Payout exceeds max_len_payout");
+        
+        Payout { payout }
     }
 
-    // ==========================================
-    // NFT METADATA (NEP-177)
-    // ==========================================
-
-    /// Get contract metadata
-    pub fn nft_metadata(&self) -> Option<NFTContractMetadata> {
-        self.metadata.get().as_ref().cloned()
+    /// Transfer a pass and return the payout the marketplace should honor (NEP-199)
+    #[payable]
+    pub fn nft_transfer_payout(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        balance: U128,
+        max_len_payout: u32,
+    ) -> Payout {
+        self.assert_not_paused();
+        require!(
+            env::attached_deposit() >= NearToken::from_yoctonear(1),
+            "Requires 1 yoctoNEAR"
+        );
+        
+        // Computed against the owner being paid, before ownership moves
+        let payout = self.nft_payout(token_id.clone(), balance, max_len_payout);
+        
+        let sender_id = env::predecessor_account_id();
+        self.internal_transfer(&sender_id, &receiver_id, &token_id, approval_id, memo);
+        
+        payout
     }
 
     // ==========================================
-    // ADMIN
+    // NFT APPROVAL MANAGEMENT (NEP-178)
     // ==========================================
 
-    /// Update platform fee
-    pub fn set_platform_fee(&mut self, new_fee_bps: u16) {
+    /// Approve an account to transfer a token on the owner's behalf (NEP-178)
+    /// If `msg` is given, `nft_on_approve` is called on the approved account
+    #[payable]
+    pub fn nft_approve(
+        &mut self,
+        token_id: TokenId,
+        account_id: AccountId,
+        msg: Option<String>,
+    ) -> Option<Promise> {
         require!(
-            env::predecessor_account_id() == self.owner_id,
-            "Only owner"
+            env::attached_deposit() >= NearToken::from_yoctonear(1),
+            "Requires 1 yoctoNEAR"
         );
-        require!(new_fee_bps <= 1000, "Fee cannot exceed 10%");
-        self.platform_fee_bps = new_fee_bps;
+        
+        let owner_id = env::predecessor_account_id();
+        let token = self.tokens_by_id.get_mut(&token_id).expect("Token not found");
+        require!(token.owner_id == owner_id, "Not token owner");
+        
+        let approval_id = token.next_approval_id;
+        token.next_approval_id += 1;
+        
+        if let Some(approvals) = self.approved_accounts.get_mut(&token_id) {
+            approvals.insert(account_id.clone(), approval_id);
+        } else {
+            let mut approvals = Self::new_approvals(&token_id, approval_id);
+            approvals.insert(account_id.clone(), approval_id);
+            self.approved_accounts.insert(token_id.clone(), approvals);
+        }
+        
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"nep178\",\"version\":\"1.0.0\",\"event\":\"nft_approve\",\"data\":[{{\"token_id\":\"{}\",\"account_id\":\"{}\",\"approval_id\":{}}}]}}",
+            token_id, account_id, approval_id
+        ));
+        
+        msg.map(|msg| {
+            let args = serde_json::json!({
+                "token_id": token_id,
+                "owner_id": owner_id,
+                "approval_id": approval_id,
+                "msg": msg,
+            });
+            Promise::new(account_id).function_call(
+                "nft_on_approve".to_string(),
+                args.to_string().into_bytes(),
+                NearToken::from_yoctonear(0),
+                GAS_FOR_NFT_ON_APPROVE,
+            )
+        })
     }
 
-    /// Get contract stats
-    pub fn get_stats(&self) -> serde_json::Value {
-        serde_json::json!({
-            "sources": self.sources.len(),
+    /// Revoke a single account's approval (NEP-178)
+    #[payable]
+    pub fn nft_revoke(&mut self, token_id: TokenId, account_id: AccountId) {
+        require!(
+            env::attached_deposit() >= NearToken::from_yoctonear(1),
+            "Requires 1 yoctoNEAR"
+        );
+        
+        let token = self.tokens_by_id.get(&token_id).expect("Token not found");
+        require!(token.owner_id == env::predecessor_account_id(), "Not token owner");
+        
+        if let Some(approvals) = self.approved_accounts.get_mut(&token_id) {
+            approvals.remove(&account_id);
+        }
+    }
+
+    /// Revoke all approvals for a token (NEP-178)
+    #[payable]
+    pub fn nft_revoke_all(&mut self, token_id: TokenId) {
+        require!(
+            env::attached_deposit() >= NearToken::from_yoctonear(1),
+            "Requires 1 yoctoNEAR"
+        );
+        
+        let token = self.tokens_by_id.get(&token_id).expect("Token not found");
+        require!(token.owner_id == env::predecessor_account_id(), "Not token owner");
+        
+        self.internal_clear_approvals(&token_id);
+    }
+
+    /// Check if an account is approved for a token (NEP-178)
+    /// If `approval_id` is given, it must match the current approval
+    pub fn nft_is_approved(
+        &self,
+        token_id: TokenId,
+        approved_account_id: AccountId,
+        approval_id: Option<u64>,
+    ) -> bool {
+        let current = match self.approved_accounts
+            .get(&token_id)
+            .and_then(|approvals| approvals.get(&approved_account_id))
+        {
+            Some(id) => *id,
+            None => return false,
+        };
+        
+        approval_id.map(|id| id == current).unwrap_or(true)
+    }
+
+    // ==========================================
+    // NFT ENUMERATION (NEP-181)
+    // ==========================================
+
+    /// Get total supply
+    pub fn nft_total_supply(&self) -> U128 {
+        U128(self.tokens_by_id.len() as u128)
+    }
+
+    /// Get number of tokens owned by an account
+    pub fn nft_supply_for_owner(&self, account_id: AccountId) -> U128 {
+        self.tokens_per_owner
+            .get(&account_id)
+            .map(|set| U128(set.len() as u128))
+            .unwrap_or(U128(0))
+    }
+
+    /// Get all tokens (paginated)
+    pub fn nft_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<serde_json::Value> {
+        let from = from_index.map(|i| i.0 as usize).unwrap_or(0);
+        let limit = limit.unwrap_or(50).min(100) as usize;
+        
+        self.tokens_by_id
+            .keys()
+            .skip(from)
+            .take(limit)
+            .filter_map(|token_id| self.nft_token(token_id.clone()))
+            .collect()
+    }
+
+    /// Get tokens for owner
+    pub fn nft_tokens_for_owner(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<serde_json::Value> {
+        let from = from_index.map(|i| i.0 as usize).unwrap_or(0);
+        let limit = limit.unwrap_or(50) as usize;
+        
+        match self.tokens_per_owner.get(&account_id) {
+            Some(tokens) => tokens
+                .iter()
+                .skip(from)
+                .take(limit)
+                .filter_map(|token_id| self.nft_token(token_id.clone()))
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    // ==========================================
+    // NFT METADATA (NEP-177)
+    // ==========================================
+
+    /// Get contract metadata
+    pub fn nft_metadata(&self) -> Option<NFTContractMetadata> {
+        self.metadata.get().as_ref().cloned()
+    }
+
+    // ==========================================
+    // STORAGE MANAGEMENT (NEP-145)
+    // ==========================================
+
+    /// Deposit NEAR to cover storage for an account's access passes
+    /// With `registration_only`, the account is registered and the deposit refunded
+    #[payable]
+    pub fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let deposit = env::attached_deposit();
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let mut balance = self.storage_balances
+            .get(&account_id)
+            .cloned()
+            .unwrap_or(StorageBalance { total: U128(0), available: U128(0) });
+        
+        // No minimum balance is required, so registration needs no deposit
+        if registration_only.unwrap_or(false) {
+            if !deposit.is_zero() {
+                Promise::new(env::predecessor_account_id()).transfer(deposit);
+            }
+        } else {
+            balance.total = U128(balance.total.0 + deposit.as_yoctonear());
+            balance.available = U128(balance.available.0 + deposit.as_yoctonear());
+        }
+        
+        self.storage_balances.insert(account_id, balance.clone());
+        balance
+    }
+
+    /// Withdraw unused storage deposit (all available if `amount` is omitted)
+    #[payable]
+    pub fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        require!(
+            env::attached_deposit() >= NearToken::from_yoctonear(1),
+            "Requires 1 yoctoNEAR"
+        );
+        
+        let account_id = env::predecessor_account_id();
+        let mut balance = self.storage_balances
+            .get(&account_id)
+            .cloned()
+            .expect("Account not registered");
+        
+        let amount = amount.map(|a| a.0).unwrap_or(balance.available.0);
+        require!(amount <= balance.available.0, "Amount exceeds available balance");
+        
+        balance.total = U128(balance.total.0 - amount);
+        balance.available = U128(balance.available.0 - amount);
+        self.storage_balances.insert(account_id.clone(), balance.clone());
+        
+        if amount > 0 {
+            Promise::new(account_id).transfer(NearToken::from_yoctonear(amount));
+        }
+        balance
+    }
+
+    /// Get storage balance for an account (NEP-145)
+    pub fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.storage_balances.get(&account_id).cloned()
+    }
+
+    /// Get storage balance bounds (NEP-145)
+    pub fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds { min: U128(0), max: None }
+    }
+
+    // ==========================================
+    // ADMIN
+    // ==========================================
+
+    /// Update platform fee
+    pub fn set_platform_fee(&mut self, new_fee_bps: u16) {
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only owner"
+        );
+        require!(new_fee_bps <= 1000, "Fee cannot exceed 10%");
+        self.platform_fee_bps = new_fee_bps;
+    }
+
+    /// Set the intel-registry used by `anchor_post_verified` (None disables it)
+    pub fn set_intel_registry(&mut self, intel_registry: Option<AccountId>) {
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only owner"
+        );
+        self.intel_registry = intel_registry;
+    }
+
+    /// Set how long expired passes keep granting access (owner only)
+    pub fn set_grace_period(&mut self, grace_period_ns: U64) {
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only owner"
+        );
+        self.grace_period_ns = grace_period_ns.0;
+    }
+
+    /// Get the access grace period after pass expiry (ns)
+    pub fn get_grace_period(&self) -> U64 {
+        U64(self.grace_period_ns)
+    }
+
+    /// Pause or resume all mutating operations (views stay available)
+    pub fn set_paused(&mut self, paused: bool) {
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only owner"
+        );
+        self.paused = paused;
+        env::log_str(if paused { "Contract paused" } else { "Contract unpaused" });
+    }
+
+    /// Whether mutating operations are currently paused
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Set the treasury account that withdrawn platform fees are sent to (owner only)
+    pub fn set_fee_recipient(&mut self, fee_recipient: AccountId) {
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only owner"
+        );
+        env::log_str(&format!("Fee recipient set to {}", fee_recipient));
+        self.fee_recipient = fee_recipient;
+    }
+
+    /// Get the account that receives withdrawn platform fees
+    pub fn get_fee_recipient(&self) -> AccountId {
+        self.fee_recipient.clone()
+    }
+
+    /// Get the configured intel-registry
+    pub fn get_intel_registry(&self) -> Option<AccountId> {
+        self.intel_registry.clone()
+    }
+
+    /// Get platform fees accrued since the last reset (USDC cents)
+    pub fn get_accrued_fees(&self) -> U128 {
+        U128(self.accrued_fees_usdc_cents)
+    }
+
+    /// Zero the fee accumulator once the relayer has settled USDC off-chain
+    /// Returns the amount that was cleared
+    pub fn reset_accrued_fees(&mut self) -> U128 {
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only owner"
+        );
+        let cleared = self.accrued_fees_usdc_cents;
+        self.accrued_fees_usdc_cents = 0;
+        env::log_str(&format!("Accrued fees reset: {} USDC cents", cleared));
+        U128(cleared)
+    }
+
+    /// Get the platform's share of tips held by the contract (yoctoNEAR)
+    pub fn get_accrued_tip_fees(&self) -> U128 {
+        U128(self.accrued_tip_fees)
+    }
+
+    /// Send accrued tip fees to the fee recipient
+    pub fn withdraw_tip_fees(&mut self) -> Promise {
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only owner"
+        );
+        require!(self.accrued_tip_fees > 0, "No tip fees to withdraw");
+        
+        let amount = self.accrued_tip_fees;
+        self.accrued_tip_fees = 0;
+        Promise::new(self.fee_recipient.clone()).transfer(NearToken::from_yoctonear(amount))
+    }
+
+    /// Get contract stats
+    pub fn get_stats(&self) -> serde_json::Value {
+        serde_json::json!({
+            "sources": self.sources.len(),
             "access_passes": self.tokens_by_id.len(),
             "platform_fee_bps": self.platform_fee_bps,
         })
     }
+
+    // ==========================================
+    // INTERNAL
+    // ==========================================
+
+    /// Move a token to a new owner, clearing approvals
+    /// Sender must own the token or hold an approval for it
+    /// Returns the token as it was before the transfer
+    fn internal_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_id: &TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) -> Token {
+        let token = self.tokens_by_id.get(token_id).expect("Token not found").clone();
+        
+        if let Some(pass_data) = self.access_pass_data.get(token_id) {
+            require!(!pass_data.soulbound, "Access pass is soulbound");
+        }
+        
+        if &token.owner_id != sender_id {
+            let current = self.approved_accounts
+                .get(token_id)
+                .and_then(|approvals| approvals.get(sender_id))
+                .copied();
+            require!(current.is_some(), "Not token owner or approved");
+            if let Some(id) = approval_id {
+                require!(current == Some(id), "Approval ID mismatch");
+            }
+        }
+        
+        self.internal_set_owner(token_id, &token.owner_id, receiver_id, token.next_approval_id);
+        
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"nep171\",\"version\":\"1.0.0\",\"event\":\"nft_transfer\",\"data\":[{{\"old_owner_id\":\"{}\",\"new_owner_id\":\"{}\",\"token_ids\":[\"{}\"]}}]}}",
+            token.owner_id, receiver_id, token_id
+        ));
+        
+        if let Some(m) = memo {
+            env::log_str(&format!("Transfer {} to {}: {}", token_id, receiver_id, m));
+        }
+        
+        token
+    }
+
+    /// Reassign a token between owner sets and clear its approvals
+    fn internal_set_owner(
+        &mut self,
+        token_id: &TokenId,
+        from: &AccountId,
+        to: &AccountId,
+        next_approval_id: u64,
+    ) {
+        // Remove from current owner
+        if let Some(from_tokens) = self.tokens_per_owner.get_mut(from) {
+            from_tokens.remove(token_id);
+        }
+        
+        // Add to new owner
+        if let Some(to_tokens) = self.tokens_per_owner.get_mut(to) {
+            to_tokens.insert(token_id.clone());
+        } else {
+            let mut new_set = UnorderedSet::new(StorageKey::TokenPerOwnerInner {
+                account_id_hash: env::sha256(to.as_bytes()).to_vec(),
+            });
+            new_set.insert(token_id.clone());
+            self.tokens_per_owner.insert(to.clone(), new_set);
+        }
+        
+        // Move between (owner, source) buckets
+        let source_hash = self.access_pass_data.get(token_id).map(|p| p.source_hash.clone());
+        if let Some(source_hash) = source_hash {
+            self.internal_unindex_pass(from, &source_hash, token_id);
+            self.internal_index_pass(to, &source_hash, token_id);
+        }
+        
+        // Any change of owner invalidates prior approvals
+        self.internal_clear_approvals(token_id);
+        
+        self.tokens_by_id.insert(token_id.clone(), Token {
+            owner_id: to.clone(),
+            next_approval_id,
+        });
+    }
+
+    /// Why `internal_anchor_post` would refuse `input`, if it would
+    fn anchor_rejection(&self, input: &PostAnchorInput) -> Option<String> {
+        // Validate content hash (64 char hex SHA256)
+        if input.content_hash.len() != 64 || !input.content_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Some("Invalid content hash".to_string());
+        }
+        
+        if input.zk_proofs.iter().any(|t| !ZK_PROOF_TYPES.contains(&t.as_str())) {
+            return Some("Unknown proof type".to_string());
+        }
+        
+        match self.post_cids.get(&input.content_cid) {
+            Some(existing) if !input.allow_duplicate_cid => {
+                Some(format!("CID already anchored under {}", existing))
+            }
+            _ => None,
+        }
+    }
+
+    /// Store a post anchor and count it against its source
+    /// Caller persists the updated source
+    fn internal_anchor_post(&mut self, source: &mut Source, input: PostAnchorInput) {
+        if let Some(reason) = self.anchor_rejection(&input) {
+            env::panic_str(&reason);
+        }
+        
+        let post_id = input.post_id;
+        if self.post_cids.get(&input.content_cid).is_none() {
+            self.post_cids.insert(input.content_cid.clone(), post_id.clone());
+        }
+        
+        let anchor = PostAnchor {
+            post_id: post_id.clone(),
+            content_hash: input.content_hash,
+            content_cid: input.content_cid,
+            is_premium: input.is_premium,
+            epoch: input.epoch,
+            created_at: U64(env::block_timestamp()),
+            source_hash: input.codename_hash.clone(),
+            zk_proofs: input.zk_proofs.clone(),
+            redacted: false,
+            redaction_reason: None,
+            proof_ids: input.proof_ids,
+            read_count: 0,
+        };
+        
+        self.posts.insert(post_id.clone(), anchor);
+        self.post_order.push(post_id.clone());
+        
+        // Index by proof type
+        for proof_type in &input.zk_proofs {
+            if let Some(posts) = self.proof_type_posts.get_mut(proof_type) {
+                posts.insert(post_id.clone());
+            } else {
+                let mut posts = UnorderedSet::new(StorageKey::ProofTypePostsInner {
+                    proof_type_hash: env::sha256(proof_type.as_bytes()).to_vec(),
+                });
+                posts.insert(post_id.clone());
+                self.proof_type_posts.insert(proof_type.clone(), posts);
+            }
+        }
+        
+        // Add to source's posts
+        if let Some(posts) = self.source_posts.get_mut(&input.codename_hash) {
+            posts.insert(post_id.clone());
+        }
+        
+        source.post_count += 1;
+        
+        env::log_str(&format!("Post anchored: {}", &post_id[..16.min(post_id.len())]));
+    }
+
+    /// Charge the bytes written by a mint against the receiver's storage balance
+    fn internal_charge_mint_storage(&mut self, receiver_id: &AccountId, source_hash: &str, initial_storage: u64) {
+        self.internal_flush_pass_storage(receiver_id, source_hash);
+        
+        let bytes = env::storage_usage().saturating_sub(initial_storage);
+        let cost = env::storage_byte_cost().as_yoctonear() * bytes as u128;
+        
+        let mut balance = self.storage_balances
+            .get(receiver_id)
+            .cloned()
+            .expect("Receiver has no storage deposit");
+        require!(balance.available.0 >= cost, "Insufficient storage balance");
+        balance.available = U128(balance.available.0 - cost);
+        self.storage_balances.insert(receiver_id.clone(), balance);
+    }
+
+    /// Credit the bytes freed by a burn back to the holder's storage balance
+    fn internal_refund_burn_storage(&mut self, owner_id: &AccountId, source_hash: &str, initial_storage: u64) {
+        self.internal_flush_pass_storage(owner_id, source_hash);
+        
+        let bytes = initial_storage.saturating_sub(env::storage_usage());
+        let refund = env::storage_byte_cost().as_yoctonear() * bytes as u128;
+        
+        if let Some(balance) = self.storage_balances.get_mut(owner_id) {
+            balance.available = U128(balance.available.0.saturating_add(refund).min(balance.total.0));
+        }
+    }
+
+    /// Write out cached pass collections so `env::storage_usage` is accurate
+    fn internal_flush_pass_storage(&mut self, owner_id: &AccountId, source_hash: &str) {
+        // Collections cache writes until the call ends, so flush before measuring
+        self.tokens_by_id.flush();
+        self.token_metadata_by_id.flush();
+        self.access_pass_data.flush();
+        if let Some(tokens) = self.tokens_per_owner.get_mut(owner_id) {
+            tokens.flush();
+        }
+        self.tokens_per_owner.flush();
+        if let Some(passes) = self.source_passes.get_mut(&(owner_id.clone(), source_hash.to_string())) {
+            passes.flush();
+        }
+        self.source_passes.flush();
+        if let Some(subscribers) = self.source_subscribers.get_mut(source_hash) {
+            subscribers.flush();
+        }
+        self.source_subscribers.flush();
+        self.package_minted.flush();
+        self.approved_accounts.flush();
+        self.sources.flush();
+    }
+
+    /// Empty approvals map for a token
+    /// Prefix includes the first approval ID so a map created after a clear
+    /// never sees entries left behind by an earlier one
+    fn new_approvals(token_id: &TokenId, approval_id: u64) -> UnorderedMap<AccountId, u64> {
+        UnorderedMap::new(StorageKey::ApprovedAccountsInner {
+            token_id_hash: env::sha256(format!("{}:{}", token_id, approval_id).as_bytes()).to_vec(),
+        })
+    }
+
+    /// Current approvals on a token
+    fn internal_approvals(&self, token_id: &TokenId) -> HashMap<AccountId, u64> {
+        self.approved_accounts
+            .get(token_id)
+            .map(|approvals| approvals.iter().map(|(account_id, id)| (account_id.clone(), *id)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Drop every approval on a token, entries included
+    fn internal_clear_approvals(&mut self, token_id: &TokenId) {
+        if let Some(mut approvals) = self.approved_accounts.remove(token_id) {
+            approvals.clear();
+        }
+    }
+
+    /// Whether a pass grants access now: lifetime (expiry 0), or not yet past expiry plus grace
+    fn internal_pass_grants_access(&self, pass_data: &AccessPassData) -> bool {
+        pass_data.expires_at.0 == 0
+            || pass_data.expires_at.0.saturating_add(self.grace_period_ns) >= env::block_timestamp()
+    }
+
+    /// Remove a pass and everything indexed under it
+    /// Passes still granting access no longer count toward the source's subscribers
+    fn internal_burn(&mut self, token_id: &TokenId) {
+        let initial_storage = env::storage_usage();
+        let owner_id = self.tokens_by_id.remove(token_id).expect("Token not found").owner_id;
+        self.token_metadata_by_id.remove(token_id);
+        self.internal_clear_approvals(token_id);
+        if let Some(tokens) = self.tokens_per_owner.get_mut(&owner_id) {
+            tokens.remove(token_id);
+        }
+        
+        let pass_data = self.access_pass_data.remove(token_id).expect("Access pass not found");
+        let source_hash = pass_data.source_hash;
+        self.internal_unindex_pass(&owner_id, &source_hash, token_id);
+        
+        // Burned passes free up capped supply
+        if let Some(minted) = self.package_minted.get_mut(&(source_hash.clone(), pass_data.package_id)) {
+            *minted = minted.saturating_sub(1);
+        }
+        
+        if self.internal_pass_grants_access(&pass_data) {
+            if let Some(source) = self.sources.get_mut(&source_hash) {
+                source.subscriber_count = source.subscriber_count.saturating_sub(1);
+            }
+        }
+        
+        self.internal_refund_burn_storage(&owner_id, &source_hash, initial_storage);
+        
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"nep171\",\"version\":\"1.0.0\",\"event\":\"nft_burn\",\"data\":[{{\"owner_id\":\"{}\",\"token_ids\":[\"{}\"]}}]}}",
+            owner_id, token_id
+        ));
+    }
+
+    /// Record the platform's share of a USDC payment
+    fn internal_accrue_fee(&mut self, amount_paid_usdc_cents: u32) {
+        self.accrued_fees_usdc_cents += amount_paid_usdc_cents as u128 * self.platform_fee_bps as u128 / 10_000;
+    }
+
+    /// Panic if the owner has paused the contract
+    fn assert_not_paused(&self) {
+        require!(!self.paused, "Contract is paused");
+    }
+
+    /// Panic unless every profile field is within its length limit
+    fn assert_valid_profile(profile: &SourceProfile) {
+        require!(
+            profile.bio.chars().count() <= MAX_PROFILE_BIO_CHARS,
+            "Bio cannot exceed 280 characters"
+        );
+        if let Some(cid) = &profile.avatar_cid {
+            require!(!cid.is_empty() && cid.len() <= MAX_AVATAR_CID_BYTES, "Avatar CID must be 1-128 bytes");
+        }
+        require!(
+            profile.categories.len() <= MAX_PROFILE_CATEGORIES,
+            "Cannot have more than 10 categories"
+        );
+        for category in &profile.categories {
+            require!(!category.is_empty() && category.len() <= MAX_CATEGORY_BYTES, "Category must be 1-32 bytes");
+        }
+    }
+
+    /// Whether an account may manage a source (contract owner or its controller)
+    fn internal_is_source_admin(&self, source_hash: &str, account_id: &AccountId) -> bool {
+        if account_id == &self.owner_id {
+            return true;
+        }
+        self.sources
+            .get(source_hash)
+            .and_then(|s| s.controller.as_ref())
+            .map(|controller| controller == account_id)
+            .unwrap_or(false)
+    }
+
+    /// Drop a pass from its (owner, source) bucket, and the owner from the
+    /// source's subscribers once they hold no other pass for it
+    fn internal_unindex_pass(&mut self, owner_id: &AccountId, source_hash: &str, token_id: &TokenId) {
+        let mut holds_none = false;
+        if let Some(passes) = self.source_passes.get_mut(&(owner_id.clone(), source_hash.to_string())) {
+            passes.remove(token_id);
+            holds_none = passes.is_empty();
+        }
+        if holds_none {
+            if let Some(subscribers) = self.source_subscribers.get_mut(source_hash) {
+                subscribers.remove(owner_id);
+            }
+        }
+    }
+
+    /// Add a pass to its (owner, source) bucket
+    fn internal_index_pass(&mut self, owner_id: &AccountId, source_hash: &str, token_id: &TokenId) {
+        let key = (owner_id.clone(), source_hash.to_string());
+        if let Some(passes) = self.source_passes.get_mut(&key) {
+            passes.insert(token_id.clone());
+        } else {
+            let mut passes = UnorderedSet::new(StorageKey::SourcePassesInner {
+                key_hash: env::sha256(format!("{}:{}", owner_id, source_hash).as_bytes()).to_vec(),
+            });
+            passes.insert(token_id.clone());
+            self.source_passes.insert(key, passes);
+        }
+        
+        if let Some(subscribers) = self.source_subscribers.get_mut(source_hash) {
+            subscribers.insert(owner_id.clone());
+        } else {
+            let mut subscribers = UnorderedSet::new(StorageKey::SourceSubscribersInner {
+                source_hash: env::sha256(source_hash.as_bytes()).to_vec(),
+            });
+            subscribers.insert(owner_id.clone());
+            self.source_subscribers.insert(source_hash.to_string(), subscribers);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::mock::MockAction;
+    use near_sdk::test_utils::{get_created_receipts, get_logs, VMContextBuilder};
+    use near_sdk::{testing_env, RuntimeFeesConfig};
+
+    fn contract_account() -> AccountId {
+        "humint.near".parse().unwrap()
+    }
+
+    fn get_context(predecessor: AccountId) -> VMContextBuilder {
+        let mut builder = VMContextBuilder::new();
+        builder.current_account_id(contract_account());
+        builder.predecessor_account_id(predecessor);
+        builder.attached_deposit(NearToken::from_yoctonear(1));
+        builder.block_timestamp(1_000_000_000);
+        builder
+    }
+
+    fn source_hash() -> String {
+        "a".repeat(64)
+    }
+
+    fn monthly_package() -> Package {
+        Package {
+            id: "monthly".to_string(),
+            name: "Monthly".to_string(),
+            price_usdc_cents: 500,
+            duration_days: 30,
+            description: "30 days of access".to_string(),
+            soulbound: false,
+            media: None,
+            is_lifetime: false,
+            max_supply: None,
+        }
+    }
+
+    /// Prepay storage for accounts, restoring the owner's context afterwards
+    fn deposit_storage(contract: &mut HumintFeed, accounts: &[&str]) {
+        let mut context = get_context("owner.near".parse().unwrap());
+        testing_env!(context.attached_deposit(NearToken::from_near(1)).build());
+        for account in accounts {
+            contract.storage_deposit(Some(account.parse().unwrap()), None);
+        }
+        testing_env!(get_context("owner.near".parse().unwrap()).build());
+    }
+
+    /// Contract with one source and an access pass minted to alice
+    fn setup() -> (HumintFeed, TokenId) {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = HumintFeed::new(owner, 250);
+        deposit_storage(&mut contract, &["alice.near", "bob.near"]);
+        contract.register_source(
+            source_hash(),
+            "pubkey".to_string(),
+            vec![monthly_package()],
+            Some("source.near".parse().unwrap()),
+            None,
+        );
+        let token_id = contract.mint_access_pass(alice, source_hash(), "monthly".to_string(), 500, None);
+
+        (contract, token_id)
+    }
+
+    fn resolve_with(contract: &mut HumintFeed, token_id: &TokenId, receiver_result: &[u8]) -> bool {
+        resolve_with_approvals(contract, token_id, receiver_result, None)
+    }
+
+    fn resolve_with_approvals(
+        contract: &mut HumintFeed,
+        token_id: &TokenId,
+        receiver_result: &[u8],
+        approved_account_ids: Option<HashMap<AccountId, u64>>,
+    ) -> bool {
+        testing_env!(
+            get_context(contract_account()).build(),
+            near_sdk::test_vm_config(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Successful(receiver_result.to_vec())]
+        );
+        contract.nft_resolve_transfer(
+            "alice.near".parse().unwrap(),
+            "escrow.near".parse().unwrap(),
+            token_id.clone(),
+            approved_account_ids,
+        )
+    }
+
+    #[test]
+    fn test_transfer_call_kept_by_receiver() {
+        let (mut contract, token_id) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let escrow: AccountId = "escrow.near".parse().unwrap();
+
+        testing_env!(get_context(alice.clone()).build());
+        contract.nft_transfer_call(escrow.clone(), token_id.clone(), None, None, "list".to_string());
+
+        assert!(resolve_with(&mut contract, &token_id, b"false"));
+        assert_eq!(contract.tokens_by_id.get(&token_id).unwrap().owner_id, escrow);
+        assert!(contract.tokens_per_owner.get(&escrow).unwrap().contains(&token_id));
+        assert!(!contract.tokens_per_owner.get(&alice).unwrap().contains(&token_id));
+    }
+
+    #[test]
+    fn test_transfer_call_returned_by_receiver() {
+        let (mut contract, token_id) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let escrow: AccountId = "escrow.near".parse().unwrap();
+
+        testing_env!(get_context(alice.clone()).build());
+        contract.nft_transfer_call(escrow.clone(), token_id.clone(), None, None, "list".to_string());
+
+        assert!(!resolve_with(&mut contract, &token_id, b"true"));
+        assert_eq!(contract.tokens_by_id.get(&token_id).unwrap().owner_id, alice);
+        assert!(contract.tokens_per_owner.get(&alice).unwrap().contains(&token_id));
+        assert!(!contract.tokens_per_owner.get(&escrow).unwrap().contains(&token_id));
+    }
+
+    #[test]
+    fn test_returned_transfer_call_restores_approvals() {
+        let (mut contract, token_id) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let escrow: AccountId = "escrow.near".parse().unwrap();
+        let market: AccountId = "market.near".parse().unwrap();
+
+        testing_env!(get_context(alice).build());
+        contract.nft_approve(token_id.clone(), market.clone(), None);
+        contract.nft_transfer_call(escrow.clone(), token_id.clone(), None, None, "list".to_string());
+        assert!(!contract.nft_is_approved(token_id.clone(), market.clone(), None));
+
+        // The receiver's own approvals do not survive the return
+        testing_env!(get_context(escrow.clone()).build());
+        contract.nft_approve(token_id.clone(), "carol.near".parse().unwrap(), None);
+
+        let approvals = HashMap::from([(market.clone(), 0)]);
+        assert!(!resolve_with_approvals(&mut contract, &token_id, b"true", Some(approvals)));
+        assert!(contract.nft_is_approved(token_id.clone(), market, Some(0)));
+        assert!(!contract.nft_is_approved(token_id, "carol.near".parse().unwrap(), None));
+    }
+
+    #[test]
+    fn test_kept_transfer_call_drops_approvals() {
+        let (mut contract, token_id) = setup();
+        let market: AccountId = "market.near".parse().unwrap();
+
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        contract.nft_approve(token_id.clone(), market.clone(), None);
+        contract.nft_transfer_call("escrow.near".parse().unwrap(), token_id.clone(), None, None, "list".to_string());
+
+        let approvals = HashMap::from([(market.clone(), 0)]);
+        assert!(resolve_with_approvals(&mut contract, &token_id, b"false", Some(approvals)));
+        assert!(!contract.nft_is_approved(token_id, market, None));
+    }
+
+    #[test]
+    fn test_transferable_pass() {
+        let (mut contract, token_id) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+
+        assert!(!contract.get_access_pass(token_id.clone()).unwrap().soulbound);
+
+        testing_env!(get_context(alice).build());
+        contract.nft_transfer(bob.clone(), token_id.clone(), None, None);
+
+        assert_eq!(contract.tokens_by_id.get(&token_id).unwrap().owner_id, bob);
+    }
+
+    /// Pass from `setup` with market.near approved by alice under approval ID 0
+    fn setup_approved() -> (HumintFeed, TokenId) {
+        let (mut contract, token_id) = setup();
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        contract.nft_approve(token_id.clone(), "market.near".parse().unwrap(), None);
+        (contract, token_id)
+    }
+
+    #[test]
+    fn test_nft_approve() {
+        let (mut contract, token_id) = setup_approved();
+        let market: AccountId = "market.near".parse().unwrap();
+
+        assert!(contract.nft_is_approved(token_id.clone(), market.clone(), None));
+        assert!(contract.nft_is_approved(token_id.clone(), market.clone(), Some(0)));
+        assert!(!contract.nft_is_approved(token_id.clone(), market, Some(1)));
+        assert!(!contract.nft_is_approved(token_id.clone(), "carol.near".parse().unwrap(), None));
+
+        // Each approval gets the next ID
+        contract.nft_approve(token_id.clone(), "carol.near".parse().unwrap(), None);
+        assert!(contract.nft_is_approved(token_id, "carol.near".parse().unwrap(), Some(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not token owner")]
+    fn test_nft_approve_requires_owner() {
+        let (mut contract, token_id) = setup();
+        testing_env!(get_context("bob.near".parse().unwrap()).build());
+        contract.nft_approve(token_id, "bob.near".parse().unwrap(), None);
+    }
+
+    #[test]
+    fn test_approved_account_transfers_with_approval_id() {
+        let (mut contract, token_id) = setup_approved();
+        let bob: AccountId = "bob.near".parse().unwrap();
+
+        testing_env!(get_context("market.near".parse().unwrap()).build());
+        contract.nft_transfer(bob.clone(), token_id.clone(), Some(0), None);
+
+        assert_eq!(contract.tokens_by_id.get(&token_id).unwrap().owner_id, bob);
+    }
+
+    #[test]
+    #[should_panic(expected = "Approval ID mismatch")]
+    fn test_transfer_with_stale_approval_id() {
+        let (mut contract, token_id) = setup_approved();
+        testing_env!(get_context("market.near".parse().unwrap()).build());
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id, Some(1), None);
+    }
+
+    #[test]
+    fn test_nft_revoke() {
+        let (mut contract, token_id) = setup_approved();
+        let market: AccountId = "market.near".parse().unwrap();
+        let carol: AccountId = "carol.near".parse().unwrap();
+        contract.nft_approve(token_id.clone(), carol.clone(), None);
+
+        contract.nft_revoke(token_id.clone(), market.clone());
+        assert!(!contract.nft_is_approved(token_id.clone(), market, None));
+        assert!(contract.nft_is_approved(token_id.clone(), carol.clone(), Some(1)));
+
+        contract.nft_revoke_all(token_id.clone());
+        assert!(!contract.nft_is_approved(token_id, carol, None));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not token owner or approved")]
+    fn test_revoked_account_cannot_transfer() {
+        let (mut contract, token_id) = setup_approved();
+        contract.nft_revoke(token_id.clone(), "market.near".parse().unwrap());
+
+        testing_env!(get_context("market.near".parse().unwrap()).build());
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id, None, None);
+    }
+
+    #[test]
+    fn test_transfer_clears_approvals() {
+        let (mut contract, token_id) = setup_approved();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        let market: AccountId = "market.near".parse().unwrap();
+        contract.nft_transfer(bob.clone(), token_id.clone(), None, None);
+        assert!(!contract.nft_is_approved(token_id.clone(), market.clone(), None));
+
+        // The new owner's approvals start from a clean map
+        testing_env!(get_context(bob).build());
+        contract.nft_approve(token_id.clone(), "carol.near".parse().unwrap(), None);
+        assert!(contract.nft_is_approved(token_id.clone(), "carol.near".parse().unwrap(), Some(1)));
+        assert!(!contract.nft_is_approved(token_id, market, None));
+    }
+
+    #[test]
+    #[should_panic(expected = "Access pass is soulbound")]
+    fn test_soulbound_package_blocks_transfer() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = HumintFeed::new(owner, 250);
+        deposit_storage(&mut contract, &["alice.near"]);
+        let package = Package { soulbound: true, ..monthly_package() };
+        contract.register_source(source_hash(), "pubkey".to_string(), vec![package], None, None);
+        let token_id = contract.mint_access_pass(alice.clone(), source_hash(), "monthly".to_string(), 500, None);
+        assert!(contract.get_access_pass(token_id.clone()).unwrap().soulbound);
+
+        testing_env!(get_context(alice).build());
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Access pass is soulbound")]
+    fn test_soulbound_mint_blocks_transfer_call() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = HumintFeed::new(owner, 250);
+        deposit_storage(&mut contract, &["alice.near"]);
+        contract.register_source(source_hash(), "pubkey".to_string(), vec![monthly_package()], None, None);
+        let token_id = contract.mint_access_pass(alice.clone(), source_hash(), "monthly".to_string(), 500, Some(true));
+
+        testing_env!(get_context(alice).build());
+        contract.nft_transfer_call("escrow.near".parse().unwrap(), token_id, None, None, "list".to_string());
+    }
+
+    #[test]
+    fn test_has_access_uses_source_bucket() {
+        let (mut contract, token_a) = setup();
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        let source_b = "b".repeat(64);
+
+        testing_env!(get_context(owner).build());
+        contract.register_source(source_b.clone(), "pubkey".to_string(), vec![monthly_package()], None, None);
+        let token_b = contract.mint_access_pass(alice.clone(), source_b.clone(), "monthly".to_string(), 500, None);
+
+        // Each bucket holds only that source's pass
+        let bucket_a = contract.source_passes.get(&(alice.clone(), source_hash())).unwrap();
+        assert_eq!(bucket_a.len(), 1);
+        assert!(bucket_a.contains(&token_a));
+        let bucket_b = contract.source_passes.get(&(alice.clone(), source_b.clone())).unwrap();
+        assert_eq!(bucket_b.len(), 1);
+        assert!(bucket_b.contains(&token_b));
+
+        assert!(contract.has_access(alice.clone(), source_hash()));
+        assert!(contract.has_access(alice.clone(), source_b.clone()));
+        assert!(!contract.has_access(bob.clone(), source_hash()));
+
+        // Transfer moves the pass between buckets
+        testing_env!(get_context(alice.clone()).build());
+        contract.nft_transfer(bob.clone(), token_a.clone(), None, None);
+
+        assert!(contract.source_passes.get(&(alice.clone(), source_hash())).unwrap().is_empty());
+        assert!(contract.source_passes.get(&(bob.clone(), source_hash())).unwrap().contains(&token_a));
+        assert!(!contract.has_access(alice.clone(), source_hash()));
+        assert!(contract.has_access(bob, source_hash()));
+        assert!(contract.has_access(alice, source_b));
+    }
+
+    fn anchor_test_post(contract: &mut HumintFeed, post_id: &str) {
+        contract.anchor_post(
+            post_id.to_string(),
+            source_hash(),
+            "c".repeat(64),
+            format!("bafy-{}", post_id),
+            true,
+            "2026-02".to_string(),
+            vec![],
+            false,
+        );
+    }
+
+    #[test]
+    fn test_source_controller_manages_exclusions() {
+        let (mut contract, _) = setup();
+        let controller: AccountId = "source.near".parse().unwrap();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        anchor_test_post(&mut contract, "post-1");
+
+        testing_env!(get_context(controller).build());
+        contract.add_exclusion("post-1".to_string(), alice.clone());
+        assert!(contract.is_excluded("post-1".to_string(), alice.clone()));
+        assert!(!contract.has_post_access(alice.clone(), "post-1".to_string()));
+
+        contract.remove_exclusion("post-1".to_string(), alice.clone());
+        assert!(!contract.is_excluded("post-1".to_string(), alice));
+    }
+
+    #[test]
+    fn test_bulk_exclusions() {
+        let (mut contract, _) = setup();
+        anchor_test_post(&mut contract, "post-1");
+        let accounts: Vec<AccountId> = (0..10)
+            .map(|i| format!("leaked-{}.near", i).parse().unwrap())
+            .collect();
+
+        testing_env!(get_context("source.near".parse().unwrap()).build());
+        contract.add_exclusions("post-1".to_string(), accounts.clone());
+        for account in &accounts {
+            assert!(contract.is_excluded("post-1".to_string(), account.clone()));
+        }
+
+        contract.remove_exclusions("post-1".to_string(), accounts[..5].to_vec());
+        for (i, account) in accounts.iter().enumerate() {
+            assert_eq!(contract.is_excluded("post-1".to_string(), account.clone()), i >= 5);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner or source controller can remove exclusions")]
+    fn test_non_controller_cannot_remove_exclusions() {
+        let (mut contract, _) = setup();
+        anchor_test_post(&mut contract, "post-1");
+
+        testing_env!(get_context("mallory.near".parse().unwrap()).build());
+        contract.remove_exclusions("post-1".to_string(), vec!["alice.near".parse().unwrap()]);
+    }
+
+    #[test]
+    fn test_source_exclusion_blocks_all_premium_posts() {
+        let (mut contract, _) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        anchor_test_post(&mut contract, "post-1");
+        anchor_test_post(&mut contract, "post-2");
+
+        testing_env!(get_context("source.near".parse().unwrap()).build());
+        contract.add_source_exclusion(source_hash(), alice.clone());
+        assert!(contract.is_source_excluded(source_hash(), alice.clone()));
+        assert!(!contract.has_access(alice.clone(), source_hash()));
+        for post_id in ["post-1", "post-2"] {
+            assert!(!contract.has_post_access(alice.clone(), post_id.to_string()));
+            assert_eq!(contract.can_read_post(alice.clone(), post_id.to_string()).reason, "excluded");
+        }
+
+        contract.remove_source_exclusion(source_hash(), alice.clone());
+        assert!(contract.has_post_access(alice, "post-1".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner or source controller can add exclusions")]
+    fn test_non_controller_cannot_add_source_exclusion() {
+        let (mut contract, _) = setup();
+        testing_env!(get_context("mallory.near".parse().unwrap()).build());
+        contract.add_source_exclusion(source_hash(), "alice.near".parse().unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner or source controller can add exclusions")]
+    fn test_non_controller_cannot_add_exclusion() {
+        let (mut contract, _) = setup();
+        anchor_test_post(&mut contract, "post-1");
+
+        testing_env!(get_context("mallory.near".parse().unwrap()).build());
+        contract.add_exclusion("post-1".to_string(), "alice.near".parse().unwrap());
+    }
+
+    fn batch_input(post_id: &str) -> PostAnchorInput {
+        PostAnchorInput {
+            post_id: post_id.to_string(),
+            codename_hash: source_hash(),
+            content_hash: "c".repeat(64),
+            content_cid: format!("bafy-{}", post_id),
+            is_premium: true,
+            epoch: "2026-02".to_string(),
+            zk_proofs: vec![],
+            allow_duplicate_cid: false,
+            proof_ids: vec![],
+        }
+    }
+
+    #[test]
+    fn test_anchor_posts_batch_skips_duplicates() {
+        let (mut contract, _) = setup();
+        anchor_test_post(&mut contract, "post-1");
+
+        let anchored = contract.anchor_posts_batch(vec![
+            batch_input("post-1"),
+            batch_input("post-2"),
+            batch_input("post-3"),
+            batch_input("post-2"),
+        ]);
+
+        assert_eq!(anchored, vec!["post-2".to_string(), "post-3".to_string()]);
+        assert_eq!(contract.get_source(source_hash()).unwrap().post_count, 3);
+        assert_eq!(contract.get_source_posts(source_hash(), None, None, None, None).len(), 3);
+    }
+
+    #[test]
+    fn test_anchor_posts_batch_skips_rejected_posts() {
+        let (mut contract, _) = setup();
+        let other_source = PostAnchorInput { codename_hash: "b".repeat(64), ..batch_input("post-2") };
+        let bad_hash = PostAnchorInput { content_hash: "xyz".to_string(), ..batch_input("post-3") };
+        let duplicate_cid = PostAnchorInput { content_cid: "bafy-post-1".to_string(), ..batch_input("post-4") };
+
+        let anchored = contract.anchor_posts_batch(vec![
+            batch_input("post-1"),
+            other_source,
+            bad_hash,
+            duplicate_cid,
+            batch_input("post-5"),
+        ]);
+
+        assert_eq!(anchored, vec!["post-1".to_string(), "post-5".to_string()]);
+        assert_eq!(contract.get_source(source_hash()).unwrap().post_count, 2);
+        assert_eq!(
+            get_logs().into_iter().filter(|log| log.contains("skipped")).collect::<Vec<_>>(),
+            vec![
+                "Post post-2 skipped: Source not found".to_string(),
+                "Post post-3 skipped: Invalid content hash".to_string(),
+                "Post post-4 skipped: CID already anchored under post-1".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_anchor_posts_batch_skips_inactive_source() {
+        let (mut contract, _) = setup();
+        contract.set_source_active(source_hash(), false);
+
+        assert!(contract.anchor_posts_batch(vec![batch_input("post-1")]).is_empty());
+        assert!(contract.get_post("post-1".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot anchor more than 20 posts at once")]
+    fn test_anchor_posts_batch_size_cap() {
+        let (mut contract, _) = setup();
+        let posts = (0..21).map(|i| batch_input(&format!("post-{}", i))).collect();
+        contract.anchor_posts_batch(posts);
+    }
+
+    #[test]
+    fn test_redacted_post_denies_access() {
+        let (mut contract, _) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        anchor_test_post(&mut contract, "post-1");
+        assert!(contract.has_post_access(alice.clone(), "post-1".to_string()));
+
+        testing_env!(get_context("source.near".parse().unwrap()).build());
+        contract.redact_post("post-1".to_string(), "Operation burned".to_string());
+
+        let post = contract.get_post("post-1".to_string()).unwrap();
+        assert!(post.redacted);
+        assert_eq!(post.content_cid, "");
+        assert_eq!(post.content_hash, "c".repeat(64));
+        assert_eq!(contract.get_source_posts(source_hash(), None, None, None, None)[0].content_cid, "");
+        assert!(!contract.has_post_access(alice, "post-1".to_string()));
+    }
+
+    fn read_decision(allowed: bool, reason: &str) -> ReadDecision {
+        ReadDecision { allowed, reason: reason.to_string() }
+    }
+
+    #[test]
+    fn test_can_read_post_reasons() {
+        let (mut contract, _) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        anchor_test_post(&mut contract, "post-1");
+        contract.anchor_post(
+            "post-free".to_string(),
+            source_hash(),
+            "c".repeat(64),
+            "bafy-post-free".to_string(),
+            false,
+            "2026-02".to_string(),
+            vec![],
+            false,
+        );
+
+        assert_eq!(contract.can_read_post(bob.clone(), "post-free".to_string()), read_decision(true, "free"));
+        assert_eq!(contract.can_read_post(alice.clone(), "post-1".to_string()), read_decision(true, "subscribed"));
+        assert_eq!(contract.can_read_post(bob, "post-1".to_string()), read_decision(false, "no_subscription"));
+        assert_eq!(contract.can_read_post(alice.clone(), "missing".to_string()), read_decision(false, "not_found"));
+
+        contract.add_exclusion("post-1".to_string(), alice.clone());
+        assert_eq!(contract.can_read_post(alice.clone(), "post-1".to_string()), read_decision(false, "excluded"));
+        assert!(!contract.has_post_access(alice, "post-1".to_string()));
+    }
+
+    #[test]
+    fn test_can_read_post_expired_pass() {
+        let (mut contract, _) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        anchor_test_post(&mut contract, "post-1");
+
+        let day_ns: u64 = 24 * 60 * 60 * 1_000_000_000;
+        let mut context = get_context("owner.near".parse().unwrap());
+        testing_env!(context.block_timestamp(1_000_000_000 + 31 * day_ns).build());
+
+        assert_eq!(contract.can_read_post(alice.clone(), "post-1".to_string()), read_decision(false, "expired"));
+        assert!(!contract.has_post_access(alice, "post-1".to_string()));
+    }
+
+    #[test]
+    fn test_record_read_counts_each_reader_once() {
+        let (mut contract, _) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        anchor_test_post(&mut contract, "post-1");
+
+        testing_env!(get_context(alice.clone()).build());
+        contract.record_read("post-1".to_string());
+        contract.record_read("post-1".to_string());
+
+        assert_eq!(contract.get_read_count("post-1".to_string()), 1);
+        assert!(contract.has_read("post-1".to_string(), alice));
+        assert!(!contract.has_read("post-1".to_string(), "bob.near".parse().unwrap()));
+        assert_eq!(contract.get_post("post-1".to_string()).unwrap().read_count, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "No access to post")]
+    fn test_record_read_requires_access() {
+        let (mut contract, _) = setup();
+        anchor_test_post(&mut contract, "post-1");
+
+        testing_env!(get_context("bob.near".parse().unwrap()).build());
+        contract.record_read("post-1".to_string());
+    }
+
+    #[test]
+    fn test_mint_and_transfer_emit_nep171_events() {
+        let (mut contract, token_id) = setup();
+        assert_eq!(
+            get_logs().last().unwrap(),
+            &format!(
+                "EVENT_JSON:{{\"standard\":\"nep171\",\"version\":\"1.0.0\",\"event\":\"nft_mint\",\"data\":[{{\"owner_id\":\"alice.near\",\"token_ids\":[\"{}\"]}}]}}",
+                token_id
+            )
+        );
+
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id.clone(), None, None);
+        assert_eq!(
+            get_logs(),
+            vec![format!(
+                "EVENT_JSON:{{\"standard\":\"nep171\",\"version\":\"1.0.0\",\"event\":\"nft_transfer\",\"data\":[{{\"old_owner_id\":\"alice.near\",\"new_owner_id\":\"bob.near\",\"token_ids\":[\"{}\"]}}]}}",
+                token_id
+            )]
+        );
+    }
+
+    #[test]
+    fn test_nft_tokens_pagination() {
+        let (mut contract, _) = setup();
+        for _ in 0..4 {
+            contract.mint_access_pass("bob.near".parse().unwrap(), source_hash(), "monthly".to_string(), 500, None);
+        }
+
+        let token_ids = |page: Vec<serde_json::Value>| -> Vec<String> {
+            page.iter().map(|t| t["token_id"].as_str().unwrap().to_string()).collect()
+        };
+        let first = token_ids(contract.nft_tokens(None, Some(3)));
+        let second = token_ids(contract.nft_tokens(Some(U128(3)), Some(3)));
+
+        assert_eq!(first.len(), 3);
+        assert_eq!(second.len(), 2);
+        assert!(first.iter().all(|id| !second.contains(id)));
+    }
+
+    #[test]
+    fn test_nft_supply_for_owner() {
+        let (mut contract, _) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        contract.mint_access_pass(alice.clone(), source_hash(), "monthly".to_string(), 500, None);
+        contract.mint_access_pass(alice.clone(), source_hash(), "monthly".to_string(), 500, None);
+
+        assert_eq!(contract.nft_supply_for_owner(alice).0, 3);
+        assert_eq!(contract.nft_supply_for_owner("bob.near".parse().unwrap()).0, 0);
+    }
+
+    #[test]
+    fn test_get_source_posts_filters() {
+        let (mut contract, _) = setup();
+        let posts = [
+            ("post-1", true, "2026-01"),
+            ("post-2", false, "2026-01"),
+            ("post-3", true, "2026-02"),
+            ("post-4", false, "2026-02"),
+            ("post-5", true, "2026-02"),
+        ];
+        for (post_id, is_premium, epoch) in posts {
+            contract.anchor_post(
+                post_id.to_string(),
+                source_hash(),
+                "c".repeat(64),
+                format!("bafy-{}", post_id),
+                is_premium,
+                epoch.to_string(),
+                vec![],
+                false,
+            );
+        }
+
+        let free = contract.get_source_posts(source_hash(), None, None, Some(false), None);
+        assert_eq!(free.len(), 2);
+        assert!(free.iter().all(|p| !p.is_premium));
+
+        let premium_feb = contract.get_source_posts(source_hash(), None, None, Some(true), Some("2026-02".to_string()));
+        assert_eq!(premium_feb.len(), 2);
+        assert!(premium_feb.iter().all(|p| p.is_premium && p.epoch == "2026-02"));
+
+        // Pagination applies after filtering
+        let page = contract.get_source_posts(source_hash(), Some(1), Some(5), Some(true), None);
+        assert_eq!(page.len(), 2);
+        assert!(page.iter().all(|p| p.is_premium));
+    }
+
+    #[test]
+    fn test_source_key_rotation_history() {
+        let (mut contract, _) = setup();
+        let controller: AccountId = "source.near".parse().unwrap();
+
+        let mut context = get_context(controller.clone());
+        testing_env!(context.block_timestamp(2_000).build());
+        contract.rotate_source_key(source_hash(), "pubkey-2".to_string());
+        testing_env!(context.block_timestamp(3_000).build());
+        contract.rotate_source_key(source_hash(), "pubkey-3".to_string());
+
+        let source = contract.get_source(source_hash()).unwrap();
+        assert_eq!(source.public_key, "pubkey-3");
+        assert_eq!(source.key_history, vec![
+            ("pubkey".to_string(), U64(2_000)),
+            ("pubkey-2".to_string(), U64(3_000)),
+        ]);
+
+        let key_at = |t: u64| contract.get_source_key_at(source_hash(), U64(t)).unwrap();
+        assert_eq!(key_at(1_500), "pubkey");
+        assert_eq!(key_at(2_000), "pubkey-2");
+        assert_eq!(key_at(2_999), "pubkey-2");
+        assert_eq!(key_at(5_000), "pubkey-3");
+    }
+
+    #[test]
+    #[should_panic(expected = "Source is not accepting new subscribers")]
+    fn test_paused_source_blocks_minting() {
+        let (mut contract, _) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+
+        testing_env!(get_context("source.near".parse().unwrap()).build());
+        contract.set_source_active(source_hash(), false);
+
+        // Existing subscribers keep access
+        assert!(contract.has_access(alice.clone(), source_hash()));
+
+        testing_env!(get_context("owner.near".parse().unwrap()).build());
+        contract.mint_access_pass(alice, source_hash(), "monthly".to_string(), 500, None);
+    }
+
+    #[test]
+    fn test_active_vs_expired_access_passes() {
+        let (mut contract, first) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let day_ns: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+        // Second pass bought 20 days later
+        let mut context = get_context("owner.near".parse().unwrap());
+        testing_env!(context.block_timestamp(1_000_000_000 + 20 * day_ns).build());
+        let second = contract.mint_access_pass(alice.clone(), source_hash(), "monthly".to_string(), 500, None);
+        assert_eq!(contract.get_active_access_passes(alice.clone()).len(), 2);
+        assert!(contract.get_expired_access_passes(alice.clone()).is_empty());
+
+        // Past the first pass's expiry only
+        testing_env!(context.block_timestamp(1_000_000_000 + 31 * day_ns).build());
+        let active = contract.get_active_access_passes(alice.clone());
+        let expired = contract.get_expired_access_passes(alice);
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].0, second);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].0, first);
+    }
+
+    #[test]
+    fn test_is_pass_valid() {
+        let (mut contract, monthly) = setup();
+        let day_ns: u64 = 24 * 60 * 60 * 1_000_000_000;
+        // Packages can't be created with zero duration, so mark a pass as lifetime directly
+        let lifetime = contract.mint_access_pass(
+            "bob.near".parse().unwrap(), source_hash(), "monthly".to_string(), 500, None);
+        contract.access_pass_data.get_mut(&lifetime).unwrap().expires_at = U64(0);
+
+        assert!(contract.is_pass_valid(monthly.clone()));
+        assert!(contract.is_pass_valid(lifetime.clone()));
+        assert!(!contract.is_pass_valid("missing".to_string()));
+        assert_eq!(contract.nft_token(monthly.clone()).unwrap()["is_valid"], true);
+
+        let mut context = get_context("owner.near".parse().unwrap());
+        testing_env!(context.block_timestamp(1_000_000_000 + 31 * day_ns).build());
+        assert!(!contract.is_pass_valid(monthly.clone()));
+        assert!(contract.is_pass_valid(lifetime));
+        assert_eq!(contract.nft_token(monthly).unwrap()["is_valid"], false);
+    }
+
+    #[test]
+    fn test_pass_metadata_uses_display_name_and_media() {
+        let (mut contract, default_pass) = setup();
+        let title = |contract: &HumintFeed, token_id: &TokenId| {
+            contract.token_metadata_by_id.get(token_id).unwrap().title.clone().unwrap()
+        };
+        assert_eq!(title(&contract, &default_pass), "aaaaaaaa - Monthly");
+        assert!(contract.token_metadata_by_id.get(&default_pass).unwrap().media.is_none());
+
+        testing_env!(get_context("source.near".parse().unwrap()).build());
+        contract.set_display_name(source_hash(), Some("Nightingale".to_string()));
+        let package = Package { media: Some("https://argus.vitalpoint.ai/passes/monthly.png".to_string()), ..monthly_package() };
+        contract.update_packages(source_hash(), vec![package]);
+
+        testing_env!(get_context("owner.near".parse().unwrap()).build());
+        let named_pass = contract.mint_access_pass("bob.near".parse().unwrap(), source_hash(), "monthly".to_string(), 500, None);
+        assert_eq!(title(&contract, &named_pass), "Nightingale - Monthly");
+        assert_eq!(
+            contract.nft_token(named_pass).unwrap()["metadata"]["media"],
+            "https://argus.vitalpoint.ai/passes/monthly.png"
+        );
+        // Existing passes keep the title they were minted with
+        assert_eq!(title(&contract, &default_pass), "aaaaaaaa - Monthly");
+    }
+
+    #[test]
+    fn test_payout_routes_source_royalty() {
+        let (mut contract, token_id) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let vault: AccountId = "vault.near".parse().unwrap();
+
+        // No royalty configured: the seller keeps everything
+        let payout = contract.nft_payout(token_id.clone(), U128(1_000_000), 1).payout;
+        assert_eq!(payout[&alice], U128(1_000_000));
+
+        testing_env!(get_context("source.near".parse().unwrap()).build());
+        contract.set_payout_account(source_hash(), vault.clone());
+        contract.set_source_royalty(source_hash(), 750);
+
+        let payout = contract.nft_payout(token_id.clone(), U128(1_000_000), 2).payout;
+        assert_eq!(payout.len(), 2);
+        assert_eq!(payout[&vault], U128(75_000));
+        assert_eq!(payout[&alice], U128(925_000));
+
+        testing_env!(get_context(alice.clone()).build());
+        let payout = contract
+            .nft_transfer_payout("bob.near".parse().unwrap(), token_id.clone(), None, None, U128(1_000_000), 2)
+            .payout;
+        assert_eq!(payout[&alice], U128(925_000));
+        assert_eq!(contract.tokens_by_id.get(&token_id).unwrap().owner_id, "bob.near".parse::<AccountId>().unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "Payout exceeds max_len_payout")]
+    fn test_payout_rejects_small_max_len() {
+        let (mut contract, token_id) = setup();
+        testing_env!(get_context("source.near".parse().unwrap()).build());
+        contract.set_payout_account(source_hash(), "vault.near".parse().unwrap());
+        contract.set_source_royalty(source_hash(), 750);
+        contract.nft_payout(token_id, U128(1_000_000), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Royalty cannot exceed 25%")]
+    fn test_source_royalty_capped() {
+        let (mut contract, _) = setup();
+        contract.set_source_royalty(source_hash(), 2_501);
+    }
+
+    fn codename_hash(codename: &str) -> String {
+        env::sha256(codename.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_claim_source_control_with_preimage() {
+        let (mut contract, _) = setup();
+        let hash = codename_hash("NIGHTINGALE");
+        contract.register_source(hash.clone(), "pubkey".to_string(), vec![monthly_package()], None, None);
+
+        let claimant: AccountId = "nightingale.near".parse().unwrap();
+        testing_env!(get_context(claimant.clone()).build());
+        contract.claim_source_control(hash.clone(), "NIGHTINGALE".to_string());
+        assert_eq!(contract.get_source(hash.clone()).unwrap().controller, Some(claimant.clone()));
+
+        // The new controller can manage the source
+        contract.set_payout_account(hash.clone(), claimant.clone());
+        assert_eq!(contract.get_source(hash).unwrap().payout_account, Some(claimant));
+    }
+
+    #[test]
+    #[should_panic(expected = "Preimage does not match codename hash")]
+    fn test_claim_source_control_wrong_preimage() {
+        let (mut contract, _) = setup();
+        let hash = codename_hash("NIGHTINGALE");
+        contract.register_source(hash.clone(), "pubkey".to_string(), vec![monthly_package()], None, None);
+
+        testing_env!(get_context("mallory.near".parse().unwrap()).build());
+        contract.claim_source_control(hash, "MOCKINGBIRD".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Source already has a controller")]
+    fn test_claim_source_control_once() {
+        let (mut contract, _) = setup();
+        let hash = codename_hash("NIGHTINGALE");
+        contract.register_source(hash.clone(), "pubkey".to_string(), vec![monthly_package()], None, None);
+
+        testing_env!(get_context("nightingale.near".parse().unwrap()).build());
+        contract.claim_source_control(hash.clone(), "NIGHTINGALE".to_string());
+        // Replaying the now-public preimage doesn't hand over control
+        testing_env!(get_context("mallory.near".parse().unwrap()).build());
+        contract.claim_source_control(hash, "NIGHTINGALE".to_string());
+    }
+
+    #[test]
+    fn test_grace_period_extends_access() {
+        let (mut contract, token_id) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let hour_ns: u64 = 60 * 60 * 1_000_000_000;
+        let expires_at = 1_000_000_000 + 30 * 24 * hour_ns;
+        contract.set_grace_period(U64(2 * hour_ns));
+        assert_eq!(contract.get_grace_period(), U64(2 * hour_ns));
+
+        // Lifetime pass for bob is unaffected by the clock
+        let lifetime = contract.mint_access_pass("bob.near".parse().unwrap(), source_hash(), "monthly".to_string(), 500, None);
+        contract.access_pass_data.get_mut(&lifetime).unwrap().expires_at = U64(0);
+
+        let mut context = get_context("owner.near".parse().unwrap());
+        testing_env!(context.block_timestamp(expires_at + 1).build());
+        assert!(contract.has_access(alice.clone(), source_hash()));
+
+        testing_env!(context.block_timestamp(expires_at + 2 * hour_ns).build());
+        assert!(contract.has_access(alice.clone(), source_hash()));
+        // Pass views agree with has_access inside the grace period
+        assert!(contract.is_pass_valid(token_id.clone()));
+        assert_eq!(contract.get_active_access_passes(alice.clone()).len(), 1);
+        assert!(contract.get_expired_access_passes(alice.clone()).is_empty());
+        assert!(contract.burn_expired(vec![token_id.clone()]).is_empty());
+
+        testing_env!(context.block_timestamp(expires_at + 2 * hour_ns + 1).build());
+        assert!(!contract.has_access(alice.clone(), source_hash()));
+        assert!(contract.has_access("bob.near".parse().unwrap(), source_hash()));
+        assert!(!contract.is_pass_valid(token_id.clone()));
+        assert!(contract.get_active_access_passes(alice.clone()).is_empty());
+        assert_eq!(contract.get_expired_access_passes(alice).len(), 1);
+        assert_eq!(contract.burn_expired(vec![token_id.clone()]), vec![token_id]);
+    }
+
+    #[test]
+    fn test_lifetime_package_never_expires() {
+        let (mut contract, _) = setup();
+        let lifetime = Package {
+            id: "lifetime".to_string(),
+            duration_days: 0,
+            is_lifetime: true,
+            ..monthly_package()
+        };
+        contract.update_packages(source_hash(), vec![monthly_package(), lifetime]);
+
+        let token_id = contract.mint_access_pass("bob.near".parse().unwrap(), source_hash(), "lifetime".to_string(), 5000, None);
+        assert_eq!(contract.get_access_pass(token_id.clone()).unwrap().expires_at, U64(0));
+
+        let mut context = get_context("owner.near".parse().unwrap());
+        testing_env!(context.block_timestamp(1_000_000_000 + 3650 * 24 * 60 * 60 * 1_000_000_000).build());
+        assert!(contract.is_pass_valid(token_id));
+        assert!(contract.has_access("bob.near".parse().unwrap(), source_hash()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Package duration must be > 0")]
+    fn test_zero_duration_package_requires_lifetime_flag() {
+        let (mut contract, _) = setup();
+        let package = Package { duration_days: 0, ..monthly_package() };
+        contract.update_packages(source_hash(), vec![package]);
+    }
+
+    #[test]
+    fn test_mint_access_pass_detailed() {
+        let (mut contract, _) = setup();
+        let expires_at = 1_000_000_000u64 + 30 * 24 * 60 * 60 * 1_000_000_000;
+
+        let minted = contract.mint_access_pass_detailed(
+            "bob.near".parse().unwrap(), source_hash(), "monthly".to_string(), 500, None);
+
+        assert_eq!(minted["token"]["owner_id"], "bob.near");
+        assert_eq!(minted["token"]["metadata"]["expires_at"], expires_at.to_string());
+        assert_eq!(minted["access_pass"]["expires_at"], expires_at.to_string());
+        assert_eq!(minted["access_pass"]["package_id"], "monthly");
+        assert_eq!(minted["token"]["is_valid"], true);
+    }
+
+    const MONTH_NS: u64 = 30 * 24 * 60 * 60 * 1_000_000_000;
+
+    #[test]
+    fn test_renew_active_pass_extends_from_expiry() {
+        let (mut contract, token_id) = setup();
+        let expires_at = 1_000_000_000 + MONTH_NS;
+
+        // Renewing ten days early keeps the remaining time
+        let renewed_at = expires_at - 10 * 24 * 60 * 60 * 1_000_000_000;
+        let mut context = get_context("owner.near".parse().unwrap());
+        testing_env!(context.block_timestamp(renewed_at).build());
+        contract.renew_access_pass(token_id.clone(), 500);
+
+        let pass = contract.get_access_pass(token_id.clone()).unwrap();
+        assert_eq!(pass.expires_at, U64(expires_at + MONTH_NS));
+        assert_eq!(pass.amount_paid_usdc_cents, 1_000);
+
+        let metadata = contract.token_metadata_by_id.get(&token_id).unwrap();
+        assert_eq!(metadata.expires_at, Some((expires_at + MONTH_NS).to_string()));
+        assert_eq!(metadata.updated_at, Some(renewed_at.to_string()));
+        assert_eq!(contract.get_source(source_hash()).unwrap().subscriber_count, 1);
+    }
+
+    #[test]
+    fn test_renew_expired_pass_extends_from_now() {
+        let (mut contract, token_id) = setup();
+        let renewed_at = 1_000_000_000 + 3 * MONTH_NS;
+
+        let mut context = get_context("owner.near".parse().unwrap());
+        testing_env!(context.block_timestamp(renewed_at).build());
+        contract.renew_access_pass(token_id.clone(), 500);
+        contract.renew_access_pass(token_id.clone(), 700);
+
+        let pass = contract.get_access_pass(token_id.clone()).unwrap();
+        assert_eq!(pass.expires_at, U64(renewed_at + 2 * MONTH_NS));
+        assert_eq!(pass.amount_paid_usdc_cents, 1_700);
+        assert!(contract.is_pass_valid(token_id.clone()));
+        assert!(contract.has_access("alice.near".parse().unwrap(), source_hash()));
+
+        let metadata = contract.token_metadata_by_id.get(&token_id).unwrap();
+        assert_eq!(metadata.expires_at, Some((renewed_at + 2 * MONTH_NS).to_string()));
+        assert_eq!(metadata.updated_at, Some(renewed_at.to_string()));
+        assert_eq!(contract.get_source(source_hash()).unwrap().subscriber_count, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Lifetime pass cannot be renewed")]
+    fn test_renew_lifetime_pass_rejected() {
+        let (mut contract, _) = setup();
+        let lifetime = Package {
+            id: "lifetime".to_string(),
+            duration_days: 0,
+            is_lifetime: true,
+            ..monthly_package()
+        };
+        contract.update_packages(source_hash(), vec![monthly_package(), lifetime]);
+        let token_id = contract.mint_access_pass("bob.near".parse().unwrap(), source_hash(), "lifetime".to_string(), 5000, None);
+
+        contract.renew_access_pass(token_id, 5000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Package not found")]
+    fn test_renew_removed_package_rejected() {
+        let (mut contract, token_id) = setup();
+        let weekly = Package { id: "weekly".to_string(), duration_days: 7, ..monthly_package() };
+        contract.update_packages(source_hash(), vec![weekly]);
+
+        contract.renew_access_pass(token_id, 500);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner can renew access passes")]
+    fn test_renew_requires_owner() {
+        let (mut contract, token_id) = setup();
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        contract.renew_access_pass(token_id, 500);
+    }
+
+    #[test]
+    fn test_accrued_fees_across_mints() {
+        // setup() mints one $5.00 pass at 2.5%
+        let (mut contract, _) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        assert_eq!(contract.get_accrued_fees().0, 12);
+
+        contract.set_platform_fee(1000);
+        contract.mint_access_pass(alice.clone(), source_hash(), "monthly".to_string(), 2_000, None);
+        assert_eq!(contract.get_accrued_fees().0, 212);
+
+        contract.set_platform_fee(0);
+        contract.mint_access_pass(alice, source_hash(), "monthly".to_string(), 2_000, None);
+        assert_eq!(contract.get_accrued_fees().0, 212);
+
+        assert_eq!(contract.reset_accrued_fees().0, 212);
+        assert_eq!(contract.get_accrued_fees().0, 0);
+    }
+
+    #[test]
+    fn test_storage_deposit_and_mint_consumes_balance() {
+        let (mut contract, _) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let one_near = NearToken::from_near(1).as_yoctonear();
+
+        let before = contract.storage_balance_of(alice.clone()).unwrap();
+        assert_eq!(before.total.0, one_near);
+        assert!(before.available.0 < one_near);
+
+        contract.mint_access_pass(alice.clone(), source_hash(), "monthly".to_string(), 500, None);
+        let after = contract.storage_balance_of(alice.clone()).unwrap();
+        assert_eq!(after.total.0, one_near);
+        assert!(after.available.0 < before.available.0);
+
+        testing_env!(get_context(alice.clone()).build());
+        let withdrawn = contract.storage_withdraw(None);
+        assert_eq!(withdrawn.available.0, 0);
+        assert_eq!(withdrawn.total.0, one_near - after.available.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient storage balance")]
+    fn test_mint_without_enough_storage_balance() {
+        let (mut contract, _) = setup();
+        let carol: AccountId = "carol.near".parse().unwrap();
+
+        let mut context = get_context("owner.near".parse().unwrap());
+        testing_env!(context.attached_deposit(NearToken::from_yoctonear(10)).build());
+        contract.storage_deposit(Some(carol.clone()), None);
+
+        testing_env!(get_context("owner.near".parse().unwrap()).build());
+        contract.mint_access_pass(carol, source_hash(), "monthly".to_string(), 500, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "CID already anchored under post-1")]
+    fn test_duplicate_cid_rejected() {
+        let (mut contract, _) = setup();
+        anchor_test_post(&mut contract, "post-1");
+
+        contract.anchor_post(
+            "post-2".to_string(),
+            source_hash(),
+            "c".repeat(64),
+            "bafy-post-1".to_string(),
+            true,
+            "2026-02".to_string(),
+            vec![],
+            false,
+        );
+    }
+
+    #[test]
+    fn test_duplicate_cid_allowed_with_override() {
+        let (mut contract, _) = setup();
+        anchor_test_post(&mut contract, "post-1");
+
+        contract.anchor_post(
+            "post-2".to_string(),
+            source_hash(),
+            "c".repeat(64),
+            "bafy-post-1".to_string(),
+            true,
+            "2026-02".to_string(),
+            vec![],
+            true,
+        );
+
+        assert!(contract.get_post("post-2".to_string()).is_some());
+        // Lookup keeps pointing at the first anchor
+        assert_eq!(contract.find_post_by_cid("bafy-post-1".to_string()).unwrap().post_id, "post-1");
+    }
+
+    #[test]
+    fn test_recent_posts_across_sources() {
+        let (mut contract, _) = setup();
+        let source_b = "b".repeat(64);
+        contract.register_source(source_b.clone(), "pubkey".to_string(), vec![monthly_package()], None, None);
+
+        anchor_test_post(&mut contract, "post-1");
+        contract.anchor_post(
+            "post-2".to_string(),
+            source_b,
+            "c".repeat(64),
+            "bafy-post-2".to_string(),
+            false,
+            "2026-02".to_string(),
+            vec![],
+            false,
+        );
+        anchor_test_post(&mut contract, "post-3");
+
+        let ids = |posts: Vec<PostAnchor>| -> Vec<String> { posts.into_iter().map(|p| p.post_id).collect() };
+        assert_eq!(ids(contract.get_recent_posts(None, None, None)), vec!["post-3", "post-2", "post-1"]);
+        assert_eq!(ids(contract.get_recent_posts(Some(1), Some(1), None)), vec!["post-2"]);
+        assert_eq!(ids(contract.get_recent_posts(None, None, Some(true))), vec!["post-3", "post-1"]);
+    }
+
+    #[test]
+    fn test_posts_indexed_by_proof_type() {
+        let (mut contract, _) = setup();
+        let proofs = [
+            ("post-1", vec!["SatelliteImagery", "LocationProximity"]),
+            ("post-2", vec!["SatelliteImagery"]),
+            ("post-3", vec!["TimestampRange", "LocationProximity"]),
+        ];
+        for (post_id, zk_proofs) in proofs {
+            contract.anchor_post(
+                post_id.to_string(),
+                source_hash(),
+                "c".repeat(64),
+                format!("bafy-{}", post_id),
+                true,
+                "2026-02".to_string(),
+                zk_proofs.into_iter().map(String::from).collect(),
+                false,
+            );
+        }
+
+        let ids = |proof_type: &str| -> Vec<String> {
+            let mut ids: Vec<String> = contract
+                .get_posts_by_proof_type(proof_type.to_string(), None, None)
+                .into_iter()
+                .map(|p| p.post_id)
+                .collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(ids("SatelliteImagery"), vec!["post-1", "post-2"]);
+        assert_eq!(ids("LocationProximity"), vec!["post-1", "post-3"]);
+        assert_eq!(ids("TimestampRange"), vec!["post-3"]);
+        assert!(ids("FinancialThreshold").is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown proof type")]
+    fn test_unknown_proof_type_rejected() {
+        let (mut contract, _) = setup();
+        contract.anchor_post(
+            "post-1".to_string(),
+            source_hash(),
+            "c".repeat(64),
+            "bafy-post-1".to_string(),
+            true,
+            "2026-02".to_string(),
+            vec!["Vibes".to_string()],
+            false,
+        );
+    }
+
+    #[test]
+    fn test_tip_source_splits_fee() {
+        let (mut contract, _) = setup();
+        testing_env!(get_context("source.near".parse().unwrap()).build());
+        contract.set_payout_account(source_hash(), "payout.near".parse().unwrap());
+
+        let mut context = get_context("alice.near".parse().unwrap());
+        testing_env!(context.attached_deposit(NearToken::from_yoctonear(10_000)).build());
+        contract.tip_source(source_hash());
+
+        // 2.5% platform fee
+        assert_eq!(contract.get_accrued_tip_fees().0, 250);
+        assert_eq!(contract.get_source(source_hash()).unwrap().total_tips_received.0, 9_750);
+    }
+
+    #[test]
+    #[should_panic(expected = "Source is not active")]
+    fn test_tip_inactive_source_rejected() {
+        let (mut contract, _) = setup();
+        testing_env!(get_context("source.near".parse().unwrap()).build());
+        contract.set_payout_account(source_hash(), "payout.near".parse().unwrap());
+        contract.set_source_active(source_hash(), false);
+
+        let mut context = get_context("alice.near".parse().unwrap());
+        testing_env!(context.attached_deposit(NearToken::from_near(1)).build());
+        contract.tip_source(source_hash());
+    }
+    fn registry_proof(proof_id: &str, source_hash: &str, proof_type: &str) -> Vec<u8> {
+        serde_json::json!({
+            "proof_id": proof_id,
+            "source_hash": source_hash,
+            "proof_type": proof_type,
+            "status": "Verified",
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    fn verified_input(post_id: &str, proof_ids: &[&str]) -> PostAnchorInput {
+        PostAnchorInput {
+            post_id: post_id.to_string(),
+            codename_hash: source_hash(),
+            content_hash: "c".repeat(64),
+            content_cid: format!("bafy-{}", post_id),
+            is_premium: true,
+            epoch: "2026-02".to_string(),
+            zk_proofs: vec![],
+            allow_duplicate_cid: false,
+            proof_ids: proof_ids.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+
+    /// Run the anchor callback with the given `get_proof` results from the mock registry
+    fn resolve_anchor_with(
+        contract: &mut HumintFeed,
+        input: PostAnchorInput,
+        registry_results: Vec<Vec<u8>>,
+    ) -> bool {
+        resolve_anchor_with_deposit(contract, input, registry_results, 0)
+    }
+
+    fn resolve_anchor_with_deposit(
+        contract: &mut HumintFeed,
+        input: PostAnchorInput,
+        registry_results: Vec<Vec<u8>>,
+        deposit: u128,
+    ) -> bool {
+        testing_env!(
+            get_context(contract_account()).build(),
+            near_sdk::test_vm_config(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            registry_results.into_iter().map(PromiseResult::Successful).collect()
+        );
+        contract.resolve_verified_anchor(input, "source.near".parse().unwrap(), U128(deposit))
+    }
+
+    /// Transfer amounts created for `receiver`
+    fn transfers_to(receiver: &str) -> Vec<NearToken> {
+        get_created_receipts()
+            .into_iter()
+            .filter(|r| r.receiver_id.as_str() == receiver)
+            .flat_map(|r| r.actions)
+            .filter_map(|a| match a {
+                MockAction::Transfer { deposit, .. } => Some(deposit),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_anchor_post_verified_requires_registry() {
+        let (mut contract, _) = setup();
+        assert_eq!(contract.get_intel_registry(), None);
+        contract.set_intel_registry(Some("intel.near".parse().unwrap()));
+        assert_eq!(contract.get_intel_registry(), Some("intel.near".parse().unwrap()));
+
+        contract.anchor_post_verified(
+            "post-v".to_string(),
+            source_hash(),
+            "c".repeat(64),
+            "bafy-post-v".to_string(),
+            true,
+            "2026-02".to_string(),
+            vec!["proof-1".to_string(), "proof-2".to_string()],
+            false,
+        );
+        // Nothing is written until the registry lookups resolve
+        assert!(contract.get_post("post-v".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Intel registry not configured")]
+    fn test_anchor_post_verified_without_registry() {
+        let (mut contract, _) = setup();
+        contract.anchor_post_verified(
+            "post-v".to_string(),
+            source_hash(),
+            "c".repeat(64),
+            "bafy-post-v".to_string(),
+            true,
+            "2026-02".to_string(),
+            vec!["proof-1".to_string()],
+            false,
+        );
+    }
+
+    #[test]
+    fn test_verified_anchor_with_present_proofs() {
+        let (mut contract, _) = setup();
+        let anchored = resolve_anchor_with(
+            &mut contract,
+            verified_input("post-v", &["proof-1", "proof-2"]),
+            vec![
+                registry_proof("proof-1", &source_hash(), "LocationProximity"),
+                registry_proof("proof-2", &source_hash(), "LocationProximity"),
+            ],
+        );
+
+        assert!(anchored);
+        let post = contract.get_post("post-v".to_string()).unwrap();
+        assert_eq!(post.proof_ids, vec!["proof-1".to_string(), "proof-2".to_string()]);
+        assert_eq!(post.zk_proofs, vec!["LocationProximity".to_string()]);
+        assert_eq!(contract.get_source(source_hash()).unwrap().post_count, 1);
+    }
+
+    #[test]
+    fn test_verified_anchor_with_absent_proof() {
+        let (mut contract, _) = setup();
+        let anchored = resolve_anchor_with(
+            &mut contract,
+            verified_input("post-v", &["proof-1", "missing"]),
+            vec![
+                registry_proof("proof-1", &source_hash(), "LocationProximity"),
+                b"null".to_vec(),
+            ],
+        );
+
+        assert!(!anchored);
+        assert!(contract.get_post("post-v".to_string()).is_none());
+        assert_eq!(
+            get_logs().last().unwrap(),
+            "Verified anchor rejected: Proof missing not found in registry"
+        );
+    }
+
+    #[test]
+    fn test_verified_anchor_with_foreign_proof() {
+        let (mut contract, _) = setup();
+        let anchored = resolve_anchor_with(
+            &mut contract,
+            verified_input("post-v", &["proof-1"]),
+            vec![registry_proof("proof-1", &"b".repeat(64), "LocationProximity")],
+        );
+
+        assert!(!anchored);
+        assert!(contract.get_post("post-v".to_string()).is_none());
+    }
+
+    fn anchor_verified(contract: &mut HumintFeed, content_hash: String, content_cid: &str) {
+        contract.set_intel_registry(Some("intel.near".parse().unwrap()));
+        contract.anchor_post_verified(
+            "post-v".to_string(),
+            source_hash(),
+            content_hash,
+            content_cid.to_string(),
+            true,
+            "2026-02".to_string(),
+            vec!["proof-1".to_string()],
+            false,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid content hash")]
+    fn test_anchor_post_verified_checks_hash_before_lookup() {
+        let (mut contract, _) = setup();
+        anchor_verified(&mut contract, "not-a-hash".to_string(), "bafy-post-v");
+    }
+
+    #[test]
+    #[should_panic(expected = "CID already anchored under post-1")]
+    fn test_anchor_post_verified_checks_cid_before_lookup() {
+        let (mut contract, _) = setup();
+        contract.anchor_post(
+            "post-1".to_string(),
+            source_hash(),
+            "c".repeat(64),
+            "bafy-shared".to_string(),
+            false,
+            "2026-02".to_string(),
+            vec![],
+            false,
+        );
+        anchor_verified(&mut contract, "c".repeat(64), "bafy-shared");
+    }
+
+    #[test]
+    fn test_verified_anchor_refunds_on_unknown_proof_type() {
+        let (mut contract, _) = setup();
+        let anchored = resolve_anchor_with_deposit(
+            &mut contract,
+            verified_input("post-v", &["proof-1"]),
+            vec![registry_proof("proof-1", &source_hash(), "PalmReading")],
+            1_000,
+        );
+
+        // The callback rejects instead of panicking, so the caller gets the deposit back
+        assert!(!anchored);
+        assert!(contract.get_post("post-v".to_string()).is_none());
+        assert_eq!(get_logs().last().unwrap(), "Verified anchor rejected: Unknown proof type");
+        assert_eq!(transfers_to("source.near"), vec![NearToken::from_yoctonear(1_000)]);
+    }
+
+    #[test]
+    fn test_verified_anchor_refunds_on_cid_taken_in_flight() {
+        let (mut contract, _) = setup();
+        let mut input = verified_input("post-v", &["proof-1"]);
+        input.content_cid = "bafy-shared".to_string();
+        contract.anchor_post(
+            "post-1".to_string(),
+            source_hash(),
+            "c".repeat(64),
+            "bafy-shared".to_string(),
+            false,
+            "2026-02".to_string(),
+            vec![],
+            false,
+        );
+
+        let anchored = resolve_anchor_with_deposit(
+            &mut contract,
+            input,
+            vec![registry_proof("proof-1", &source_hash(), "LocationProximity")],
+            1_000,
+        );
+
+        assert!(!anchored);
+        assert_eq!(
+            get_logs().last().unwrap(),
+            "Verified anchor rejected: CID already anchored under post-1"
+        );
+        assert_eq!(transfers_to("source.near"), vec![NearToken::from_yoctonear(1_000)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn test_paused_blocks_mint() {
+        let (mut contract, _) = setup();
+        contract.set_paused(true);
+        contract.mint_access_pass("bob.near".parse().unwrap(), source_hash(), "monthly".to_string(), 500, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn test_paused_blocks_anchor() {
+        let (mut contract, _) = setup();
+        contract.set_paused(true);
+        contract.anchor_post(
+            "post-1".to_string(),
+            source_hash(),
+            "c".repeat(64),
+            "bafy-post-1".to_string(),
+            true,
+            "2026-02".to_string(),
+            vec![],
+            false,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn test_paused_blocks_transfer() {
+        let (mut contract, token_id) = setup();
+        contract.set_paused(true);
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn test_paused_blocks_register_source() {
+        let (mut contract, _) = setup();
+        contract.set_paused(true);
+        contract.register_source("b".repeat(64), "pubkey".to_string(), vec![monthly_package()], None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn test_paused_blocks_update_packages() {
+        let (mut contract, _) = setup();
+        contract.set_paused(true);
+        contract.update_packages(source_hash(), vec![monthly_package()]);
+    }
+
+    #[test]
+    fn test_unpause_restores_mutations() {
+        let (mut contract, token_id) = setup();
+        contract.set_paused(true);
+        assert!(contract.is_paused());
+        // Views keep working while paused
+        assert!(contract.has_access("alice.near".parse().unwrap(), source_hash()));
+
+        contract.set_paused(false);
+        assert!(!contract.is_paused());
+        contract.update_packages(source_hash(), vec![monthly_package()]);
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id.clone(), None, None);
+        assert_eq!(contract.tokens_by_id.get(&token_id).unwrap().owner_id, "bob.near".parse::<AccountId>().unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner")]
+    fn test_set_paused_owner_only() {
+        let (mut contract, _) = setup();
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        contract.set_paused(true);
+    }
+
+    #[test]
+    fn test_fee_recipient_defaults_to_owner() {
+        let (mut contract, _) = setup();
+        assert_eq!(contract.get_fee_recipient(), "owner.near".parse::<AccountId>().unwrap());
+
+        contract.set_fee_recipient("treasury.near".parse().unwrap());
+        assert_eq!(contract.get_fee_recipient(), "treasury.near".parse::<AccountId>().unwrap());
+        assert_eq!(contract.owner_id, "owner.near".parse::<AccountId>().unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner")]
+    fn test_set_fee_recipient_owner_only() {
+        let (mut contract, _) = setup();
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        contract.set_fee_recipient("alice.near".parse().unwrap());
+    }
+
+    fn test_profile() -> SourceProfile {
+        SourceProfile {
+            bio: "Regional energy infrastructure reporting".to_string(),
+            avatar_cid: Some("bafy-avatar".to_string()),
+            categories: vec!["energy".to_string(), "infrastructure".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_register_source_with_profile() {
+        let (mut contract, _) = setup();
+        let source_b = "b".repeat(64);
+        contract.register_source(source_b.clone(), "pubkey".to_string(), vec![monthly_package()], None, Some(test_profile()));
+
+        let listed = contract.list_sources(None, None, None, None);
+        let profile = listed.iter().find(|s| s.codename_hash == source_b).unwrap().profile.as_ref().unwrap();
+        assert_eq!(profile.bio, "Regional energy infrastructure reporting");
+        assert_eq!(profile.categories, vec!["energy".to_string(), "infrastructure".to_string()]);
+        assert!(contract.get_source(source_hash()).unwrap().profile.is_none());
+    }
+
+    #[test]
+    fn test_controller_updates_source_profile() {
+        let (mut contract, _) = setup();
+        testing_env!(get_context("source.near".parse().unwrap()).build());
+        contract.update_source_profile(source_hash(), Some(test_profile()));
+
+        let updated = SourceProfile {
+            bio: "é".repeat(280),
+            avatar_cid: None,
+            ..test_profile()
+        };
+        contract.update_source_profile(source_hash(), Some(updated));
+        let profile = contract.get_source(source_hash()).unwrap().profile.unwrap();
+        assert_eq!(profile.bio.chars().count(), 280);
+        assert_eq!(profile.avatar_cid, None);
+
+        contract.update_source_profile(source_hash(), None);
+        assert!(contract.get_source(source_hash()).unwrap().profile.is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner or source controller")]
+    fn test_update_source_profile_requires_controller() {
+        let (mut contract, _) = setup();
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        contract.update_source_profile(source_hash(), Some(test_profile()));
+    }
+
+    #[test]
+    fn test_list_sources_filters_by_category() {
+        let (mut contract, _) = setup();
+        let energy = "b".repeat(64);
+        let cyber = "c".repeat(64);
+        contract.register_source(energy.clone(), "pubkey".to_string(), vec![monthly_package()], None, Some(test_profile()));
+        let cyber_profile = SourceProfile {
+            categories: vec!["Cyber".to_string()],
+            ..test_profile()
+        };
+        contract.register_source(cyber.clone(), "pubkey".to_string(), vec![monthly_package()], None, Some(cyber_profile));
+
+        let hashes = |sources: Vec<Source>| sources.into_iter().map(|s| s.codename_hash).collect::<Vec<_>>();
+        assert_eq!(hashes(contract.list_sources(None, None, None, Some("energy".to_string()))), vec![energy.clone()]);
+        assert_eq!(hashes(contract.list_sources(None, None, None, Some("cyber".to_string()))), vec![cyber]);
+        assert!(contract.list_sources(None, None, None, Some("finance".to_string())).is_empty());
+        assert_eq!(contract.list_sources(None, None, None, None).len(), 3);
+
+        contract.set_source_active(energy, false);
+        assert!(contract.list_sources(None, None, None, Some("energy".to_string())).is_empty());
+    }
+
+    #[test]
+    fn test_list_sources_can_include_inactive() {
+        let (mut contract, _) = setup();
+        contract.register_source("b".repeat(64), "pubkey".to_string(), vec![monthly_package()], None, None);
+        contract.set_source_active(source_hash(), false);
+
+        let active = contract.list_sources(None, None, None, None);
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].codename_hash, "b".repeat(64));
+
+        let all = contract.list_sources(None, None, Some(true), None);
+        assert_eq!(all.len(), 2);
+        assert!(all.iter().any(|s| s.codename_hash == source_hash() && !s.is_active));
+        assert_eq!(contract.list_sources(Some(1), Some(1), Some(true), None).len(), 1);
+    }
+
+    #[test]
+    fn test_get_source_subscribers_lists_active_holders() {
+        let (mut contract, alice_pass) = setup();
+        deposit_storage(&mut contract, &["carol.near"]);
+        let bob_pass = contract.mint_access_pass("bob.near".parse().unwrap(), source_hash(), "monthly".to_string(), 500, None);
+        contract.mint_access_pass("carol.near".parse().unwrap(), source_hash(), "monthly".to_string(), 500, None);
+        contract.access_pass_data.get_mut(&bob_pass).unwrap().expires_at = U64(1);
+
+        testing_env!(get_context("source.near".parse().unwrap()).build());
+        let accounts = |list: Vec<AccountId>| {
+            let mut names = list.into_iter().map(|a| a.to_string()).collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+        assert_eq!(
+            accounts(contract.get_source_subscribers(source_hash(), None, None)),
+            vec!["alice.near", "carol.near"]
+        );
+        assert_eq!(contract.get_source_subscribers(source_hash(), Some(1), Some(10)).len(), 1);
+
+        // Transferring away the only pass drops the holder
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        contract.nft_transfer("dave.near".parse().unwrap(), alice_pass, None, None);
+        testing_env!(get_context("owner.near".parse().unwrap()).build());
+        assert_eq!(
+            accounts(contract.get_source_subscribers(source_hash(), None, None)),
+            vec!["carol.near", "dave.near"]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner or source controller")]
+    fn test_get_source_subscribers_requires_controller() {
+        let (contract, _) = setup();
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        contract.get_source_subscribers(source_hash(), None, None);
+    }
+
+    /// Setup plus a "founders" package capped at two passes
+    fn setup_capped() -> HumintFeed {
+        let (mut contract, _) = setup();
+        let founders = Package {
+            id: "founders".to_string(),
+            max_supply: Some(2),
+            ..monthly_package()
+        };
+        contract.update_packages(source_hash(), vec![monthly_package(), founders]);
+        contract
+    }
+
+    #[test]
+    fn test_package_max_supply_counts_down() {
+        let mut contract = setup_capped();
+        assert_eq!(contract.remaining_supply(source_hash(), "monthly".to_string()), None);
+        assert_eq!(contract.remaining_supply(source_hash(), "founders".to_string()), Some(2));
+
+        contract.mint_access_pass("alice.near".parse().unwrap(), source_hash(), "founders".to_string(), 500, None);
+        assert_eq!(contract.remaining_supply(source_hash(), "founders".to_string()), Some(1));
+        contract.mint_access_pass("bob.near".parse().unwrap(), source_hash(), "founders".to_string(), 500, None);
+        assert_eq!(contract.remaining_supply(source_hash(), "founders".to_string()), Some(0));
+
+        // Uncapped packages keep minting
+        contract.mint_access_pass("bob.near".parse().unwrap(), source_hash(), "monthly".to_string(), 500, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Package sold out")]
+    fn test_package_sold_out() {
+        let mut contract = setup_capped();
+        for account in ["alice.near", "bob.near", "alice.near"] {
+            contract.mint_access_pass(account.parse().unwrap(), source_hash(), "founders".to_string(), 500, None);
+        }
+    }
+
+    #[test]
+    fn test_burn_access_pass_frees_storage() {
+        let (mut contract, token_id) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let available_before = contract.storage_balance_of(alice.clone()).unwrap().available.0;
+        assert_eq!(contract.get_source(source_hash()).unwrap().subscriber_count, 1);
+
+        testing_env!(get_context(alice.clone()).build());
+        contract.burn_access_pass(token_id.clone());
+
+        assert!(contract.tokens_by_id.get(&token_id).is_none());
+        assert!(contract.token_metadata_by_id.get(&token_id).is_none());
+        assert!(contract.get_access_pass(token_id.clone()).is_none());
+        assert!(!contract.tokens_per_owner.get(&alice).unwrap().contains(&token_id));
+        assert!(contract.source_passes.get(&(alice.clone(), source_hash())).unwrap().is_empty());
+        assert!(!contract.has_access(alice.clone(), source_hash()));
+        assert_eq!(contract.get_source(source_hash()).unwrap().subscriber_count, 0);
+        assert!(contract.storage_balance_of(alice).unwrap().available.0 > available_before);
+        assert!(get_logs().iter().any(|log| log.contains("\"event\":\"nft_burn\"") && log.contains(&token_id)));
+    }
+
+    /// Storage used once pending approval writes for `token_id` are flushed
+    fn flushed_approval_storage(contract: &mut HumintFeed, token_id: &TokenId) -> u64 {
+        if let Some(approvals) = contract.approved_accounts.get_mut(token_id) {
+            approvals.flush();
+        }
+        contract.approved_accounts.flush();
+        env::storage_usage()
+    }
+
+    #[test]
+    fn test_revoke_all_frees_approval_storage() {
+        let (mut contract, token_id) = setup_approved();
+        contract.nft_revoke_all(token_id.clone());
+        let cleared = flushed_approval_storage(&mut contract, &token_id);
+
+        contract.nft_approve(token_id.clone(), "market.near".parse().unwrap(), None);
+        contract.nft_approve(token_id.clone(), "carol.near".parse().unwrap(), None);
+        assert!(flushed_approval_storage(&mut contract, &token_id) > cleared);
+
+        contract.nft_revoke_all(token_id.clone());
+        assert_eq!(flushed_approval_storage(&mut contract, &token_id), cleared);
+    }
+
+    #[test]
+    fn test_burn_frees_approval_storage() {
+        let (mut contract, approved) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let plain = contract.mint_access_pass(alice.clone(), source_hash(), "monthly".to_string(), 500, None);
+        // A third pass keeps alice's indexes alive, so both burns free the same pass bytes
+        contract.mint_access_pass(alice.clone(), source_hash(), "monthly".to_string(), 500, None);
+
+        testing_env!(get_context(alice.clone()).build());
+        let before_approve = flushed_approval_storage(&mut contract, &approved);
+        contract.nft_approve(approved.clone(), "market.near".parse().unwrap(), None);
+        let approval_bytes = flushed_approval_storage(&mut contract, &approved) - before_approve;
+        assert!(approval_bytes > 0);
+
+        let burn_refund = |contract: &mut HumintFeed, token_id: &TokenId| {
+            let before = contract.storage_balance_of(alice.clone()).unwrap().available.0;
+            contract.burn_access_pass(token_id.clone());
+            contract.storage_balance_of(alice.clone()).unwrap().available.0 - before
+        };
+        let approved_refund = burn_refund(&mut contract, &approved);
+        let plain_refund = burn_refund(&mut contract, &plain);
+        assert_eq!(
+            approved_refund - plain_refund,
+            approval_bytes as u128 * env::storage_byte_cost().as_yoctonear()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Only token owner or contract owner")]
+    fn test_burn_access_pass_rejects_other_accounts() {
+        let (mut contract, token_id) = setup();
+        testing_env!(get_context("bob.near".parse().unwrap()).build());
+        contract.burn_access_pass(token_id);
+    }
+
+    #[test]
+    fn test_burn_expired_skips_valid_passes() {
+        let (mut contract, alice_pass) = setup();
+        let bob_pass = contract.mint_access_pass("bob.near".parse().unwrap(), source_hash(), "monthly".to_string(), 500, None);
+        contract.access_pass_data.get_mut(&alice_pass).unwrap().expires_at = U64(1);
+
+        let burned = contract.burn_expired(vec![alice_pass.clone(), bob_pass.clone(), "ap-missing".to_string()]);
+        assert_eq!(burned, vec![alice_pass.clone()]);
+        assert!(contract.get_access_pass(alice_pass).is_none());
+        assert!(contract.get_access_pass(bob_pass).is_some());
+        // Only active passes count toward subscribers, so the expired burn leaves it alone
+        assert_eq!(contract.get_source(source_hash()).unwrap().subscriber_count, 2);
+    }
+
+    #[test]
+    fn test_burn_frees_capped_supply() {
+        let mut contract = setup_capped();
+        let founders = contract.mint_access_pass("bob.near".parse().unwrap(), source_hash(), "founders".to_string(), 500, None);
+        assert_eq!(contract.remaining_supply(source_hash(), "founders".to_string()), Some(1));
+
+        contract.burn_access_pass(founders);
+        assert_eq!(contract.remaining_supply(source_hash(), "founders".to_string()), Some(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Bio cannot exceed 280 characters")]
+    fn test_source_profile_bio_cap() {
+        let (mut contract, _) = setup();
+        let profile = SourceProfile {
+            bio: "x".repeat(281),
+            ..test_profile()
+        };
+        contract.update_source_profile(source_hash(), Some(profile));
+    }
+
+    #[test]
+    #[should_panic(expected = "Category must be 1-32 bytes")]
+    fn test_source_profile_category_cap() {
+        let (mut contract, _) = setup();
+        let profile = SourceProfile {
+            categories: vec!["x".repeat(MAX_CATEGORY_BYTES + 1)],
+            ..test_profile()
+        };
+        contract.update_source_profile(source_hash(), Some(profile));
+    }
 }
@@ -0,0 +1,31 @@
+//! NEP-171 receiver for workspaces tests
+//!
+//! Keeps or returns every token it is sent, as configured at init.
+
+use near_sdk::{near, AccountId, PanicOnDefault};
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct MockReceiver {
+    return_tokens: bool,
+}
+
+#[near]
+impl MockReceiver {
+    #[init]
+    pub fn new(return_tokens: bool) -> Self {
+        Self { return_tokens }
+    }
+
+    /// Returns true to ask the NFT contract to give the token back
+    pub fn nft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_id: AccountId,
+        token_id: String,
+        msg: String,
+    ) -> bool {
+        let _ = (sender_id, previous_owner_id, token_id, msg);
+        self.return_tokens
+    }
+}
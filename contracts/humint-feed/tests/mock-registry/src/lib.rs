@@ -0,0 +1,38 @@
+//! Minimal intel-registry serving `get_proof` for workspaces tests
+//!
+//! Only the fields humint-feed reads are stored; anyone can add proofs.
+
+use near_sdk::serde_json::{self, Value};
+use near_sdk::store::LookupMap;
+use near_sdk::{near, PanicOnDefault};
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct MockIntelRegistry {
+    /// proof_id -> (source_hash, proof_type)
+    proofs: LookupMap<String, (String, String)>,
+}
+
+#[near]
+impl MockIntelRegistry {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            proofs: LookupMap::new(b"p"),
+        }
+    }
+
+    pub fn set_proof(&mut self, proof_id: String, source_hash: String, proof_type: String) {
+        self.proofs.insert(proof_id, (source_hash, proof_type));
+    }
+
+    pub fn get_proof(&self, proof_id: String) -> Option<Value> {
+        let (source_hash, proof_type) = self.proofs.get(&proof_id)?;
+        Some(serde_json::json!({
+            "proof_id": proof_id,
+            "source_hash": source_hash,
+            "proof_type": proof_type,
+            "status": "Verified",
+        }))
+    }
+}
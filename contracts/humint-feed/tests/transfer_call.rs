@@ -0,0 +1,124 @@
+//! Sandbox test: `nft_transfer_call` against receivers that keep or return the pass
+
+use near_workspaces::network::Sandbox;
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, Contract, Worker};
+use serde_json::{json, Value};
+
+async fn deploy_receiver(worker: &Worker<Sandbox>, return_tokens: bool) -> anyhow::Result<Contract> {
+    let receiver = worker
+        .dev_deploy(&near_workspaces::compile_project("./tests/mock-receiver").await?)
+        .await?;
+    receiver
+        .call("new")
+        .args_json(json!({ "return_tokens": return_tokens }))
+        .transact()
+        .await?
+        .into_result()?;
+    Ok(receiver)
+}
+
+async fn owner_of(humint: &Contract, token_id: &str) -> anyhow::Result<String> {
+    let token: Value = humint.view("nft_token").args_json(json!({ "token_id": token_id })).await?.json()?;
+    Ok(token["owner_id"].as_str().unwrap().to_string())
+}
+
+async fn is_approved(humint: &Contract, token_id: &str, account: &Account) -> anyhow::Result<bool> {
+    let approved: bool = humint
+        .view("nft_is_approved")
+        .args_json(json!({ "token_id": token_id, "approved_account_id": account.id() }))
+        .await?
+        .json()?;
+    Ok(approved)
+}
+
+/// Send `token_id` from alice to `receiver`; returns what `nft_transfer_call` resolved to
+async fn transfer_call(humint: &Contract, alice: &Account, receiver: &Contract, token_id: &str) -> anyhow::Result<bool> {
+    let kept: bool = alice
+        .call(humint.id(), "nft_transfer_call")
+        .args_json(json!({ "receiver_id": receiver.id(), "token_id": token_id, "msg": "list" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?
+        .json()?;
+    Ok(kept)
+}
+
+#[tokio::test]
+async fn test_transfer_call_with_mock_receivers() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let humint = worker.dev_deploy(&near_workspaces::compile_project("./").await?).await?;
+    let rejecting = deploy_receiver(&worker, true).await?;
+    let accepting = deploy_receiver(&worker, false).await?;
+    let alice = worker.dev_create_account().await?;
+    let market = worker.dev_create_account().await?;
+    let source_hash = "a".repeat(64);
+
+    humint
+        .call("new")
+        .args_json(json!({ "owner_id": humint.id(), "platform_fee_bps": 250 }))
+        .transact()
+        .await?
+        .into_result()?;
+    humint
+        .call("register_source")
+        .args_json(json!({
+            "codename_hash": source_hash,
+            "public_key": "pubkey",
+            "packages": [{
+                "id": "monthly",
+                "name": "Monthly",
+                "price_usdc_cents": 500,
+                "duration_days": 30,
+                "description": "30 days of access",
+                "soulbound": false,
+                "media": null,
+                "is_lifetime": false,
+                "max_supply": null,
+            }],
+            "controller": null,
+            "profile": null,
+        }))
+        .transact()
+        .await?
+        .into_result()?;
+    humint
+        .call("storage_deposit")
+        .args_json(json!({ "account_id": alice.id() }))
+        .deposit(NearToken::from_near(1))
+        .transact()
+        .await?
+        .into_result()?;
+    let token_id: String = humint
+        .call("mint_access_pass")
+        .args_json(json!({
+            "receiver_id": alice.id(),
+            "source_hash": source_hash,
+            "package_id": "monthly",
+            "amount_paid_usdc_cents": 500,
+        }))
+        .max_gas()
+        .transact()
+        .await?
+        .json()?;
+    alice
+        .call(humint.id(), "nft_approve")
+        .args_json(json!({ "token_id": token_id, "account_id": market.id() }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // A rejecting receiver sends the pass back along with alice's approvals
+    assert!(!transfer_call(&humint, &alice, &rejecting, &token_id).await?);
+    assert_eq!(owner_of(&humint, &token_id).await?, alice.id().as_str());
+    assert!(is_approved(&humint, &token_id, &market).await?);
+
+    // An accepting receiver keeps it, and the approvals stay cleared
+    assert!(transfer_call(&humint, &alice, &accepting, &token_id).await?);
+    assert_eq!(owner_of(&humint, &token_id).await?, accepting.id().as_str());
+    assert!(!is_approved(&humint, &token_id, &market).await?);
+
+    Ok(())
+}
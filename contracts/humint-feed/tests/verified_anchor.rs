@@ -0,0 +1,95 @@
+//! Sandbox test: `anchor_post_verified` against a mock intel-registry
+
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, Contract};
+use serde_json::{json, Value};
+
+async fn anchor(
+    humint: &Contract,
+    caller: &Account,
+    post_id: &str,
+    source_hash: &str,
+    proof_id: &str,
+) -> anyhow::Result<bool> {
+    let anchored = caller
+        .call(humint.id(), "anchor_post_verified")
+        .args_json(json!({
+            "post_id": post_id,
+            "codename_hash": source_hash,
+            "content_hash": "c".repeat(64),
+            "content_cid": format!("bafy-{}", post_id),
+            "is_premium": false,
+            "epoch": "2026-02",
+            "proof_ids": [proof_id],
+            "allow_duplicate_cid": false,
+        }))
+        .deposit(NearToken::from_near(1))
+        .max_gas()
+        .transact()
+        .await?
+        .json()?;
+    Ok(anchored)
+}
+
+#[tokio::test]
+async fn test_verified_anchor_through_mock_registry() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let humint = worker.dev_deploy(&near_workspaces::compile_project("./").await?).await?;
+    let registry = worker.dev_deploy(&near_workspaces::compile_project("./tests/mock-registry").await?).await?;
+    let source = worker.dev_create_account().await?;
+    let source_hash = "a".repeat(64);
+
+    humint
+        .call("new")
+        .args_json(json!({ "owner_id": humint.id(), "platform_fee_bps": 250 }))
+        .transact()
+        .await?
+        .into_result()?;
+    humint
+        .call("set_intel_registry")
+        .args_json(json!({ "intel_registry": registry.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+    source
+        .call(humint.id(), "register_source")
+        .args_json(json!({
+            "codename_hash": source_hash,
+            "public_key": "pubkey",
+            "packages": [],
+            "controller": source.id(),
+            "profile": null,
+        }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    registry.call("new").transact().await?.into_result()?;
+    for (proof_id, owner_hash) in [("proof-own", source_hash.clone()), ("proof-foreign", "b".repeat(64))] {
+        registry
+            .call("set_proof")
+            .args_json(json!({ "proof_id": proof_id, "source_hash": owner_hash, "proof_type": "LocationProximity" }))
+            .transact()
+            .await?
+            .into_result()?;
+    }
+
+    // A proof registered to the source anchors the post with the registry's proof type
+    assert!(anchor(&humint, &source, "post-own", &source_hash, "proof-own").await?);
+    let post: Value = humint.view("get_post").args_json(json!({ "post_id": "post-own" })).await?.json()?;
+    assert_eq!(post["zk_proofs"], json!(["LocationProximity"]));
+    assert_eq!(post["proof_ids"], json!(["proof-own"]));
+
+    // A foreign proof is rejected and the 1 NEAR deposit comes back, less gas
+    let before = source.view_account().await?.balance;
+    assert!(!anchor(&humint, &source, "post-foreign", &source_hash, "proof-foreign").await?);
+    let after = source.view_account().await?.balance;
+    assert!(before.saturating_sub(after) < NearToken::from_millinear(100));
+    let post: Option<Value> = humint.view("get_post").args_json(json!({ "post_id": "post-foreign" })).await?.json()?;
+    assert!(post.is_none());
+
+    // A missing proof is rejected the same way
+    assert!(!anchor(&humint, &source, "post-missing", &source_hash, "proof-missing").await?);
+
+    Ok(())
+}
@@ -11,6 +11,8 @@ set -e
 NETWORK=${1:-testnet}
 CONTRACT_ACCOUNT=${2:-intel-registry.argus-test.testnet}
 OWNER_ACCOUNT=${3:-argus-test.testnet}
+# Pass "migrate" when upgrading a contract deployed with the original state layout
+MODE=${4:-init}
 
 echo "📦 Deploying intel-registry to ${NETWORK}..."
 echo "   Contract: ${CONTRACT_ACCOUNT}"
@@ -33,9 +35,15 @@ fi
 echo "🚀 Deploying contract..."
 near deploy --accountId $CONTRACT_ACCOUNT --wasmFile $WASM_PATH
 
-# Initialize
-echo "🔧 Initializing contract..."
-near call $CONTRACT_ACCOUNT new "{\"owner\": \"$OWNER_ACCOUNT\"}" --accountId $OWNER_ACCOUNT
+if [ "$MODE" = "migrate" ]; then
+    # Migrate existing state
+    echo "🔧 Migrating contract state..."
+    near call $CONTRACT_ACCOUNT migrate '{}' --accountId $CONTRACT_ACCOUNT
+else
+    # Initialize
+    echo "🔧 Initializing contract..."
+    near call $CONTRACT_ACCOUNT new "{\"owner\": \"$OWNER_ACCOUNT\"}" --accountId $OWNER_ACCOUNT
+fi
 
 echo ""
 echo "✅ Deployment complete!"
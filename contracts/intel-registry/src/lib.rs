@@ -1,8 +1,20 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{LookupMap, UnorderedMap, Vector};
-use near_sdk::json_types::U64;
+use near_sdk::collections::{LookupMap, UnorderedMap, UnorderedSet, Vector};
+use near_sdk::json_types::{U128, U64};
 use near_sdk::serde::{Deserialize, Serialize};
-use near_sdk::{env, near_bindgen, AccountId, PanicOnDefault, BorshStorageKey};
+use near_sdk::serde_json::{self, json};
+use near_sdk::{env, near_bindgen, AccountId, Balance, PanicOnDefault, BorshStorageKey, Promise};
+
+/// Default dispute window: 3 days
+const DEFAULT_DISPUTE_WINDOW_NS: u64 = 3 * 24 * 60 * 60 * 1_000_000_000;
+/// Default reputation half-life: ~30 days of 1s blocks
+const DEFAULT_REPUTATION_HALF_LIFE_BLOCKS: u64 = 30 * 24 * 60 * 60;
+/// Default number of attestations a proof needs before it can be Verified
+const DEFAULT_MIN_ATTESTATIONS_FOR_VERIFIED: u32 = 3;
+/// Stakes attesting at least this confidence are slashed when a proof is refuted
+const SLASH_CONFIDENCE: u8 = 70;
+/// Stakes attesting below this confidence are refunded when a proof is refuted
+const REFUND_CONFIDENCE: u8 = 30;
 
 /// Intel Registry - On-chain verification for HUMINT intelligence proofs
 /// 
@@ -28,6 +40,74 @@ pub struct IntelRegistry {
     total_attestations: u64,
     /// Contract owner (for admin functions)
     owner: AccountId,
+    /// How attestation confidences are aggregated
+    confidence_mode: ConfidenceMode,
+    /// Minimum NEAR stake required to attest
+    min_attest_stake: Balance,
+    /// Stake held per (proof_id, attestor)
+    attestation_stakes: LookupMap<(String, AccountId), Balance>,
+    /// Proof type -> proof IDs
+    proofs_by_type: LookupMap<String, Vector<String>>,
+    /// Verification status -> proof IDs
+    proofs_by_status: LookupMap<String, Vector<String>>,
+    /// Proof ID -> position within its status bucket
+    status_positions: LookupMap<String, u64>,
+    /// Proof IDs in registration order
+    proof_order: Vector<String>,
+    /// Track record of each attestor
+    attestor_stats: LookupMap<AccountId, AttestorStats>,
+    /// Average confidence at or above which a proof is Verified
+    verified_threshold: u8,
+    /// Attestations required, alongside the confidence threshold, for Verified
+    min_attestations_for_verified: u32,
+    /// Average confidence below which a proof may be refuted by consensus
+    refute_threshold: u8,
+    /// Source hash -> proof IDs
+    source_proofs: LookupMap<String, Vector<String>>,
+    /// Source hash -> account that first registered a proof for it
+    source_controllers: LookupMap<String, AccountId>,
+    /// Proof ID -> (linked proof ID, relation)
+    proof_links: LookupMap<String, Vector<(String, String)>>,
+    /// Open disputes by proof ID
+    disputes: LookupMap<String, Dispute>,
+    /// How long a source has to respond to a proposed refutation (ns)
+    dispute_window_ns: u64,
+    /// Organization -> accounts allowed to attest on its behalf
+    org_delegates: LookupMap<AccountId, UnorderedSet<AccountId>>,
+    /// Blocks of inactivity after which a source's activity and verified
+    /// contributions count half (0 disables decay)
+    reputation_half_life_blocks: u64,
+    /// Account proposed as the next owner, until it accepts
+    pending_owner: Option<AccountId>,
+}
+
+/// State layout of the originally deployed contract, read by `migrate`
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct OldIntelRegistry {
+    proofs: UnorderedMap<String, OldProofCommitment>,
+    attestations: LookupMap<String, Vector<Attestation>>,
+    source_stats: LookupMap<String, SourceStats>,
+    intel_proofs: LookupMap<String, Vector<String>>,
+    total_proofs: u64,
+    total_attestations: u64,
+    owner: AccountId,
+}
+
+/// Proof layout before expiry and corroboration were tracked
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct OldProofCommitment {
+    pub proof_id: String,
+    pub commitment: String,
+    pub proof_type: ProofType,
+    pub source_hash: String,
+    pub intel_hash: String,
+    pub public_inputs_hash: String,
+    pub block_height: U64,
+    pub timestamp_ns: U64,
+    pub metadata: Option<String>,
+    pub status: VerificationStatus,
+    pub attestation_count: u32,
+    pub avg_confidence: u8,
 }
 
 #[derive(BorshStorageKey, BorshSerialize)]
@@ -38,6 +118,22 @@ enum StorageKey {
     SourceStats,
     IntelProofs,
     IntelProofVector { intel_hash: String },
+    AttestationStakes,
+    ProofsByType,
+    ProofsByTypeVector { proof_type: String },
+    ProofsByStatus,
+    ProofsByStatusVector { status: String },
+    StatusPositions,
+    ProofOrder,
+    AttestorStats,
+    SourceProofs,
+    SourceProofVector { source_hash: String },
+    SourceControllers,
+    ProofLinks,
+    ProofLinkVector { proof_id: String },
+    Disputes,
+    OrgDelegates,
+    OrgDelegateSet { org_account: AccountId },
 }
 
 /// Proof types supported by the system
@@ -66,6 +162,24 @@ pub enum ProofType {
     GenericCommitment,
 }
 
+impl ProofType {
+    /// Index key for this proof type
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProofType::LocationProximity => "LocationProximity",
+            ProofType::TimestampRange => "TimestampRange",
+            ProofType::DocumentContains => "DocumentContains",
+            ProofType::ImageMetadata => "ImageMetadata",
+            ProofType::MultiSourceCorroboration => "MultiSourceCorroboration",
+            ProofType::VerifiableCredential => "VerifiableCredential",
+            ProofType::SatelliteImagery => "SatelliteImagery",
+            ProofType::NetworkMembership => "NetworkMembership",
+            ProofType::FinancialThreshold => "FinancialThreshold",
+            ProofType::GenericCommitment => "GenericCommitment",
+        }
+    }
+}
+
 /// A proof commitment stored on-chain
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
@@ -94,6 +208,28 @@ pub struct ProofCommitment {
     pub attestation_count: u32,
     /// Average attestation confidence (0-100)
     pub avg_confidence: u8,
+    /// Block timestamp (ns) after which the proof is stale
+    pub expires_at: Option<U64>,
+    /// Linked proofs from other sources backing the same intel
+    pub corroboration_count: u32,
+    /// Whether the proof is currently included in its source's `verified_count`
+    pub counted_verified: bool,
+    /// Whether attestor track records reflect this proof settling as Verified
+    pub credited_verified: bool,
+}
+
+/// Input for batch proof registration (same fields as `register_proof`)
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProofInput {
+    pub proof_id: String,
+    pub commitment: String,
+    pub proof_type: ProofType,
+    pub source_hash: String,
+    pub intel_hash: String,
+    pub public_inputs_hash: String,
+    pub metadata: Option<String>,
+    pub expires_at: Option<U64>,
 }
 
 /// Verification status of a proof
@@ -102,12 +238,49 @@ pub struct ProofCommitment {
 pub enum VerificationStatus {
     /// Just registered, no attestations
     Pending,
-    /// Has attestations, confidence >= 70
+    /// Has attestations, confidence >= verified threshold
     Verified,
-    /// Has attestations, confidence < 70
+    /// Has attestations, confidence < verified threshold
     Contested,
     /// Proven false by counter-evidence
     Refuted,
+    /// Refutation proposed, awaiting the end of the dispute window
+    UnderDispute,
+}
+
+impl VerificationStatus {
+    /// Index key for this status
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VerificationStatus::Pending => "Pending",
+            VerificationStatus::Verified => "Verified",
+            VerificationStatus::Contested => "Contested",
+            VerificationStatus::Refuted => "Refuted",
+            VerificationStatus::UnderDispute => "UnderDispute",
+        }
+    }
+}
+
+/// Pending refutation of a proof
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Dispute {
+    /// Reason given when the refutation was proposed
+    pub reason: String,
+    /// Block timestamp (ns) after which the refutation can be finalized
+    pub deadline: U64,
+}
+
+/// How attestation confidences are aggregated into `avg_confidence`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ConfidenceMode {
+    /// Arithmetic mean of all confidences
+    Mean,
+    /// Middle confidence (mean of the two middles for even counts)
+    Median,
+    /// Mean weighted by each attestor's reputation (+1 so unproven attestors still count)
+    Weighted,
 }
 
 /// Third-party attestation
@@ -144,6 +317,18 @@ pub struct SourceStats {
     pub last_proof_height: U64,
 }
 
+/// Track record of an attestor against final proof outcomes
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AttestorStats {
+    /// Distinct proofs attested
+    pub attestations_made: u64,
+    /// Attestations that matched the final outcome
+    pub agreements: u64,
+    /// Attestations that contradicted the final outcome
+    pub disagreements: u64,
+}
+
 /// View response for proof with attestations
 #[derive(Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
@@ -152,6 +337,16 @@ pub struct ProofWithAttestations {
     pub attestations: Vec<Attestation>,
 }
 
+/// View response for on-chain commitment recomputation
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CommitmentCheck {
+    /// Whether the recomputed commitment equals the stored one
+    pub matches: bool,
+    /// Hex sha256(proof_hash || public_inputs || source_id) as computed by the contract
+    pub computed_commitment: String,
+}
+
 #[near_bindgen]
 impl IntelRegistry {
     #[init]
@@ -164,7 +359,103 @@ impl IntelRegistry {
             total_proofs: 0,
             total_attestations: 0,
             owner,
+            confidence_mode: ConfidenceMode::Mean,
+            min_attest_stake: 0,
+            attestation_stakes: LookupMap::new(StorageKey::AttestationStakes),
+            proofs_by_type: LookupMap::new(StorageKey::ProofsByType),
+            proofs_by_status: LookupMap::new(StorageKey::ProofsByStatus),
+            status_positions: LookupMap::new(StorageKey::StatusPositions),
+            proof_order: Vector::new(StorageKey::ProofOrder),
+            attestor_stats: LookupMap::new(StorageKey::AttestorStats),
+            verified_threshold: 70,
+            min_attestations_for_verified: DEFAULT_MIN_ATTESTATIONS_FOR_VERIFIED,
+            refute_threshold: 30,
+            source_proofs: LookupMap::new(StorageKey::SourceProofs),
+            source_controllers: LookupMap::new(StorageKey::SourceControllers),
+            proof_links: LookupMap::new(StorageKey::ProofLinks),
+            disputes: LookupMap::new(StorageKey::Disputes),
+            dispute_window_ns: DEFAULT_DISPUTE_WINDOW_NS,
+            reputation_half_life_blocks: DEFAULT_REPUTATION_HALF_LIFE_BLOCKS,
+            org_delegates: LookupMap::new(StorageKey::OrgDelegates),
+            pending_owner: None,
+        }
+    }
+
+    /// Upgrade state written by the original contract to the current layout
+    ///
+    /// Deploy sequence:
+    /// 1. `near deploy --accountId <registry> --wasmFile intel_registry.wasm`
+    /// 2. `near call <registry> migrate '{}' --accountId <registry>`
+    ///
+    /// Proofs are rewritten with the new fields and the type, status, source and
+    /// order indexes are rebuilt. Attestor stats start empty. The original layout
+    /// kept no source controllers, so none exist until the owner assigns them with
+    /// `set_source_controller`.
+    #[init(ignore_state)]
+    #[private]
+    pub fn migrate() -> Self {
+        let mut old: OldIntelRegistry = env::state_read().expect("No state to migrate");
+        let old_proofs: Vec<(String, OldProofCommitment)> = old.proofs.iter().collect();
+        // Old values can't be read back as the new type, so drop them and
+        // rewrite every proof under the same prefix
+        old.proofs.clear();
+
+        let mut registry = Self {
+            proofs: UnorderedMap::new(StorageKey::Proofs),
+            attestations: old.attestations,
+            source_stats: old.source_stats,
+            intel_proofs: old.intel_proofs,
+            total_proofs: old.total_proofs,
+            total_attestations: old.total_attestations,
+            owner: old.owner,
+            confidence_mode: ConfidenceMode::Mean,
+            min_attest_stake: 0,
+            attestation_stakes: LookupMap::new(StorageKey::AttestationStakes),
+            proofs_by_type: LookupMap::new(StorageKey::ProofsByType),
+            proofs_by_status: LookupMap::new(StorageKey::ProofsByStatus),
+            status_positions: LookupMap::new(StorageKey::StatusPositions),
+            proof_order: Vector::new(StorageKey::ProofOrder),
+            attestor_stats: LookupMap::new(StorageKey::AttestorStats),
+            verified_threshold: 70,
+            min_attestations_for_verified: DEFAULT_MIN_ATTESTATIONS_FOR_VERIFIED,
+            refute_threshold: 30,
+            source_proofs: LookupMap::new(StorageKey::SourceProofs),
+            source_controllers: LookupMap::new(StorageKey::SourceControllers),
+            proof_links: LookupMap::new(StorageKey::ProofLinks),
+            disputes: LookupMap::new(StorageKey::Disputes),
+            dispute_window_ns: DEFAULT_DISPUTE_WINDOW_NS,
+            reputation_half_life_blocks: DEFAULT_REPUTATION_HALF_LIFE_BLOCKS,
+            org_delegates: LookupMap::new(StorageKey::OrgDelegates),
+            pending_owner: None,
+        };
+
+        for (proof_id, old_proof) in old_proofs {
+            let counted_verified = old_proof.status == VerificationStatus::Verified;
+            let proof = ProofCommitment {
+                proof_id: old_proof.proof_id,
+                commitment: old_proof.commitment,
+                proof_type: old_proof.proof_type,
+                source_hash: old_proof.source_hash,
+                intel_hash: old_proof.intel_hash,
+                public_inputs_hash: old_proof.public_inputs_hash,
+                block_height: old_proof.block_height,
+                timestamp_ns: old_proof.timestamp_ns,
+                metadata: old_proof.metadata,
+                status: old_proof.status,
+                attestation_count: old_proof.attestation_count,
+                avg_confidence: old_proof.avg_confidence,
+                expires_at: None,
+                corroboration_count: 0,
+                counted_verified,
+                // Attestor stats start empty, so credit on the next attestation
+                credited_verified: false,
+            };
+            registry.proofs.insert(&proof_id, &proof);
+            registry.index_proof(&proof);
         }
+
+        env::log_str(&format!("Migrated {} proofs", registry.proofs.len()));
+        registry
     }
 
     /// Register a new proof commitment
@@ -177,6 +468,7 @@ impl IntelRegistry {
     /// * `intel_hash` - Hash of intel submission
     /// * `public_inputs_hash` - Hash of public inputs
     /// * `metadata` - Optional JSON metadata (max 500 chars)
+    /// * `expires_at` - Optional expiry timestamp in nanoseconds
     #[payable]
     pub fn register_proof(
         &mut self,
@@ -187,72 +479,51 @@ impl IntelRegistry {
         intel_hash: String,
         public_inputs_hash: String,
         metadata: Option<String>,
+        expires_at: Option<U64>,
     ) -> ProofCommitment {
-        // Validate inputs
-        assert!(proof_id.len() <= 64, "proof_id too long");
-        assert!(commitment.len() == 64, "commitment must be 64 hex chars (SHA-256)");
-        assert!(source_hash.len() == 64, "source_hash must be 64 hex chars");
-        assert!(intel_hash.len() == 64, "intel_hash must be 64 hex chars");
-        assert!(public_inputs_hash.len() == 64, "public_inputs_hash must be 64 hex chars");
         assert!(!self.proofs.get(&proof_id).is_some(), "proof_id already exists");
-        
-        if let Some(ref m) = metadata {
-            assert!(m.len() <= 500, "metadata too long (max 500 chars)");
-        }
 
-        let proof = ProofCommitment {
-            proof_id: proof_id.clone(),
+        self.internal_register_proof(ProofInput {
+            proof_id,
             commitment,
             proof_type,
-            source_hash: source_hash.clone(),
-            intel_hash: intel_hash.clone(),
+            source_hash,
+            intel_hash,
             public_inputs_hash,
-            block_height: U64(env::block_height()),
-            timestamp_ns: U64(env::block_timestamp()),
             metadata,
-            status: VerificationStatus::Pending,
-            attestation_count: 0,
-            avg_confidence: 0,
-        };
-
-        // Store proof
-        self.proofs.insert(&proof_id, &proof);
-        self.total_proofs += 1;
-
-        // Initialize attestations vector
-        self.attestations.insert(
-            &proof_id,
-            &Vector::new(StorageKey::AttestationVector { proof_id: proof_id.clone() }),
-        );
+            expires_at,
+        })
+    }
 
-        // Link intel to proof
-        let mut intel_proof_ids = self.intel_proofs
-            .get(&intel_hash)
-            .unwrap_or_else(|| Vector::new(StorageKey::IntelProofVector { intel_hash: intel_hash.clone() }));
-        intel_proof_ids.push(&proof_id);
-        self.intel_proofs.insert(&intel_hash, &intel_proof_ids);
+    /// Register several proofs in one transaction
+    ///
+    /// Proofs whose `proof_id` already exists (in storage or earlier in the batch)
+    /// are skipped with a `proof_skipped` event instead of aborting the batch.
+    #[payable]
+    pub fn register_proofs_batch(&mut self, proofs: Vec<ProofInput>) -> Vec<ProofCommitment> {
+        assert!(!proofs.is_empty(), "empty batch");
+        assert!(proofs.len() <= 20, "batch too large (max 20)");
 
-        // Update source stats
-        let mut stats = self.source_stats.get(&source_hash).unwrap_or_default();
-        if stats.total_proofs == 0 {
-            stats.first_proof_height = U64(env::block_height());
+        let mut registered = Vec::with_capacity(proofs.len());
+        for input in proofs {
+            if self.proofs.get(&input.proof_id).is_some() {
+                Self::emit_event("proof_skipped", json!({
+                    "proof_id": input.proof_id,
+                    "reason": "duplicate proof_id",
+                }));
+                continue;
+            }
+            registered.push(self.internal_register_proof(input));
         }
-        stats.total_proofs += 1;
-        stats.last_proof_height = U64(env::block_height());
-        self.source_stats.insert(&source_hash, &stats);
-
-        env::log_str(&format!(
-            "Proof registered: {} by source {} for intel {}",
-            proof_id, &source_hash[..8], &intel_hash[..8]
-        ));
-
-        proof
+        registered
     }
 
     /// Add attestation to a proof
     /// 
     /// Any NEAR account can attest to verify or contest a proof.
     /// Multiple attestations from same account update the previous one.
+    /// The attached deposit is held as stake (at least `min_attest_stake`) and is
+    /// slashed if the proof is refuted after a confident (>= verified threshold) attestation.
     #[payable]
     pub fn attest(
         &mut self,
@@ -260,161 +531,483 @@ impl IntelRegistry {
         confidence: u8,
         note: Option<String>,
     ) {
-        assert!(confidence >= 1 && confidence <= 100, "confidence must be 1-100");
-        
-        if let Some(ref n) = note {
-            assert!(n.len() <= 200, "note too long (max 200 chars)");
-        }
+        self.internal_attest(env::predecessor_account_id(), proof_id, confidence, note, env::attached_deposit());
+    }
 
-        let mut proof = self.proofs.get(&proof_id).expect("proof not found");
-        let attestor = env::predecessor_account_id();
+    /// Attest to several proofs in one transaction, e.g. when importing a
+    /// verifier's historical judgments
+    ///
+    /// Items are `(proof_id, confidence, note)`. Unknown proof IDs are skipped with
+    /// an `attestation_skipped` event and returned; any other invalid item aborts
+    /// the batch. The attached deposit is split evenly as stake across the items
+    /// whose proofs exist; rounding dust, or everything if none exist, is refunded.
+    #[payable]
+    pub fn attest_batch(&mut self, items: Vec<(String, u8, Option<String>)>) -> Vec<String> {
+        assert!(!items.is_empty(), "empty batch");
+        assert!(items.len() <= 20, "batch too large (max 20)");
 
-        let attestation = Attestation {
-            attestor: attestor.clone(),
-            confidence,
-            block_height: U64(env::block_height()),
-            note,
-        };
+        let attestor = env::predecessor_account_id();
+        let deposit = env::attached_deposit();
+        let existing = items.iter().filter(|(proof_id, _, _)| self.proofs.get(proof_id).is_some()).count();
+        let stake_per_item = if existing > 0 { deposit / existing as Balance } else { 0 };
 
-        // Get attestations vector
-        let mut attestations_vec = self.attestations.get(&proof_id).expect("attestations not found");
-        
-        // Check for existing attestation from same account (update if exists)
-        let mut found = false;
-        let len = attestations_vec.len();
-        for i in 0..len {
-            if let Some(existing) = attestations_vec.get(i) {
-                if existing.attestor == attestor {
-                    attestations_vec.replace(i, &attestation);
-                    found = true;
-                    break;
-                }
+        let mut missing = Vec::new();
+        let mut staked: Balance = 0;
+        for (proof_id, confidence, note) in items {
+            if self.proofs.get(&proof_id).is_none() {
+                Self::emit_event("attestation_skipped", json!({
+                    "proof_id": proof_id,
+                    "reason": "proof not found",
+                }));
+                missing.push(proof_id);
+                continue;
             }
+            self.internal_attest(attestor.clone(), proof_id, confidence, note, stake_per_item);
+            staked += stake_per_item;
         }
-        
-        if !found {
-            attestations_vec.push(&attestation);
-            proof.attestation_count += 1;
-            self.total_attestations += 1;
-            
-            // Update source stats
-            let mut stats = self.source_stats.get(&proof.source_hash).unwrap_or_default();
-            stats.total_attestations += 1;
-            stats.confidence_sum += confidence as u64;
-            self.source_stats.insert(&proof.source_hash, &stats);
+
+        let refund = deposit - staked;
+        if refund > 0 {
+            Promise::new(attestor).transfer(refund);
         }
+        missing
+    }
 
-        // Recalculate average confidence
-        let mut total_confidence: u64 = 0;
-        let count = attestations_vec.len();
-        for i in 0..count {
-            if let Some(a) = attestations_vec.get(i) {
-                total_confidence += a.confidence as u64;
-            }
+    /// Attest on behalf of an organization the caller is a delegate of
+    ///
+    /// The attestation (and any attached stake) is recorded under `org_account`,
+    /// so the one-attestation-per-attestor rule applies per organization.
+    #[payable]
+    pub fn attest_as(
+        &mut self,
+        org_account: AccountId,
+        proof_id: String,
+        confidence: u8,
+        note: Option<String>,
+    ) {
+        let caller = env::predecessor_account_id();
+        let is_delegate = self.org_delegates
+            .get(&org_account)
+            .map(|d| d.contains(&caller))
+            .unwrap_or(false);
+        assert!(is_delegate, "not a delegate of this organization");
+
+        self.internal_attest(org_account, proof_id, confidence, note, env::attached_deposit());
+    }
+
+    /// Allow an account to attest on behalf of an organization (owner or org)
+    pub fn register_delegate(&mut self, org_account: AccountId, delegate_account: AccountId) {
+        self.assert_owner_or(&org_account);
+
+        let mut delegates = self.org_delegates
+            .get(&org_account)
+            .unwrap_or_else(|| UnorderedSet::new(StorageKey::OrgDelegateSet { org_account: org_account.clone() }));
+        delegates.insert(&delegate_account);
+        self.org_delegates.insert(&org_account, &delegates);
+
+        env::log_str(&format!("Delegate registered: {} for {}", delegate_account, org_account));
+    }
+
+    /// Revoke an organization delegate (owner or org)
+    pub fn revoke_delegate(&mut self, org_account: AccountId, delegate_account: AccountId) {
+        self.assert_owner_or(&org_account);
+
+        let mut delegates = self.org_delegates.get(&org_account).expect("no delegates for organization");
+        assert!(delegates.remove(&delegate_account), "not a delegate of this organization");
+        self.org_delegates.insert(&org_account, &delegates);
+
+        env::log_str(&format!("Delegate revoked: {} for {}", delegate_account, org_account));
+    }
+
+    /// Withdraw the caller's attestation from a proof
+    ///
+    /// Reverses the attestation's contribution to the proof and source stats.
+    pub fn retract_attestation(&mut self, proof_id: String) {
+        let mut proof = self.proofs.get(&proof_id).expect("proof not found");
+        assert!(proof.status != VerificationStatus::Refuted, "proof is refuted");
+        let attestor = env::predecessor_account_id();
+        let mut attestations_vec = self.attestations.get(&proof_id).expect("attestations not found");
+
+        let index = (0..attestations_vec.len())
+            .find(|&i| attestations_vec.get(i).map(|a| a.attestor == attestor).unwrap_or(false))
+            .expect("no attestation to retract");
+        let removed = attestations_vec.swap_remove(index);
+
+        // Return the stake for the withdrawn attestation
+        if let Some(stake) = self.attestation_stakes.remove(&(proof_id.clone(), attestor.clone())) {
+            Promise::new(attestor.clone()).transfer(stake);
         }
-        proof.avg_confidence = (total_confidence / count) as u8;
 
-        // Update verification status
-        proof.status = if proof.avg_confidence >= 70 {
-            VerificationStatus::Verified
-        } else if proof.attestation_count > 0 {
-            VerificationStatus::Contested
-        } else {
-            VerificationStatus::Pending
-        };
+        proof.attestation_count -= 1;
+        self.total_attestations = self.total_attestations.saturating_sub(1);
+        let previous_status = proof.status.clone();
+        self.update_confidence(&mut proof, &attestations_vec);
+        self.move_status(&proof_id, &previous_status, &proof.status);
 
-        // Update verified count if newly verified
-        if proof.status == VerificationStatus::Verified {
-            let mut stats = self.source_stats.get(&proof.source_hash).unwrap_or_default();
-            stats.verified_count += 1;
-            self.source_stats.insert(&proof.source_hash, &stats);
+        // Reverse source stats contributions
+        let mut stats = self.source_stats.get(&proof.source_hash).unwrap_or_default();
+        stats.total_attestations = stats.total_attestations.saturating_sub(1);
+        stats.confidence_sum = stats.confidence_sum.saturating_sub(removed.confidence as u64);
+        self.source_stats.insert(&proof.source_hash, &stats);
+        self.sync_verified_count(&mut proof);
+        if proof.credited_verified {
+            self.record_verified_outcome(&removed, true);
+        } else {
+            self.settle_verified(&mut proof, &attestations_vec);
         }
 
         self.attestations.insert(&proof_id, &attestations_vec);
         self.proofs.insert(&proof_id, &proof);
 
         env::log_str(&format!(
-            "Attestation added: {} attested {} confidence to proof {}",
-            attestor, confidence, proof_id
+            "Attestation retracted: {} from proof {}",
+            attestor, proof_id
         ));
     }
 
-    /// Mark a proof as refuted (admin only or with sufficient counter-attestations)
+    /// Start refuting a proof (admin only or with sufficient counter-attestations)
+    ///
+    /// Kept for existing callers; opens a dispute exactly like `propose_refutation`,
+    /// so the proof is only refuted by `finalize_refutation` after the window.
     pub fn refute_proof(&mut self, proof_id: String, reason: String) {
-        let caller = env::predecessor_account_id();
+        self.propose_refutation(proof_id, reason);
+    }
+
+    /// Open a dispute window before refuting a proof
+    ///
+    /// Requires the owner or a low-confidence consensus. The source can
+    /// attract fresh attestations until the deadline.
+    pub fn propose_refutation(&mut self, proof_id: String, reason: String) {
         let mut proof = self.proofs.get(&proof_id).expect("proof not found");
-        
-        // Only owner or if avg confidence < 30 with >= 3 attestations
-        let can_refute = caller == self.owner || 
-            (proof.attestation_count >= 3 && proof.avg_confidence < 30);
-        
-        assert!(can_refute, "not authorized to refute");
+        self.assert_can_refute(&proof);
         assert!(reason.len() <= 500, "reason too long");
+        assert!(
+            proof.status != VerificationStatus::Refuted && proof.status != VerificationStatus::UnderDispute,
+            "proof already refuted or disputed"
+        );
 
-        proof.status = VerificationStatus::Refuted;
+        let deadline = U64(env::block_timestamp() + self.dispute_window_ns);
+        let previous_status = proof.status.clone();
+        proof.status = VerificationStatus::UnderDispute;
+        self.sync_verified_count(&mut proof);
         self.proofs.insert(&proof_id, &proof);
+        self.move_status(&proof_id, &previous_status, &proof.status);
+        self.disputes.insert(&proof_id, &Dispute { reason: reason.clone(), deadline });
 
-        // Update source stats
-        let mut stats = self.source_stats.get(&proof.source_hash).unwrap_or_default();
-        stats.refuted_count += 1;
-        self.source_stats.insert(&proof.source_hash, &stats);
-
-        env::log_str(&format!("Proof {} refuted: {}", proof_id, reason));
+        env::log_str(&format!(
+            "Refutation proposed for {} (deadline {}): {}",
+            proof_id, deadline.0, reason
+        ));
     }
 
-    // ============ VIEW METHODS ============
+    /// Settle a dispute once its window has closed
+    ///
+    /// Refutes the proof if confidence is still below the verified threshold,
+    /// otherwise dismisses the dispute. Returns whether the proof was refuted.
+    pub fn finalize_refutation(&mut self, proof_id: String) -> bool {
+        let mut proof = self.proofs.get(&proof_id).expect("proof not found");
+        let dispute = self.disputes.get(&proof_id).expect("no open dispute");
+        assert!(env::block_timestamp() >= dispute.deadline.0, "dispute window still open");
 
-    /// Get a proof by ID
-    pub fn get_proof(&self, proof_id: String) -> Option<ProofCommitment> {
-        self.proofs.get(&proof_id)
-    }
+        self.disputes.remove(&proof_id);
 
-    /// Get proof with all attestations
-    pub fn get_proof_with_attestations(&self, proof_id: String) -> Option<ProofWithAttestations> {
-        let proof = self.proofs.get(&proof_id)?;
-        let attestations_vec = self.attestations.get(&proof_id)?;
-        
-        let mut attestations = Vec::new();
-        for i in 0..attestations_vec.len() {
-            if let Some(a) = attestations_vec.get(i) {
-                attestations.push(a);
-            }
+        if proof.avg_confidence < self.verified_threshold {
+            self.internal_refute(proof_id, proof, dispute.reason);
+            return true;
         }
-        
-        Some(ProofWithAttestations { proof, attestations })
+
+        // Confidence recovered: restore the attestation-derived status
+        let previous_status = proof.status.clone();
+        proof.status = VerificationStatus::Pending;
+        let attestations_vec = self.attestations.get(&proof_id).expect("attestations not found");
+        self.update_confidence(&mut proof, &attestations_vec);
+        self.sync_verified_count(&mut proof);
+        self.settle_verified(&mut proof, &attestations_vec);
+        self.proofs.insert(&proof_id, &proof);
+        self.move_status(&proof_id, &previous_status, &proof.status);
+
+        env::log_str(&format!("Dispute dismissed for {}", proof_id));
+        false
     }
 
-    /// Get all proofs for an intel hash
-    pub fn get_intel_proofs(&self, intel_hash: String) -> Vec<ProofCommitment> {
-        let proof_ids = match self.intel_proofs.get(&intel_hash) {
-            Some(v) => v,
-            None => return vec![],
-        };
-        
-        let mut proofs = Vec::new();
-        for i in 0..proof_ids.len() {
-            if let Some(proof_id) = proof_ids.get(i) {
-                if let Some(proof) = self.proofs.get(&proof_id) {
-                    proofs.push(proof);
-                }
-            }
-        }
-        proofs
+    /// Set the dispute window length in nanoseconds (owner only)
+    pub fn set_dispute_window(&mut self, window_ns: U64) {
+        assert_eq!(env::predecessor_account_id(), self.owner, "only owner");
+        self.dispute_window_ns = window_ns.0;
     }
 
-    /// Get source statistics
-    pub fn get_source_stats(&self, source_hash: String) -> Option<SourceStats> {
-        self.source_stats.get(&source_hash)
+    /// Set the reputation half-life in blocks (owner only, 0 disables decay)
+    pub fn set_reputation_half_life(&mut self, blocks: U64) {
+        assert_eq!(env::predecessor_account_id(), self.owner, "only owner");
+        self.reputation_half_life_blocks = blocks.0;
     }
 
-    /// Calculate source reputation score (0-100)
-    pub fn get_source_reputation(&self, source_hash: String) -> u8 {
+    /// Propose a new owner (owner only); takes effect once they call `accept_ownership`
+    ///
+    /// A later proposal replaces the pending one.
+    pub fn propose_owner(&mut self, new_owner: AccountId) {
+        assert_eq!(env::predecessor_account_id(), self.owner, "only owner");
+        Self::emit_event("owner_proposed", json!({
+            "owner": self.owner,
+            "pending_owner": new_owner,
+        }));
+        self.pending_owner = Some(new_owner);
+    }
+
+    /// Become the owner (pending owner only)
+    pub fn accept_ownership(&mut self) {
+        let caller = env::predecessor_account_id();
+        assert!(self.pending_owner.as_ref() == Some(&caller), "only pending owner");
+        Self::emit_event("owner_changed", json!({
+            "previous_owner": self.owner,
+            "owner": caller,
+        }));
+        self.owner = caller;
+        self.pending_owner = None;
+    }
+
+    /// Assign the account that controls a source (owner only)
+    ///
+    /// Registering proofs is permissionless, so control is never inferred from it.
+    pub fn set_source_controller(&mut self, source_hash: String, account_id: AccountId) {
+        assert_eq!(env::predecessor_account_id(), self.owner, "only owner");
+        self.source_controllers.insert(&source_hash, &account_id);
+    }
+
+    /// Link two proofs with a relation (e.g. "corroborates")
+    ///
+    /// Callable by the owner or the controller of either proof's source.
+    /// Linking proofs of the same intel from different sources counts as corroboration.
+    pub fn link_proofs(&mut self, proof_id_a: String, proof_id_b: String, relation: String) {
+        assert!(proof_id_a != proof_id_b, "cannot link a proof to itself");
+        assert!(relation.len() <= 64, "relation too long (max 64 chars)");
+
+        let mut proof_a = self.proofs.get(&proof_id_a).expect("proof not found");
+        let mut proof_b = self.proofs.get(&proof_id_b).expect("proof not found");
+
+        let caller = env::predecessor_account_id();
+        let is_controller = |source_hash: &String| {
+            self.source_controllers.get(source_hash).map(|c| c == caller).unwrap_or(false)
+        };
+        assert!(
+            caller == self.owner || is_controller(&proof_a.source_hash) || is_controller(&proof_b.source_hash),
+            "not authorized to link proofs"
+        );
+
+        let mut links_a = self.proof_links
+            .get(&proof_id_a)
+            .unwrap_or_else(|| Vector::new(StorageKey::ProofLinkVector { proof_id: proof_id_a.clone() }));
+        assert!(!links_a.iter().any(|(id, _)| id == proof_id_b), "proofs already linked");
+        let mut links_b = self.proof_links
+            .get(&proof_id_b)
+            .unwrap_or_else(|| Vector::new(StorageKey::ProofLinkVector { proof_id: proof_id_b.clone() }));
+
+        links_a.push(&(proof_id_b.clone(), relation.clone()));
+        links_b.push(&(proof_id_a.clone(), relation.clone()));
+        self.proof_links.insert(&proof_id_a, &links_a);
+        self.proof_links.insert(&proof_id_b, &links_b);
+
+        // Independent sources backing the same intel
+        if proof_a.intel_hash == proof_b.intel_hash && proof_a.source_hash != proof_b.source_hash {
+            proof_a.corroboration_count += 1;
+            proof_b.corroboration_count += 1;
+            self.proofs.insert(&proof_id_a, &proof_a);
+            self.proofs.insert(&proof_id_b, &proof_b);
+        }
+
+        env::log_str(&format!(
+            "Proofs linked: {} <-> {} ({})",
+            proof_id_a, proof_id_b, relation
+        ));
+    }
+
+    /// Reclaim stake on a refuted proof that was not slashed
+    pub fn claim_attestation_refund(&mut self, proof_id: String) -> Promise {
+        let proof = self.proofs.get(&proof_id).expect("proof not found");
+        assert!(proof.status == VerificationStatus::Refuted, "proof not finalized");
+
+        let attestor = env::predecessor_account_id();
+        let stake = self.attestation_stakes
+            .remove(&(proof_id.clone(), attestor.clone()))
+            .expect("no refundable stake");
+
+        env::log_str(&format!("Stake refunded: {} on proof {}", attestor, proof_id));
+
+        Promise::new(attestor).transfer(stake)
+    }
+
+    /// Set the minimum stake required to attest (owner only)
+    pub fn set_min_attest_stake(&mut self, amount: U128) {
+        assert_eq!(env::predecessor_account_id(), self.owner, "only owner");
+        self.min_attest_stake = amount.0;
+    }
+
+    /// Set the confidence required for Verified status (owner only)
+    pub fn set_verified_threshold(&mut self, threshold: u8) {
+        assert_eq!(env::predecessor_account_id(), self.owner, "only owner");
+        assert!(threshold <= 100, "threshold must be 0-100");
+        assert!(self.refute_threshold < threshold, "refute threshold must be below verified threshold");
+        self.verified_threshold = threshold;
+    }
+
+    /// Set how many attestations a proof needs before it can be Verified (owner only)
+    pub fn set_min_attestations_for_verified(&mut self, count: u32) {
+        assert_eq!(env::predecessor_account_id(), self.owner, "only owner");
+        assert!(count >= 1, "at least one attestation required");
+        self.min_attestations_for_verified = count;
+    }
+
+    /// Set the confidence below which consensus can refute (owner only)
+    pub fn set_refute_threshold(&mut self, threshold: u8) {
+        assert_eq!(env::predecessor_account_id(), self.owner, "only owner");
+        assert!(threshold < self.verified_threshold, "refute threshold must be below verified threshold");
+        self.refute_threshold = threshold;
+    }
+
+    /// Set how attestation confidences are aggregated (owner only)
+    pub fn set_confidence_mode(&mut self, mode: ConfidenceMode) {
+        assert_eq!(env::predecessor_account_id(), self.owner, "only owner");
+        self.confidence_mode = mode;
+    }
+
+    // ============ VIEW METHODS ============
+
+    /// Get a proof by ID
+    pub fn get_proof(&self, proof_id: String) -> Option<ProofCommitment> {
+        self.proofs.get(&proof_id)
+    }
+
+    /// Get proof with all attestations
+    pub fn get_proof_with_attestations(&self, proof_id: String) -> Option<ProofWithAttestations> {
+        let proof = self.proofs.get(&proof_id)?;
+        let attestations_vec = self.attestations.get(&proof_id)?;
+        
+        let mut attestations = Vec::new();
+        for i in 0..attestations_vec.len() {
+            if let Some(a) = attestations_vec.get(i) {
+                attestations.push(a);
+            }
+        }
+        
+        Some(ProofWithAttestations { proof, attestations })
+    }
+
+    /// Get a page of a proof's attestations (at most 100 per call)
+    /// Counts and confidence stay on the proof itself
+    pub fn get_attestations(&self, proof_id: String, from_index: u64, limit: u64) -> Vec<Attestation> {
+        let attestations = match self.attestations.get(&proof_id) {
+            Some(v) => v,
+            None => return vec![],
+        };
+        let end = std::cmp::min(from_index.saturating_add(limit.min(100)), attestations.len());
+        (from_index..end)
+            .filter_map(|i| attestations.get(i))
+            .collect()
+    }
+
+    /// Get attestor/note pairs for a proof, skipping attestations without a note
+    ///
+    /// Cheaper than `get_attestations` when only the commentary is needed.
+    /// Keyword search over notes is left to the client.
+    pub fn get_attestation_notes(&self, proof_id: String) -> Vec<(AccountId, String)> {
+        let attestations = match self.attestations.get(&proof_id) {
+            Some(v) => v,
+            None => return vec![],
+        };
+        attestations
+            .iter()
+            .filter_map(|a| a.note.map(|note| (a.attestor, note)))
+            .collect()
+    }
+
+    /// Get all proofs for an intel hash
+    pub fn get_intel_proofs(&self, intel_hash: String) -> Vec<ProofCommitment> {
+        let proof_ids = match self.intel_proofs.get(&intel_hash) {
+            Some(v) => v,
+            None => return vec![],
+        };
+        
+        let mut proofs = Vec::new();
+        for i in 0..proof_ids.len() {
+            if let Some(proof_id) = proof_ids.get(i) {
+                if let Some(proof) = self.proofs.get(&proof_id) {
+                    proofs.push(proof);
+                }
+            }
+        }
+        proofs
+    }
+
+    /// Get proofs of a given type (paginated, registration order)
+    pub fn get_proofs_by_type(&self, proof_type: ProofType, from_index: u64, limit: u64) -> Vec<ProofCommitment> {
+        match self.proofs_by_type.get(&proof_type.as_str().to_string()) {
+            Some(ids) => self.resolve_proof_page(&ids, from_index, limit),
+            None => vec![],
+        }
+    }
+
+    /// Get proofs with a given verification status (paginated)
+    pub fn get_proofs_by_status(&self, status: VerificationStatus, from_index: u64, limit: u64) -> Vec<ProofCommitment> {
+        match self.proofs_by_status.get(&status.as_str().to_string()) {
+            Some(ids) => self.resolve_proof_page(&ids, from_index, limit),
+            None => vec![],
+        }
+    }
+
+    /// Get all proofs submitted by a source (paginated, registration order)
+    pub fn get_source_proofs(&self, source_hash: String, from_index: u64, limit: u64) -> Vec<ProofCommitment> {
+        match self.source_proofs.get(&source_hash) {
+            Some(ids) => self.resolve_proof_page(&ids, from_index, limit),
+            None => vec![],
+        }
+    }
+
+    /// Get proofs linked to a proof with their relations
+    pub fn get_linked_proofs(&self, proof_id: String) -> Vec<(String, String)> {
+        self.proof_links
+            .get(&proof_id)
+            .map(|links| links.to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Get the account controlling a source
+    pub fn get_source_controller(&self, source_hash: String) -> Option<AccountId> {
+        self.source_controllers.get(&source_hash)
+    }
+
+    /// Get source statistics
+    pub fn get_source_stats(&self, source_hash: String) -> Option<SourceStats> {
+        self.source_stats.get(&source_hash)
+    }
+
+    /// Calculate source reputation score (0-100)
+    pub fn get_source_reputation(&self, source_hash: String) -> u8 {
         let stats = match self.source_stats.get(&source_hash) {
             Some(s) => s,
             None => return 0,
         };
 
-        if stats.total_proofs == 0 {
+        // Expired proofs no longer count toward reputation
+        let mut expired_total = 0u64;
+        let mut expired_verified = 0u64;
+        if let Some(proof_ids) = self.source_proofs.get(&source_hash) {
+            for i in 0..proof_ids.len() {
+                let proof = match proof_ids.get(i).and_then(|id| self.proofs.get(&id)) {
+                    Some(p) => p,
+                    None => continue,
+                };
+                if !Self::is_fresh(&proof) {
+                    expired_total += 1;
+                    if proof.status == VerificationStatus::Verified {
+                        expired_verified += 1;
+                    }
+                }
+            }
+        }
+        let total_proofs = stats.total_proofs.saturating_sub(expired_total);
+        let verified_count = stats.verified_count.saturating_sub(expired_verified);
+
+        if total_proofs == 0 {
             return 0;
         }
 
@@ -423,20 +1016,56 @@ impl IntelRegistry {
         // Attestation bonus: min(avg_confidence, 30)
         // Refuted penalty: -(refuted / total) * 30
         // Activity bonus: min(total_proofs, 10)
+        // Base and activity bonus halve every `reputation_half_life_blocks`
+        // since the source's last proof
         
-        let verified_ratio = (stats.verified_count as f64 / stats.total_proofs as f64) * 50.0;
+        let recency = if self.reputation_half_life_blocks == 0 {
+            1.0
+        } else {
+            let idle_blocks = env::block_height().saturating_sub(stats.last_proof_height.0);
+            0.5f64.powf(idle_blocks as f64 / self.reputation_half_life_blocks as f64)
+        };
+        let verified_ratio = (verified_count as f64 / total_proofs as f64) * 50.0 * recency;
         let avg_conf = if stats.total_attestations > 0 {
             (stats.confidence_sum as f64 / stats.total_attestations as f64).min(30.0)
         } else {
             0.0
         };
-        let refuted_penalty = (stats.refuted_count as f64 / stats.total_proofs as f64) * 30.0;
-        let activity_bonus = (stats.total_proofs as f64).min(10.0);
+        let refuted_penalty = (stats.refuted_count as f64 / total_proofs as f64) * 30.0;
+        let activity_bonus = (total_proofs as f64).min(10.0) * recency;
 
         let score = verified_ratio + avg_conf - refuted_penalty + activity_bonus;
         score.max(0.0).min(100.0) as u8
     }
 
+    /// Get an attestor's track record
+    pub fn get_attestor_stats(&self, account_id: AccountId) -> Option<AttestorStats> {
+        self.attestor_stats.get(&account_id)
+    }
+
+    /// Calculate attestor reputation score (0-100)
+    ///
+    /// Accuracy against final outcomes, scaled down until 10 outcomes are known.
+    pub fn get_attestor_reputation(&self, account_id: AccountId) -> u8 {
+        let stats = match self.attestor_stats.get(&account_id) {
+            Some(s) => s,
+            None => return 0,
+        };
+
+        let resolved = stats.agreements + stats.disagreements;
+        if resolved == 0 {
+            return 0;
+        }
+
+        let accuracy = stats.agreements * 100 / resolved;
+        (accuracy * resolved.min(10) / 10) as u8
+    }
+
+    /// Check whether a proof exists and has not expired
+    pub fn is_proof_fresh(&self, proof_id: String) -> bool {
+        self.proofs.get(&proof_id).map(|p| Self::is_fresh(&p)).unwrap_or(false)
+    }
+
     /// Verify a commitment matches provided data
     /// 
     /// Client computes: sha256(proof || publicInputs || sourceId)
@@ -448,135 +1077,2027 @@ impl IntelRegistry {
         }
     }
 
+    /// Recompute a commitment on-chain from its parts and compare it to the stored one
+    ///
+    /// Parts are concatenated as UTF-8 strings before hashing. The computed hash is
+    /// returned even on mismatch (or unknown proof) so clients can debug their hashing.
+    pub fn verify_commitment_parts(
+        &self,
+        proof_id: String,
+        proof_hash: String,
+        public_inputs: String,
+        source_id: String,
+    ) -> CommitmentCheck {
+        let preimage = [proof_hash.as_bytes(), public_inputs.as_bytes(), source_id.as_bytes()].concat();
+        let computed_commitment: String = env::sha256(&preimage)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let matches = match self.proofs.get(&proof_id) {
+            Some(proof) => proof.commitment.eq_ignore_ascii_case(&computed_commitment),
+            None => false,
+        };
+        CommitmentCheck { matches, computed_commitment }
+    }
+
     /// Get total statistics
     pub fn get_stats(&self) -> (u64, u64) {
         (self.total_proofs, self.total_attestations)
     }
 
-    /// Get recent proofs (last N)
+    /// Get recent proofs (last N, newest first)
     pub fn get_recent_proofs(&self, limit: u64) -> Vec<ProofCommitment> {
-        let mut proofs: Vec<ProofCommitment> = self.proofs.values().collect();
-        proofs.sort_by(|a, b| b.block_height.0.cmp(&a.block_height.0));
-        proofs.truncate(limit as usize);
-        proofs
+        let len = self.proof_order.len();
+        let start = len.saturating_sub(limit);
+        (start..len)
+            .rev()
+            .filter_map(|i| self.proof_order.get(i))
+            .filter_map(|id| self.proofs.get(&id))
+            .collect()
+    }
+
+    /// Get proofs registered between two block heights, inclusive (paginated)
+    ///
+    /// Registration order is block-height order, so the scan starts at the first
+    /// proof at `min_height` and stops at the first one past `max_height`.
+    pub fn get_proofs_in_range(&self, min_height: u64, max_height: u64, from_index: u64, limit: u64) -> Vec<ProofCommitment> {
+        assert!(min_height <= max_height, "min_height must not exceed max_height");
+        let height_at = |i: u64| {
+            self.proof_order
+                .get(i)
+                .and_then(|id| self.proofs.get(&id))
+                .map(|p| p.block_height.0)
+                .unwrap_or(u64::MAX)
+        };
+
+        // Binary search for the first proof at or above min_height
+        let (mut lo, mut hi) = (0, self.proof_order.len());
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if height_at(mid) < min_height {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        (lo.saturating_add(from_index)..self.proof_order.len())
+            .filter_map(|i| self.proof_order.get(i))
+            .filter_map(|id| self.proofs.get(&id))
+            .take_while(|p| p.block_height.0 <= max_height)
+            .take(limit as usize)
+            .collect()
     }
 
     /// Get owner
     pub fn get_owner(&self) -> AccountId {
         self.owner.clone()
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use near_sdk::test_utils::VMContextBuilder;
-    use near_sdk::testing_env;
 
-    fn get_context(predecessor: AccountId) -> VMContextBuilder {
-        let mut builder = VMContextBuilder::new();
-        builder.predecessor_account_id(predecessor);
-        builder.block_height(100);
-        builder.block_timestamp(1_000_000_000);
-        builder
+    /// Get the proposed owner awaiting acceptance, if any
+    pub fn get_pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner.clone()
     }
 
-    fn test_commitment() -> String {
-        "a".repeat(64) // Valid 64-char hex
+    /// Get the minimum attestation stake
+    pub fn get_min_attest_stake(&self) -> U128 {
+        U128(self.min_attest_stake)
     }
 
-    #[test]
-    fn test_register_proof() {
-        let owner: AccountId = "owner.near".parse().unwrap();
-        let context = get_context(owner.clone());
-        testing_env!(context.build());
-
-        let mut contract = IntelRegistry::new(owner);
-        
-        let proof = contract.register_proof(
-            "proof-001".to_string(),
-            test_commitment(),
-            ProofType::LocationProximity,
-            test_commitment(),
-            test_commitment(),
-            test_commitment(),
-            Some("{\"radius_km\": 5}".to_string()),
-        );
+    /// Get the stake held for an attestation
+    pub fn get_attestation_stake(&self, proof_id: String, attestor: AccountId) -> U128 {
+        U128(self.attestation_stakes.get(&(proof_id, attestor)).unwrap_or(0))
+    }
 
-        assert_eq!(proof.proof_id, "proof-001");
-        assert_eq!(proof.status, VerificationStatus::Pending);
-        assert_eq!(contract.total_proofs, 1);
+    /// Get the (verified, refute) confidence thresholds
+    pub fn get_thresholds(&self) -> (u8, u8) {
+        (self.verified_threshold, self.refute_threshold)
     }
 
-    #[test]
-    fn test_attestation() {
-        let owner: AccountId = "owner.near".parse().unwrap();
-        let attestor: AccountId = "attestor.near".parse().unwrap();
-        
-        let mut context = get_context(owner.clone());
-        testing_env!(context.build());
+    /// Get the open dispute for a proof
+    pub fn get_dispute(&self, proof_id: String) -> Option<Dispute> {
+        self.disputes.get(&proof_id)
+    }
 
-        let mut contract = IntelRegistry::new(owner);
-        
-        contract.register_proof(
-            "proof-001".to_string(),
-            test_commitment(),
-            ProofType::LocationProximity,
-            test_commitment(),
-            test_commitment(),
-            test_commitment(),
-            None,
-        );
+    /// Check whether an account may attest on behalf of an organization
+    pub fn is_delegate(&self, org_account: AccountId, delegate_account: AccountId) -> bool {
+        self.org_delegates
+            .get(&org_account)
+            .map(|d| d.contains(&delegate_account))
+            .unwrap_or(false)
+    }
 
-        // Attest as different user
-        context = get_context(attestor);
-        testing_env!(context.build());
+    /// Get the attestation count required for Verified
+    pub fn get_min_attestations_for_verified(&self) -> u32 {
+        self.min_attestations_for_verified
+    }
 
-        contract.attest("proof-001".to_string(), 85, Some("Verified via satellite".to_string()));
+    /// Get the reputation half-life in blocks
+    pub fn get_reputation_half_life(&self) -> U64 {
+        U64(self.reputation_half_life_blocks)
+    }
 
-        let proof = contract.get_proof("proof-001".to_string()).unwrap();
-        assert_eq!(proof.attestation_count, 1);
-        assert_eq!(proof.avg_confidence, 85);
-        assert_eq!(proof.status, VerificationStatus::Verified);
+    /// Get the active confidence aggregation mode
+    pub fn get_confidence_mode(&self) -> ConfidenceMode {
+        self.confidence_mode.clone()
     }
 
-    #[test]
-    fn test_source_reputation() {
-        let owner: AccountId = "owner.near".parse().unwrap();
-        let attestor: AccountId = "attestor.near".parse().unwrap();
-        let source_hash = test_commitment();
-        
-        let mut context = get_context(owner.clone());
-        testing_env!(context.build());
+    /// Validate and store a proof, updating every index and source stats
+    fn internal_register_proof(&mut self, input: ProofInput) -> ProofCommitment {
+        let ProofInput {
+            proof_id,
+            commitment,
+            proof_type,
+            source_hash,
+            intel_hash,
+            public_inputs_hash,
+            metadata,
+            expires_at,
+        } = input;
 
-        let mut contract = IntelRegistry::new(owner);
+        // Validate inputs
+        assert!(proof_id.len() <= 64, "proof_id too long");
+        assert!(commitment.len() == 64, "commitment must be 64 hex chars (SHA-256)");
+        assert!(source_hash.len() == 64, "source_hash must be 64 hex chars");
+        assert!(intel_hash.len() == 64, "intel_hash must be 64 hex chars");
+        assert!(public_inputs_hash.len() == 64, "public_inputs_hash must be 64 hex chars");
         
-        // Register multiple proofs from same source
-        for i in 0..5 {
-            contract.register_proof(
-                format!("proof-{:03}", i),
-                test_commitment(),
-                ProofType::TimestampRange,
-                source_hash.clone(),
-                format!("{:064}", i), // Different intel hashes
-                test_commitment(),
-                None,
-            );
+        if let Some(ref m) = metadata {
+            assert!(m.len() <= 500, "metadata too long (max 500 chars)");
         }
-
-        // Attest to some
-        context = get_context(attestor);
-        testing_env!(context.build());
-
-        for i in 0..3 {
-            contract.attest(format!("proof-{:03}", i), 80, None);
+        if let Some(expiry) = expires_at {
+            assert!(expiry.0 > env::block_timestamp(), "expires_at must be in the future");
         }
 
-        let stats = contract.get_source_stats(source_hash.clone()).unwrap();
-        assert_eq!(stats.total_proofs, 5);
-        assert_eq!(stats.total_attestations, 3);
-
-        let reputation = contract.get_source_reputation(source_hash);
-        assert!(reputation > 50); // Should have decent reputation
+        let proof = ProofCommitment {
+            proof_id: proof_id.clone(),
+            commitment,
+            proof_type,
+            source_hash: source_hash.clone(),
+            intel_hash: intel_hash.clone(),
+            public_inputs_hash,
+            block_height: U64(env::block_height()),
+            timestamp_ns: U64(env::block_timestamp()),
+            metadata,
+            status: VerificationStatus::Pending,
+            attestation_count: 0,
+            avg_confidence: 0,
+            expires_at,
+            corroboration_count: 0,
+            counted_verified: false,
+            credited_verified: false,
+        };
+
+        // Store proof
+        self.proofs.insert(&proof_id, &proof);
+        self.index_proof(&proof);
+        self.total_proofs += 1;
+
+        // Initialize attestations vector
+        self.attestations.insert(
+            &proof_id,
+            &Vector::new(StorageKey::AttestationVector { proof_id: proof_id.clone() }),
+        );
+
+        // Link intel to proof
+        let mut intel_proof_ids = self.intel_proofs
+            .get(&intel_hash)
+            .unwrap_or_else(|| Vector::new(StorageKey::IntelProofVector { intel_hash: intel_hash.clone() }));
+        intel_proof_ids.push(&proof_id);
+        self.intel_proofs.insert(&intel_hash, &intel_proof_ids);
+
+        // Update source stats
+        let mut stats = self.source_stats.get(&source_hash).unwrap_or_default();
+        if stats.total_proofs == 0 {
+            stats.first_proof_height = U64(env::block_height());
+        }
+        stats.total_proofs += 1;
+        stats.last_proof_height = U64(env::block_height());
+        self.source_stats.insert(&source_hash, &stats);
+
+        env::log_str(&format!(
+            "Proof registered: {} by source {} for intel {}",
+            proof_id, &source_hash[..8], &intel_hash[..8]
+        ));
+        Self::emit_event("proof_registered", json!({
+            "proof_id": proof_id,
+            "proof_type": proof.proof_type.as_str(),
+            "source_hash": source_hash,
+            "intel_hash": intel_hash,
+            "status": proof.status.as_str(),
+        }));
+
+        proof
+    }
+
+    /// Record an attestation under `attestor` with `deposit` added to its stake,
+    /// updating confidence, stakes and stats
+    fn internal_attest(
+        &mut self,
+        attestor: AccountId,
+        proof_id: String,
+        confidence: u8,
+        note: Option<String>,
+        deposit: Balance,
+    ) {
+        assert!(confidence >= 1 && confidence <= 100, "confidence must be 1-100");
+        
+        if let Some(ref n) = note {
+            assert!(n.len() <= 200, "note too long (max 200 chars)");
+        }
+
+        let mut proof = self.proofs.get(&proof_id).expect("proof not found");
+        // Refutation is final; disputed proofs still take attestations since
+        // finalize_refutation settles on the confidence they produce
+        assert!(proof.status != VerificationStatus::Refuted, "proof is refuted");
+
+        // Record stake (updates add to the existing stake)
+        let stake_key = (proof_id.clone(), attestor.clone());
+        let stake = self.attestation_stakes.get(&stake_key).unwrap_or(0) + deposit;
+        assert!(stake >= self.min_attest_stake, "insufficient attestation stake");
+        if stake > 0 {
+            self.attestation_stakes.insert(&stake_key, &stake);
+        }
+
+        let attestation = Attestation {
+            attestor: attestor.clone(),
+            confidence,
+            block_height: U64(env::block_height()),
+            note,
+        };
+
+        // Get attestations vector
+        let mut attestations_vec = self.attestations.get(&proof_id).expect("attestations not found");
+        
+        // Check for existing attestation from same account (update if exists)
+        let mut replaced = None;
+        let len = attestations_vec.len();
+        for i in 0..len {
+            if let Some(existing) = attestations_vec.get(i) {
+                if existing.attestor == attestor {
+                    replaced = Some(attestations_vec.replace(i, &attestation));
+                    break;
+                }
+            }
+        }
+        
+        if replaced.is_none() {
+            attestations_vec.push(&attestation);
+            proof.attestation_count += 1;
+            self.total_attestations += 1;
+
+            let mut attestor_stats = self.attestor_stats.get(&attestor).unwrap_or_default();
+            attestor_stats.attestations_made += 1;
+            self.attestor_stats.insert(&attestor, &attestor_stats);
+            
+            // Update source stats
+            let mut stats = self.source_stats.get(&proof.source_hash).unwrap_or_default();
+            stats.total_attestations += 1;
+            stats.confidence_sum += confidence as u64;
+            self.source_stats.insert(&proof.source_hash, &stats);
+        }
+
+        let previous_status = proof.status.clone();
+        self.update_confidence(&mut proof, &attestations_vec);
+        self.move_status(&proof_id, &previous_status, &proof.status);
+
+        self.sync_verified_count(&mut proof);
+        if proof.credited_verified {
+            if let Some(old) = &replaced {
+                self.record_verified_outcome(old, true);
+            }
+            self.record_verified_outcome(&attestation, false);
+        } else {
+            self.settle_verified(&mut proof, &attestations_vec);
+        }
+
+        self.attestations.insert(&proof_id, &attestations_vec);
+        self.proofs.insert(&proof_id, &proof);
+
+        env::log_str(&format!(
+            "Attestation added: {} attested {} confidence to proof {}",
+            attestor, confidence, proof_id
+        ));
+        Self::emit_event("attestation_added", json!({
+            "proof_id": proof_id,
+            "attestor": attestor,
+            "confidence": confidence,
+            "avg_confidence": proof.avg_confidence,
+            "status": proof.status.as_str(),
+        }));
+    }
+
+    fn assert_owner_or(&self, account_id: &AccountId) {
+        let caller = env::predecessor_account_id();
+        assert!(caller == self.owner || &caller == account_id, "not authorized");
+    }
+
+    /// Authorize a refutation: owner, or consensus of >= 3 low-confidence attestations
+    fn assert_can_refute(&self, proof: &ProofCommitment) {
+        let can_refute = env::predecessor_account_id() == self.owner ||
+            (proof.attestation_count >= 3 && proof.avg_confidence < self.refute_threshold);
+        assert!(can_refute, "not authorized to refute");
+    }
+
+    /// Mark a proof Refuted and settle stakes, attestor records and source stats
+    fn internal_refute(&mut self, proof_id: String, mut proof: ProofCommitment, reason: String) {
+        // Settling again would re-credit every attestor and the source
+        assert!(proof.status != VerificationStatus::Refuted, "proof already refuted");
+
+        let previous_status = proof.status.clone();
+        proof.status = VerificationStatus::Refuted;
+        self.sync_verified_count(&mut proof);
+        let was_credited = proof.credited_verified;
+        proof.credited_verified = false;
+        self.proofs.insert(&proof_id, &proof);
+        self.move_status(&proof_id, &previous_status, &proof.status);
+        self.disputes.remove(&proof_id);
+
+        // Settle stakes and attestor track records: confident attestors were wrong
+        // and are slashed, doubters were right and are refunded.
+        // Anyone in between can claim their stake via claim_attestation_refund.
+        // Stakes settle on fixed bands rather than the owner-configurable thresholds,
+        // so a threshold change after staking can't alter an attestor's terms.
+        let mut slashed: Balance = 0;
+        if let Some(attestations_vec) = self.attestations.get(&proof_id) {
+            for i in 0..attestations_vec.len() {
+                let a = match attestations_vec.get(i) {
+                    Some(a) => a,
+                    None => continue,
+                };
+                // Refutation overrides an earlier Verified outcome
+                if was_credited {
+                    self.record_verified_outcome(&a, true);
+                }
+                let mut attestor_stats = self.attestor_stats.get(&a.attestor).unwrap_or_default();
+                if a.confidence >= self.verified_threshold {
+                    attestor_stats.disagreements += 1;
+                } else if a.confidence < self.refute_threshold {
+                    attestor_stats.agreements += 1;
+                }
+                self.attestor_stats.insert(&a.attestor, &attestor_stats);
+
+                let stake_key = (proof_id.clone(), a.attestor.clone());
+                if a.confidence >= SLASH_CONFIDENCE {
+                    slashed += self.attestation_stakes.remove(&stake_key).unwrap_or(0);
+                } else if a.confidence < REFUND_CONFIDENCE {
+                    if let Some(stake) = self.attestation_stakes.remove(&stake_key) {
+                        Promise::new(a.attestor.clone()).transfer(stake);
+                    }
+                }
+            }
+        }
+        if slashed > 0 {
+            Promise::new(self.owner.clone()).transfer(slashed);
+        }
+
+        // Update source stats
+        let mut stats = self.source_stats.get(&proof.source_hash).unwrap_or_default();
+        stats.refuted_count += 1;
+        self.source_stats.insert(&proof.source_hash, &stats);
+
+        env::log_str(&format!("Proof {} refuted: {}", proof_id, reason));
+        Self::emit_event("proof_refuted", json!({
+            "proof_id": proof_id,
+            "reason": reason,
+            "avg_confidence": proof.avg_confidence,
+            "status": proof.status.as_str(),
+        }));
+    }
+
+    /// Keep the source's `verified_count` in step with the proof's status
+    ///
+    /// Adjusts the count only when the proof enters or leaves Verified, so repeated
+    /// attestations on a Verified proof never count it twice.
+    fn sync_verified_count(&mut self, proof: &mut ProofCommitment) {
+        let is_verified = proof.status == VerificationStatus::Verified;
+        if is_verified == proof.counted_verified {
+            return;
+        }
+        let mut stats = self.source_stats.get(&proof.source_hash).unwrap_or_default();
+        if is_verified {
+            stats.verified_count += 1;
+        } else {
+            stats.verified_count = stats.verified_count.saturating_sub(1);
+        }
+        self.source_stats.insert(&proof.source_hash, &stats);
+        proof.counted_verified = is_verified;
+    }
+
+    /// Credit every attestor on a proof that has just reached Verified
+    ///
+    /// Happens once per proof; afterwards `internal_attest` and `retract_attestation`
+    /// keep the credit in step with individual attestations.
+    fn settle_verified(&mut self, proof: &mut ProofCommitment, attestations_vec: &Vector<Attestation>) {
+        if proof.credited_verified || proof.status != VerificationStatus::Verified {
+            return;
+        }
+        for a in attestations_vec.iter() {
+            self.record_verified_outcome(&a, false);
+        }
+        proof.credited_verified = true;
+    }
+
+    /// Record (or with `reverse`, undo) how an attestation compares with a Verified outcome:
+    /// confident attestors agreed, doubters disagreed, anyone in between is neutral
+    fn record_verified_outcome(&mut self, a: &Attestation, reverse: bool) {
+        let mut attestor_stats = self.attestor_stats.get(&a.attestor).unwrap_or_default();
+        let counter = if a.confidence >= self.verified_threshold {
+            &mut attestor_stats.agreements
+        } else if a.confidence < self.refute_threshold {
+            &mut attestor_stats.disagreements
+        } else {
+            return;
+        };
+        *counter = if reverse { counter.saturating_sub(1) } else { *counter + 1 };
+        self.attestor_stats.insert(&a.attestor, &attestor_stats);
+    }
+
+    /// Log an indexer-friendly event (NEP-297 envelope)
+    fn emit_event(event: &str, data: serde_json::Value) {
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"argus-intel\",\"version\":\"1.0.0\",\"event\":\"{}\",\"data\":[{}]}}",
+            event, data
+        ));
+    }
+
+    fn is_fresh(proof: &ProofCommitment) -> bool {
+        proof.expires_at.map(|e| env::block_timestamp() < e.0).unwrap_or(true)
+    }
+
+    /// Resolve a page of proof IDs from an index
+    fn resolve_proof_page(&self, ids: &Vector<String>, from_index: u64, limit: u64) -> Vec<ProofCommitment> {
+        let end = std::cmp::min(from_index.saturating_add(limit), ids.len());
+        (from_index..end)
+            .filter_map(|i| ids.get(i))
+            .filter_map(|id| self.proofs.get(&id))
+            .collect()
+    }
+
+    /// Add a proof to the order, source, type and status indexes
+    fn index_proof(&mut self, proof: &ProofCommitment) {
+        let proof_id = &proof.proof_id;
+        self.proof_order.push(proof_id);
+
+        let mut source_proof_ids = self.source_proofs
+            .get(&proof.source_hash)
+            .unwrap_or_else(|| Vector::new(StorageKey::SourceProofVector { source_hash: proof.source_hash.clone() }));
+        source_proof_ids.push(proof_id);
+        self.source_proofs.insert(&proof.source_hash, &source_proof_ids);
+
+        let type_key = proof.proof_type.as_str().to_string();
+        let mut type_proof_ids = self.proofs_by_type
+            .get(&type_key)
+            .unwrap_or_else(|| Vector::new(StorageKey::ProofsByTypeVector { proof_type: type_key.clone() }));
+        type_proof_ids.push(proof_id);
+        self.proofs_by_type.insert(&type_key, &type_proof_ids);
+        self.index_status(proof_id, &proof.status);
+    }
+
+    /// Add a proof to its status bucket
+    fn index_status(&mut self, proof_id: &String, status: &VerificationStatus) {
+        let key = status.as_str().to_string();
+        let mut bucket = self.proofs_by_status
+            .get(&key)
+            .unwrap_or_else(|| Vector::new(StorageKey::ProofsByStatusVector { status: key.clone() }));
+        self.status_positions.insert(proof_id, &bucket.len());
+        bucket.push(proof_id);
+        self.proofs_by_status.insert(&key, &bucket);
+    }
+
+    /// Move a proof between status buckets when its status changes
+    fn move_status(&mut self, proof_id: &String, from: &VerificationStatus, to: &VerificationStatus) {
+        if from == to {
+            return;
+        }
+
+        let key = from.as_str().to_string();
+        if let (Some(mut bucket), Some(position)) = (self.proofs_by_status.get(&key), self.status_positions.get(proof_id)) {
+            bucket.swap_remove(position);
+            // The last entry was swapped into the vacated slot
+            if let Some(moved) = bucket.get(position) {
+                self.status_positions.insert(&moved, &position);
+            }
+            self.proofs_by_status.insert(&key, &bucket);
+        }
+
+        self.index_status(proof_id, to);
+    }
+
+    /// Recalculate a proof's average confidence and verification status
+    fn update_confidence(&self, proof: &mut ProofCommitment, attestations_vec: &Vector<Attestation>) {
+        let count = attestations_vec.len();
+        if count == 0 {
+            proof.avg_confidence = 0;
+            proof.status = VerificationStatus::Pending;
+            return;
+        }
+
+        let mut confidences: Vec<u8> = Vec::with_capacity(count as usize);
+        let mut weighted_sum: u64 = 0;
+        let mut weight_total: u64 = 0;
+        for i in 0..count {
+            if let Some(a) = attestations_vec.get(i) {
+                if self.confidence_mode == ConfidenceMode::Weighted {
+                    let weight = self.get_attestor_reputation(a.attestor.clone()) as u64 + 1;
+                    weighted_sum += a.confidence as u64 * weight;
+                    weight_total += weight;
+                }
+                confidences.push(a.confidence);
+            }
+        }
+
+        proof.avg_confidence = match self.confidence_mode {
+            ConfidenceMode::Mean => {
+                let total: u64 = confidences.iter().map(|&c| c as u64).sum();
+                (total / confidences.len() as u64) as u8
+            }
+            ConfidenceMode::Median => {
+                confidences.sort_unstable();
+                let mid = confidences.len() / 2;
+                if confidences.len().is_multiple_of(2) {
+                    ((confidences[mid - 1] as u16 + confidences[mid] as u16) / 2) as u8
+                } else {
+                    confidences[mid]
+                }
+            }
+            ConfidenceMode::Weighted => (weighted_sum / weight_total) as u8,
+        };
+
+        // Disputed proofs keep their status until the dispute is settled
+        if proof.status == VerificationStatus::UnderDispute {
+            return;
+        }
+
+        // Update verification status
+        let enough_attestations = proof.attestation_count >= self.min_attestations_for_verified;
+        proof.status = if proof.avg_confidence >= self.verified_threshold && enough_attestations {
+            VerificationStatus::Verified
+        } else if proof.attestation_count > 0 {
+            VerificationStatus::Contested
+        } else {
+            VerificationStatus::Pending
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    fn get_context(predecessor: AccountId) -> VMContextBuilder {
+        let mut builder = VMContextBuilder::new();
+        builder.predecessor_account_id(predecessor);
+        builder.block_height(100);
+        builder.block_timestamp(1_000_000_000);
+        builder
+    }
+
+    fn test_commitment() -> String {
+        "a".repeat(64) // Valid 64-char hex
+    }
+
+    #[test]
+    fn test_register_proof() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let context = get_context(owner.clone());
+        testing_env!(context.build());
+
+        let mut contract = IntelRegistry::new(owner);
+        
+        let proof = contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::LocationProximity,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            Some("{\"radius_km\": 5}".to_string()),
+            None,
+        );
+
+        assert_eq!(proof.proof_id, "proof-001");
+        assert_eq!(proof.status, VerificationStatus::Pending);
+        assert_eq!(contract.total_proofs, 1);
+    }
+
+    #[test]
+    fn test_attestation() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let attestor: AccountId = "attestor.near".parse().unwrap();
+        
+        let mut context = get_context(owner.clone());
+        testing_env!(context.build());
+
+        let mut contract = IntelRegistry::new(owner);
+        
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::LocationProximity,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+
+        // Attest as different user
+        context = get_context(attestor);
+        testing_env!(context.build());
+
+        contract.attest("proof-001".to_string(), 85, Some("Verified via satellite".to_string()));
+
+        let proof = contract.get_proof("proof-001".to_string()).unwrap();
+        assert_eq!(proof.attestation_count, 1);
+        assert_eq!(proof.avg_confidence, 85);
+        // One high-confidence attestation isn't enough on its own
+        assert_eq!(proof.status, VerificationStatus::Contested);
+        assert_eq!(contract.get_source_stats(test_commitment()).unwrap().verified_count, 0);
+
+        testing_env!(get_context("second.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 85, None);
+        assert_eq!(contract.get_proof("proof-001".to_string()).unwrap().status, VerificationStatus::Contested);
+
+        testing_env!(get_context("third.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 85, None);
+        assert_eq!(contract.get_proof("proof-001".to_string()).unwrap().status, VerificationStatus::Verified);
+        assert_eq!(contract.get_source_stats(test_commitment()).unwrap().verified_count, 1);
+
+        // Further attestations on a Verified proof don't count it again
+        testing_env!(get_context("fourth.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 90, None);
+        assert_eq!(contract.get_source_stats(test_commitment()).unwrap().verified_count, 1);
+    }
+
+    /// Have the owner dispute a proof and finalize once the default window closes
+    fn refute_after_dispute(contract: &mut IntelRegistry, proof_id: &str, reason: &str) -> bool {
+        testing_env!(get_context("owner.near".parse().unwrap()).build());
+        contract.propose_refutation(proof_id.to_string(), reason.to_string());
+        let mut context = get_context("owner.near".parse().unwrap());
+        testing_env!(context.block_timestamp(1_000_000_000 + DEFAULT_DISPUTE_WINDOW_NS).build());
+        contract.finalize_refutation(proof_id.to_string())
+    }
+
+    /// Register a proof, doubt it, and have the owner refute it
+    fn refuted_registry() -> IntelRegistry {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+        let mut contract = IntelRegistry::new(owner.clone());
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::LocationProximity,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+        testing_env!(get_context("attestor.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 20, None);
+        assert!(refute_after_dispute(&mut contract, "proof-001", "Forged"));
+        testing_env!(get_context(owner).build());
+        contract
+    }
+
+    #[test]
+    #[should_panic(expected = "proof already refuted")]
+    fn test_refuted_proof_cannot_be_refuted_again() {
+        let mut contract = refuted_registry();
+        contract.refute_proof("proof-001".to_string(), "Forged again".to_string());
+    }
+
+    #[test]
+    fn test_refute_proof_opens_dispute() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+        let mut contract = IntelRegistry::new(owner);
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::LocationProximity,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+        contract.refute_proof("proof-001".to_string(), "Forged".to_string());
+
+        let proof = contract.get_proof("proof-001".to_string()).unwrap();
+        assert_eq!(proof.status, VerificationStatus::UnderDispute);
+        assert_eq!(
+            contract.get_dispute("proof-001".to_string()).unwrap().deadline,
+            U64(1_000_000_000 + DEFAULT_DISPUTE_WINDOW_NS)
+        );
+        assert_eq!(contract.get_source_stats(test_commitment()).unwrap().refuted_count, 0);
+    }
+
+    #[test]
+    fn test_attestors_credited_when_proof_verified() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+        let mut contract = IntelRegistry::new(owner.clone());
+        contract.set_min_attestations_for_verified(2);
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::LocationProximity,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+        let stats = |contract: &IntelRegistry, account: &str| {
+            let s = contract.get_attestor_stats(account.parse().unwrap()).unwrap();
+            (s.agreements, s.disagreements)
+        };
+
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 95, None);
+        assert_eq!(stats(&contract, "alice.near"), (0, 0));
+
+        // Reaching Verified credits everyone already on the proof
+        testing_env!(get_context("bob.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 95, None);
+        assert_eq!(contract.get_proof("proof-001".to_string()).unwrap().status, VerificationStatus::Verified);
+        assert_eq!(stats(&contract, "alice.near"), (1, 0));
+        assert_eq!(stats(&contract, "bob.near"), (1, 0));
+
+        // Later attestations are credited individually, and only once
+        testing_env!(get_context("carol.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 10, None);
+        contract.attest("proof-001".to_string(), 15, None);
+        assert_eq!(stats(&contract, "carol.near"), (0, 1));
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 90, None);
+        assert_eq!(stats(&contract, "alice.near"), (1, 0));
+
+        // Refutation reverses the Verified credit
+        assert!(refute_after_dispute(&mut contract, "proof-001", "Forged"));
+        assert_eq!(stats(&contract, "alice.near"), (0, 1));
+        assert_eq!(stats(&contract, "bob.near"), (0, 1));
+        assert_eq!(stats(&contract, "carol.near"), (1, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "proof is refuted")]
+    fn test_attest_on_refuted_proof() {
+        let mut contract = refuted_registry();
+        testing_env!(get_context("late.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 95, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "proof is refuted")]
+    fn test_retract_on_refuted_proof() {
+        let mut contract = refuted_registry();
+        testing_env!(get_context("attestor.near".parse().unwrap()).build());
+        contract.retract_attestation("proof-001".to_string());
+    }
+
+    #[test]
+    fn test_verified_count_not_double_counted() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+        let mut contract = IntelRegistry::new(owner.clone());
+        contract.set_min_attestations_for_verified(1);
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::LocationProximity,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+
+        for attestor in ["a.near", "b.near", "c.near"] {
+            testing_env!(get_context(attestor.parse().unwrap()).build());
+            contract.attest("proof-001".to_string(), 90, None);
+        }
+        assert_eq!(contract.get_proof("proof-001".to_string()).unwrap().status, VerificationStatus::Verified);
+        assert_eq!(contract.get_source_stats(test_commitment()).unwrap().verified_count, 1);
+
+        // Disputing a Verified proof takes it back out of the count
+        testing_env!(get_context(owner).build());
+        contract.propose_refutation("proof-001".to_string(), "Forged".to_string());
+        assert_eq!(contract.get_source_stats(test_commitment()).unwrap().verified_count, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "only owner")]
+    fn test_min_attestations_owner_only() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+        let mut contract = IntelRegistry::new(owner);
+        assert_eq!(contract.get_min_attestations_for_verified(), 3);
+
+        testing_env!(get_context("attestor.near".parse().unwrap()).build());
+        contract.set_min_attestations_for_verified(1);
+    }
+
+    #[test]
+    fn test_source_reputation() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let attestor: AccountId = "attestor.near".parse().unwrap();
+        let source_hash = test_commitment();
+        
+        let mut context = get_context(owner.clone());
+        testing_env!(context.build());
+
+        let mut contract = IntelRegistry::new(owner);
+        contract.set_min_attestations_for_verified(1);
+        
+        // Register multiple proofs from same source
+        for i in 0..5 {
+            contract.register_proof(
+                format!("proof-{:03}", i),
+                test_commitment(),
+                ProofType::TimestampRange,
+                source_hash.clone(),
+                format!("{:064}", i), // Different intel hashes
+                test_commitment(),
+                None,
+                None,
+            );
+        }
+
+        // Attest to some
+        context = get_context(attestor);
+        testing_env!(context.build());
+
+        for i in 0..3 {
+            contract.attest(format!("proof-{:03}", i), 80, None);
+        }
+
+        let stats = contract.get_source_stats(source_hash.clone()).unwrap();
+        assert_eq!(stats.total_proofs, 5);
+        assert_eq!(stats.total_attestations, 3);
+
+        let reputation = contract.get_source_reputation(source_hash);
+        assert!(reputation > 50); // Should have decent reputation
+    }
+
+    #[test]
+    fn test_source_reputation_decays_when_idle() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let source_hash = test_commitment();
+        testing_env!(get_context(owner.clone()).build());
+        let mut contract = IntelRegistry::new(owner.clone());
+        contract.set_min_attestations_for_verified(1);
+        contract.set_reputation_half_life(U64(1_000));
+        assert_eq!(contract.get_reputation_half_life(), U64(1_000));
+
+        for i in 0..10 {
+            contract.register_proof(
+                format!("proof-{:03}", i),
+                test_commitment(),
+                ProofType::LocationProximity,
+                source_hash.clone(),
+                test_commitment(),
+                test_commitment(),
+                None,
+                None,
+            );
+        }
+        testing_env!(get_context("attestor.near".parse().unwrap()).build());
+        for i in 0..10 {
+            contract.attest(format!("proof-{:03}", i), 90, None);
+        }
+        assert_eq!(contract.get_source_reputation(source_hash.clone()), 90);
+
+        // One half-life later the verified and activity components are halved
+        let mut context = get_context(owner.clone());
+        testing_env!(context.block_height(1_100).build());
+        assert_eq!(contract.get_source_reputation(source_hash.clone()), 60);
+
+        // Long idle leaves only the attestation component
+        testing_env!(context.block_height(100_100).build());
+        assert_eq!(contract.get_source_reputation(source_hash.clone()), 30);
+
+        // Disabling decay restores the full score
+        contract.set_reputation_half_life(U64(0));
+        assert_eq!(contract.get_source_reputation(source_hash), 90);
+    }
+
+    #[test]
+    fn test_retract_attestation() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let attestor: AccountId = "attestor.near".parse().unwrap();
+        let source_hash = test_commitment();
+
+        let mut context = get_context(owner.clone());
+        testing_env!(context.build());
+
+        let mut contract = IntelRegistry::new(owner);
+        contract.set_min_attestations_for_verified(1);
+
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::SatelliteImagery,
+            source_hash.clone(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+
+        context = get_context(attestor);
+        testing_env!(context.build());
+
+        contract.attest("proof-001".to_string(), 85, None);
+        assert_eq!(contract.get_proof("proof-001".to_string()).unwrap().status, VerificationStatus::Verified);
+
+        contract.retract_attestation("proof-001".to_string());
+
+        let proof = contract.get_proof("proof-001".to_string()).unwrap();
+        assert_eq!(proof.attestation_count, 0);
+        assert_eq!(proof.avg_confidence, 0);
+        assert_eq!(proof.status, VerificationStatus::Pending);
+
+        let stats = contract.get_source_stats(source_hash).unwrap();
+        assert_eq!(stats.total_attestations, 0);
+        assert_eq!(stats.confidence_sum, 0);
+        assert_eq!(stats.verified_count, 0);
+    }
+
+    /// Two confident attestors and one low outlier under the given mode
+    fn attest_with_outlier(mode: ConfidenceMode) -> ProofCommitment {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        contract.set_confidence_mode(mode);
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::ImageMetadata,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+
+        for (attestor, confidence) in [("a.near", 75), ("b.near", 75), ("c.near", 10)] {
+            testing_env!(get_context(attestor.parse().unwrap()).build());
+            contract.attest("proof-001".to_string(), confidence, None);
+        }
+        contract.get_proof("proof-001".to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_mean_confidence_skewed_by_outlier() {
+        let proof = attest_with_outlier(ConfidenceMode::Mean);
+        assert_eq!(proof.avg_confidence, 53);
+        assert_eq!(proof.status, VerificationStatus::Contested);
+    }
+
+    #[test]
+    fn test_median_confidence_resists_outlier() {
+        let proof = attest_with_outlier(ConfidenceMode::Median);
+        assert_eq!(proof.avg_confidence, 75);
+        assert_eq!(proof.status, VerificationStatus::Verified);
+    }
+
+    #[test]
+    fn test_refute_slashes_and_refunds_stakes() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let stake: Balance = 1_000_000;
+
+        let mut context = get_context(owner.clone());
+        testing_env!(context.build());
+
+        let mut contract = IntelRegistry::new(owner.clone());
+        contract.set_min_attest_stake(U128(stake));
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::DocumentContains,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+
+        for (attestor, confidence) in [("high.near", 90), ("mid.near", 50), ("low.near", 10)] {
+            context = get_context(attestor.parse().unwrap());
+            testing_env!(context.attached_deposit(stake).build());
+            contract.attest("proof-001".to_string(), confidence, None);
+        }
+
+        assert!(refute_after_dispute(&mut contract, "proof-001", "fabricated"));
+
+        let stake_of = |c: &IntelRegistry, a: &str| c.get_attestation_stake("proof-001".to_string(), a.parse().unwrap()).0;
+        assert_eq!(stake_of(&contract, "high.near"), 0); // slashed
+        assert_eq!(stake_of(&contract, "low.near"), 0); // refunded
+        assert_eq!(stake_of(&contract, "mid.near"), stake); // claimable
+
+        context = get_context("mid.near".parse().unwrap());
+        testing_env!(context.build());
+        contract.claim_attestation_refund("proof-001".to_string());
+        assert_eq!(stake_of(&contract, "mid.near"), 0);
+    }
+
+    #[test]
+    fn test_stake_bands_ignore_threshold_changes() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let stake: Balance = 1_000_000;
+
+        let mut context = get_context(owner.clone());
+        testing_env!(context.build());
+
+        let mut contract = IntelRegistry::new(owner.clone());
+        contract.set_min_attest_stake(U128(stake));
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::DocumentContains,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+
+        for (attestor, confidence) in [("high.near", 75), ("low.near", 25)] {
+            context = get_context(attestor.parse().unwrap());
+            testing_env!(context.attached_deposit(stake).build());
+            contract.attest("proof-001".to_string(), confidence, None);
+        }
+
+        // Moving the thresholds after staking leaves both attestors in their bands
+        testing_env!(get_context(owner.clone()).build());
+        contract.set_verified_threshold(90);
+        contract.set_refute_threshold(20);
+        assert!(refute_after_dispute(&mut contract, "proof-001", "fabricated"));
+
+        let stake_of = |c: &IntelRegistry, a: &str| c.get_attestation_stake("proof-001".to_string(), a.parse().unwrap()).0;
+        assert_eq!(stake_of(&contract, "high.near"), 0); // slashed
+        assert_eq!(stake_of(&contract, "low.near"), 0); // refunded
+    }
+
+    #[test]
+    #[should_panic(expected = "insufficient attestation stake")]
+    fn test_attest_requires_min_stake() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        contract.set_min_attest_stake(U128(1_000));
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::DocumentContains,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+
+        testing_env!(get_context("attestor.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 80, None);
+    }
+
+    #[test]
+    fn test_proofs_by_type_and_status() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+
+        let mut context = get_context(owner.clone());
+        testing_env!(context.build());
+
+        let mut contract = IntelRegistry::new(owner);
+        contract.set_min_attestations_for_verified(1);
+        for (i, proof_type) in [ProofType::LocationProximity, ProofType::DocumentContains, ProofType::LocationProximity]
+            .into_iter()
+            .enumerate()
+        {
+            contract.register_proof(
+                format!("proof-{:03}", i),
+                test_commitment(),
+                proof_type,
+                test_commitment(),
+                test_commitment(),
+                test_commitment(),
+                None,
+                None,
+            );
+        }
+
+        let location = contract.get_proofs_by_type(ProofType::LocationProximity, 0, 10);
+        assert_eq!(location.len(), 2);
+        assert_eq!(contract.get_proofs_by_type(ProofType::LocationProximity, 1, 10)[0].proof_id, "proof-002");
+        assert_eq!(contract.get_proofs_by_type(ProofType::SatelliteImagery, 0, 10).len(), 0);
+        assert_eq!(contract.get_proofs_by_status(VerificationStatus::Pending, 0, 10).len(), 3);
+
+        // Attestation flips proof-000 to Verified
+        context = get_context("attestor.near".parse().unwrap());
+        testing_env!(context.build());
+        contract.attest("proof-000".to_string(), 90, None);
+
+        let verified = contract.get_proofs_by_status(VerificationStatus::Verified, 0, 10);
+        assert_eq!(verified.len(), 1);
+        assert_eq!(verified[0].proof_id, "proof-000");
+
+        let pending: Vec<String> = contract
+            .get_proofs_by_status(VerificationStatus::Pending, 0, 10)
+            .into_iter()
+            .map(|p| p.proof_id)
+            .collect();
+        assert_eq!(pending.len(), 2);
+        assert!(!pending.contains(&"proof-000".to_string()));
+
+        // A second move keeps the swapped entry's position valid
+        contract.attest("proof-002".to_string(), 20, None);
+        let pending = contract.get_proofs_by_status(VerificationStatus::Pending, 0, 10);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].proof_id, "proof-001");
+        assert_eq!(contract.get_proofs_by_status(VerificationStatus::Contested, 0, 10).len(), 1);
+    }
+
+    #[test]
+    fn test_recent_proofs_reads_only_tail() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        let register = |contract: &mut IntelRegistry, range: std::ops::Range<u32>| {
+            for i in range {
+                // Fresh context per call to stay within per-call log and gas limits
+                testing_env!(get_context("owner.near".parse().unwrap()).build());
+                contract.register_proof(
+                    format!("proof-{:03}", i),
+                    test_commitment(),
+                    ProofType::GenericCommitment,
+                    test_commitment(),
+                    test_commitment(),
+                    test_commitment(),
+                    None,
+                    None,
+                );
+            }
+        };
+        let gas_for_recent = |contract: &IntelRegistry| {
+            let before = env::used_gas();
+            let recent = contract.get_recent_proofs(5);
+            (env::used_gas() - before, recent)
+        };
+
+        register(&mut contract, 0..10);
+        let (gas_small, _) = gas_for_recent(&contract);
+
+        register(&mut contract, 10..200);
+        let (gas_large, recent) = gas_for_recent(&contract);
+
+        // Cost is independent of registry size: only `limit` proofs are read
+        assert_eq!(gas_small, gas_large);
+        let ids: Vec<String> = recent.into_iter().map(|p| p.proof_id).collect();
+        assert_eq!(ids, vec!["proof-199", "proof-198", "proof-197", "proof-196", "proof-195"]);
+    }
+
+    /// Build a perfect track record for `attestor` by doubting proofs the owner refutes
+    fn build_attestor_reputation(contract: &mut IntelRegistry, attestor: &str, proofs: u32) {
+        for i in 0..proofs {
+            let proof_id = format!("history-{:03}", i);
+            testing_env!(get_context("owner.near".parse().unwrap()).build());
+            contract.register_proof(
+                proof_id.clone(),
+                test_commitment(),
+                ProofType::GenericCommitment,
+                test_commitment(),
+                test_commitment(),
+                test_commitment(),
+                None,
+                None,
+            );
+
+            testing_env!(get_context(attestor.parse().unwrap()).build());
+            contract.attest(proof_id.clone(), 10, None);
+
+            assert!(refute_after_dispute(contract, &proof_id, "fabricated"));
+        }
+    }
+
+    fn weighted_confidence(expert_confidence: u8, newcomer_confidence: u8) -> u8 {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner.clone());
+        contract.set_confidence_mode(ConfidenceMode::Weighted);
+        build_attestor_reputation(&mut contract, "expert.near", 10);
+        assert_eq!(contract.get_attestor_reputation("expert.near".parse().unwrap()), 100);
+        assert_eq!(contract.get_attestor_reputation("newcomer.near".parse().unwrap()), 0);
+
+        testing_env!(get_context(owner).build());
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::DocumentContains,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+        for (attestor, confidence) in [("expert.near", expert_confidence), ("newcomer.near", newcomer_confidence)] {
+            testing_env!(get_context(attestor.parse().unwrap()).build());
+            contract.attest("proof-001".to_string(), confidence, None);
+        }
+        contract.get_proof("proof-001".to_string()).unwrap().avg_confidence
+    }
+
+    // An unweighted mean would be 50 in both cases below
+
+    #[test]
+    fn test_weighted_confidence_expert_pulls_up() {
+        let avg = weighted_confidence(90, 10);
+        assert!(avg > 80, "expert pulled average to {}", avg);
+    }
+
+    #[test]
+    fn test_weighted_confidence_expert_pulls_down() {
+        let avg = weighted_confidence(10, 90);
+        assert!(avg < 20, "expert pulled average to {}", avg);
+    }
+
+    #[test]
+    fn test_custom_verified_threshold() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        contract.set_verified_threshold(90);
+        assert_eq!(contract.get_thresholds(), (90, 30));
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::LocationProximity,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+
+        testing_env!(get_context("attestor.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 85, None);
+
+        let proof = contract.get_proof("proof-001".to_string()).unwrap();
+        assert_eq!(proof.avg_confidence, 85);
+        assert_eq!(proof.status, VerificationStatus::Contested);
+    }
+
+    #[test]
+    #[should_panic(expected = "refute threshold must be below verified threshold")]
+    fn test_refute_threshold_must_stay_below_verified() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        contract.set_refute_threshold(70);
+    }
+
+    #[test]
+    fn test_expired_proof_reduces_reputation() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let source_hash = test_commitment();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        for (proof_id, expires_at) in [("proof-001", None), ("proof-002", Some(U64(2_000_000_000)))] {
+            contract.register_proof(
+                proof_id.to_string(),
+                test_commitment(),
+                ProofType::TimestampRange,
+                source_hash.clone(),
+                test_commitment(),
+                test_commitment(),
+                None,
+                expires_at,
+            );
+        }
+
+        testing_env!(get_context("attestor.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 90, None);
+        contract.attest("proof-002".to_string(), 90, None);
+        assert!(contract.is_proof_fresh("proof-002".to_string()));
+        let fresh_reputation = contract.get_source_reputation(source_hash.clone());
+
+        // Advance past proof-002's expiry
+        let mut context = get_context("attestor.near".parse().unwrap());
+        testing_env!(context.block_timestamp(3_000_000_000).build());
+        assert!(!contract.is_proof_fresh("proof-002".to_string()));
+        assert!(contract.is_proof_fresh("proof-001".to_string()));
+        assert!(contract.get_source_reputation(source_hash) < fresh_reputation);
+    }
+
+    #[test]
+    fn test_link_corroborating_proofs() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let intel_hash = test_commitment();
+
+        let mut context = get_context(owner.clone());
+        testing_env!(context.build());
+        let mut contract = IntelRegistry::new(owner);
+
+        for (proof_id, source, controller) in [("proof-a", "b", "alpha.near"), ("proof-b", "c", "bravo.near")] {
+            context = get_context(controller.parse().unwrap());
+            testing_env!(context.build());
+            contract.register_proof(
+                proof_id.to_string(),
+                test_commitment(),
+                ProofType::MultiSourceCorroboration,
+                source.repeat(64),
+                intel_hash.clone(),
+                test_commitment(),
+                None,
+                None,
+            );
+        }
+        assert_eq!(contract.get_source_controller("b".repeat(64)), None);
+
+        context = get_context("owner.near".parse().unwrap());
+        testing_env!(context.build());
+        contract.set_source_controller("b".repeat(64), "alpha.near".parse().unwrap());
+        assert_eq!(contract.get_source_controller("b".repeat(64)), Some("alpha.near".parse().unwrap()));
+
+        context = get_context("alpha.near".parse().unwrap());
+        testing_env!(context.build());
+        contract.link_proofs("proof-a".to_string(), "proof-b".to_string(), "corroborates".to_string());
+
+        assert_eq!(
+            contract.get_linked_proofs("proof-a".to_string()),
+            vec![("proof-b".to_string(), "corroborates".to_string())]
+        );
+        assert_eq!(
+            contract.get_linked_proofs("proof-b".to_string()),
+            vec![("proof-a".to_string(), "corroborates".to_string())]
+        );
+        assert_eq!(contract.get_proof("proof-a".to_string()).unwrap().corroboration_count, 1);
+        assert_eq!(contract.get_proof("proof-b".to_string()).unwrap().corroboration_count, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "not authorized to link proofs")]
+    fn test_link_proofs_requires_controller() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        for proof_id in ["proof-a", "proof-b"] {
+            contract.register_proof(
+                proof_id.to_string(),
+                test_commitment(),
+                ProofType::MultiSourceCorroboration,
+                test_commitment(),
+                test_commitment(),
+                test_commitment(),
+                None,
+                None,
+            );
+        }
+
+        testing_env!(get_context("mallory.near".parse().unwrap()).build());
+        contract.link_proofs("proof-a".to_string(), "proof-b".to_string(), "corroborates".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "not authorized to link proofs")]
+    fn test_first_registrant_does_not_control_source() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+        let mut contract = IntelRegistry::new(owner);
+
+        // A stranger registers proofs for a source before its real owner appears
+        testing_env!(get_context("mallory.near".parse().unwrap()).build());
+        for proof_id in ["proof-a", "proof-b"] {
+            contract.register_proof(
+                proof_id.to_string(),
+                test_commitment(),
+                ProofType::MultiSourceCorroboration,
+                test_commitment(),
+                test_commitment(),
+                test_commitment(),
+                None,
+                None,
+            );
+        }
+        assert_eq!(contract.get_source_controller(test_commitment()), None);
+
+        contract.link_proofs("proof-a".to_string(), "proof-b".to_string(), "corroborates".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "only owner")]
+    fn test_set_source_controller_owner_only() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+        let mut contract = IntelRegistry::new(owner);
+
+        testing_env!(get_context("mallory.near".parse().unwrap()).build());
+        contract.set_source_controller(test_commitment(), "mallory.near".parse().unwrap());
+    }
+
+    #[test]
+    fn test_events_emitted() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let hash = test_commitment();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        contract.set_min_attestations_for_verified(1);
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::LocationProximity,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+        assert_eq!(
+            get_logs().last().unwrap(),
+            &format!(
+                "EVENT_JSON:{{\"standard\":\"argus-intel\",\"version\":\"1.0.0\",\"event\":\"proof_registered\",\"data\":[{{\"intel_hash\":\"{}\",\"proof_id\":\"proof-001\",\"proof_type\":\"LocationProximity\",\"source_hash\":\"{}\",\"status\":\"Pending\"}}]}}",
+                hash, hash
+            )
+        );
+
+        testing_env!(get_context("attestor.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 85, None);
+        assert_eq!(
+            get_logs().last().unwrap(),
+            "EVENT_JSON:{\"standard\":\"argus-intel\",\"version\":\"1.0.0\",\"event\":\"attestation_added\",\"data\":[{\"attestor\":\"attestor.near\",\"avg_confidence\":85,\"confidence\":85,\"proof_id\":\"proof-001\",\"status\":\"Verified\"}]}"
+        );
+
+        testing_env!(get_context("owner.near".parse().unwrap()).build());
+        contract.refute_proof("proof-001".to_string(), "doctored \"photo\"".to_string());
+        testing_env!(get_context("attestor.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 20, None);
+
+        let mut context = get_context("anyone.near".parse().unwrap());
+        testing_env!(context.block_timestamp(1_000_000_000 + DEFAULT_DISPUTE_WINDOW_NS).build());
+        assert!(contract.finalize_refutation("proof-001".to_string()));
+        assert_eq!(
+            get_logs().last().unwrap(),
+            "EVENT_JSON:{\"standard\":\"argus-intel\",\"version\":\"1.0.0\",\"event\":\"proof_refuted\",\"data\":[{\"avg_confidence\":20,\"proof_id\":\"proof-001\",\"reason\":\"doctored \\\"photo\\\"\",\"status\":\"Refuted\"}]}"
+        );
+    }
+
+    fn proof_input(proof_id: &str, proof_type: ProofType) -> ProofInput {
+        ProofInput {
+            proof_id: proof_id.to_string(),
+            commitment: test_commitment(),
+            proof_type,
+            source_hash: test_commitment(),
+            intel_hash: "b".repeat(64),
+            public_inputs_hash: test_commitment(),
+            metadata: None,
+            expires_at: None,
+        }
+    }
+
+    #[test]
+    fn test_register_proofs_batch() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        let registered = contract.register_proofs_batch(vec![
+            proof_input("proof-loc", ProofType::LocationProximity),
+            proof_input("proof-time", ProofType::TimestampRange),
+            proof_input("proof-doc", ProofType::DocumentContains),
+        ]);
+
+        assert_eq!(registered.len(), 3);
+        assert_eq!(contract.get_intel_proofs("b".repeat(64)).len(), 3);
+        assert_eq!(contract.get_source_stats(test_commitment()).unwrap().total_proofs, 3);
+        assert_eq!(contract.get_stats(), (3, 0));
+    }
+
+    #[test]
+    fn test_register_proofs_batch_skips_duplicates() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        contract.register_proofs_batch(vec![proof_input("proof-loc", ProofType::LocationProximity)]);
+
+        let registered = contract.register_proofs_batch(vec![
+            proof_input("proof-loc", ProofType::LocationProximity),
+            proof_input("proof-doc", ProofType::DocumentContains),
+            proof_input("proof-doc", ProofType::DocumentContains),
+        ]);
+
+        assert_eq!(registered.len(), 1);
+        assert_eq!(registered[0].proof_id, "proof-doc");
+        assert_eq!(contract.get_stats(), (2, 0));
+        let skipped = get_logs()
+            .iter()
+            .filter(|l| l.contains("\"event\":\"proof_skipped\""))
+            .count();
+        assert_eq!(skipped, 2);
+    }
+
+    #[test]
+    fn test_attest_batch_skips_missing_proofs() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        contract.set_min_attestations_for_verified(1);
+        contract.register_proofs_batch(vec![
+            proof_input("proof-loc", ProofType::LocationProximity),
+            proof_input("proof-doc", ProofType::DocumentContains),
+        ]);
+
+        testing_env!(get_context("partner.near".parse().unwrap()).build());
+        let missing = contract.attest_batch(vec![
+            ("proof-loc".to_string(), 90, Some("historical: confirmed".to_string())),
+            ("proof-gone".to_string(), 80, None),
+            ("proof-doc".to_string(), 30, None),
+            ("proof-never".to_string(), 50, None),
+        ]);
+
+        assert_eq!(missing, vec!["proof-gone".to_string(), "proof-never".to_string()]);
+        assert_eq!(contract.get_stats(), (2, 2));
+        let loc = contract.get_proof("proof-loc".to_string()).unwrap();
+        assert_eq!(loc.avg_confidence, 90);
+        assert_eq!(loc.status, VerificationStatus::Verified);
+        assert_eq!(contract.get_proof("proof-doc".to_string()).unwrap().attestation_count, 1);
+        assert_eq!(
+            contract.get_attestation_notes("proof-loc".to_string()),
+            vec![("partner.near".parse().unwrap(), "historical: confirmed".to_string())]
+        );
+        let skipped = get_logs()
+            .iter()
+            .filter(|l| l.contains("\"event\":\"attestation_skipped\""))
+            .count();
+        assert_eq!(skipped, 2);
+    }
+
+    #[test]
+    fn test_attest_batch_splits_stake_across_existing_proofs() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        contract.set_min_attest_stake(U128(1_000));
+        contract.register_proofs_batch(vec![
+            proof_input("proof-loc", ProofType::LocationProximity),
+            proof_input("proof-doc", ProofType::DocumentContains),
+        ]);
+
+        // Missing proofs do not dilute the stake below the minimum
+        let mut context = get_context("partner.near".parse().unwrap());
+        testing_env!(context.attached_deposit(2_000).build());
+        contract.attest_batch(vec![
+            ("proof-loc".to_string(), 90, None),
+            ("proof-gone".to_string(), 80, None),
+            ("proof-doc".to_string(), 30, None),
+        ]);
+
+        for proof_id in ["proof-loc", "proof-doc"] {
+            let stake = contract.get_attestation_stake(proof_id.to_string(), "partner.near".parse().unwrap());
+            assert_eq!(stake, U128(1_000));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "batch too large (max 20)")]
+    fn test_attest_batch_size_cap() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        let items = (0..21).map(|i| (format!("proof-{}", i), 80, None)).collect();
+        contract.attest_batch(items);
+    }
+
+    #[test]
+    fn test_two_step_ownership_transfer() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let next: AccountId = "next-owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner.clone());
+        contract.propose_owner(next.clone());
+        assert_eq!(contract.get_owner(), owner);
+        assert_eq!(contract.get_pending_owner(), Some(next.clone()));
+
+        testing_env!(get_context(next.clone()).build());
+        contract.accept_ownership();
+        assert_eq!(contract.get_owner(), next);
+        assert_eq!(contract.get_pending_owner(), None);
+
+        // The new owner holds admin rights
+        contract.set_dispute_window(U64(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "only pending owner")]
+    fn test_accept_ownership_requires_pending_owner() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        contract.propose_owner("next-owner.near".parse().unwrap());
+
+        testing_env!(get_context("mallory.near".parse().unwrap()).build());
+        contract.accept_ownership();
+    }
+
+    #[test]
+    #[should_panic(expected = "only owner")]
+    fn test_propose_owner_owner_only() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        testing_env!(get_context("mallory.near".parse().unwrap()).build());
+        contract.propose_owner("mallory.near".parse().unwrap());
+    }
+
+    /// Register a proof and have the owner propose its refutation at t = 1s
+    fn disputed_registry() -> IntelRegistry {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        contract.set_dispute_window(U64(1_000_000_000));
+        contract.set_min_attestations_for_verified(1);
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::ImageMetadata,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+        contract.propose_refutation("proof-001".to_string(), "EXIF mismatch".to_string());
+        contract
+    }
+
+    #[test]
+    fn test_finalize_refutation_after_deadline() {
+        let mut contract = disputed_registry();
+        assert_eq!(contract.get_proof("proof-001".to_string()).unwrap().status, VerificationStatus::UnderDispute);
+        assert_eq!(contract.get_dispute("proof-001".to_string()).unwrap().deadline, U64(2_000_000_000));
+
+        // Low-confidence attestations during the window keep the dispute open
+        testing_env!(get_context("attestor.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 20, None);
+        assert_eq!(contract.get_proof("proof-001".to_string()).unwrap().status, VerificationStatus::UnderDispute);
+
+        let mut context = get_context("anyone.near".parse().unwrap());
+        testing_env!(context.block_timestamp(2_000_000_000).build());
+        assert!(contract.finalize_refutation("proof-001".to_string()));
+
+        assert_eq!(contract.get_proof("proof-001".to_string()).unwrap().status, VerificationStatus::Refuted);
+        assert!(contract.get_dispute("proof-001".to_string()).is_none());
+        assert_eq!(contract.get_source_stats(test_commitment()).unwrap().refuted_count, 1);
+    }
+
+    #[test]
+    fn test_dispute_dismissed_when_confidence_recovers() {
+        let mut contract = disputed_registry();
+
+        testing_env!(get_context("attestor.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 95, None);
+
+        let mut context = get_context("anyone.near".parse().unwrap());
+        testing_env!(context.block_timestamp(2_000_000_000).build());
+        assert!(!contract.finalize_refutation("proof-001".to_string()));
+        assert_eq!(contract.get_proof("proof-001".to_string()).unwrap().status, VerificationStatus::Verified);
+    }
+
+    #[test]
+    #[should_panic(expected = "dispute window still open")]
+    fn test_finalize_refutation_before_deadline() {
+        let mut contract = disputed_registry();
+        contract.finalize_refutation("proof-001".to_string());
+    }
+
+    #[test]
+    fn test_get_source_proofs_paginated() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let source_hash = "c".repeat(64);
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        for (i, intel) in ["d", "e", "f", "d", "e"].iter().enumerate() {
+            contract.register_proof(
+                format!("proof-{:03}", i),
+                test_commitment(),
+                ProofType::GenericCommitment,
+                source_hash.clone(),
+                intel.repeat(64),
+                test_commitment(),
+                None,
+                None,
+            );
+        }
+        // Another source's proof stays out of the listing
+        contract.register_proof(
+            "other-001".to_string(),
+            test_commitment(),
+            ProofType::GenericCommitment,
+            test_commitment(),
+            "d".repeat(64),
+            test_commitment(),
+            None,
+            None,
+        );
+
+        let ids = |proofs: Vec<ProofCommitment>| proofs.into_iter().map(|p| p.proof_id).collect::<Vec<_>>();
+        assert_eq!(ids(contract.get_source_proofs(source_hash.clone(), 0, 2)), vec!["proof-000", "proof-001"]);
+        assert_eq!(ids(contract.get_source_proofs(source_hash.clone(), 2, 2)), vec!["proof-002", "proof-003"]);
+        assert_eq!(ids(contract.get_source_proofs(source_hash.clone(), 4, 2)), vec!["proof-004"]);
+        assert!(contract.get_source_proofs(source_hash, 10, 2).is_empty());
+        assert!(contract.get_source_proofs("0".repeat(64), 0, 10).is_empty());
+    }
+
+    fn registry_with_org_delegate() -> IntelRegistry {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+
+        let mut contract = IntelRegistry::new(owner);
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::VerifiableCredential,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+
+        testing_env!(get_context("newsroom.near".parse().unwrap()).build());
+        contract.register_delegate("newsroom.near".parse().unwrap(), "reporter.near".parse().unwrap());
+        contract.register_delegate("newsroom.near".parse().unwrap(), "editor.near".parse().unwrap());
+        contract
+    }
+
+    #[test]
+    fn test_delegated_attestation() {
+        let mut contract = registry_with_org_delegate();
+        let org: AccountId = "newsroom.near".parse().unwrap();
+
+        testing_env!(get_context("reporter.near".parse().unwrap()).build());
+        contract.attest_as(org.clone(), "proof-001".to_string(), 60, None);
+
+        // A second staffer updates the org's attestation rather than adding one
+        testing_env!(get_context("editor.near".parse().unwrap()).build());
+        contract.attest_as(org.clone(), "proof-001".to_string(), 90, None);
+
+        let proof = contract.get_proof_with_attestations("proof-001".to_string()).unwrap();
+        assert_eq!(proof.proof.attestation_count, 1);
+        assert_eq!(proof.proof.avg_confidence, 90);
+        assert_eq!(proof.attestations[0].attestor, org);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a delegate of this organization")]
+    fn test_revoked_delegate_rejected() {
+        let mut contract = registry_with_org_delegate();
+        contract.revoke_delegate("newsroom.near".parse().unwrap(), "reporter.near".parse().unwrap());
+        assert!(!contract.is_delegate("newsroom.near".parse().unwrap(), "reporter.near".parse().unwrap()));
+
+        testing_env!(get_context("reporter.near".parse().unwrap()).build());
+        contract.attest_as("newsroom.near".parse().unwrap(), "proof-001".to_string(), 80, None);
+    }
+
+    #[test]
+    fn test_get_proofs_in_range() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+        let mut contract = IntelRegistry::new(owner.clone());
+
+        let mut context = get_context(owner);
+        for (i, height) in [100u64, 150, 200, 200, 250, 300].iter().enumerate() {
+            testing_env!(context.block_height(*height).build());
+            contract.register_proof(
+                format!("proof-{:03}", i),
+                test_commitment(),
+                ProofType::TimestampRange,
+                test_commitment(),
+                test_commitment(),
+                test_commitment(),
+                None,
+                None,
+            );
+        }
+
+        let ids = |proofs: Vec<ProofCommitment>| proofs.into_iter().map(|p| p.proof_id).collect::<Vec<_>>();
+        assert_eq!(
+            ids(contract.get_proofs_in_range(150, 250, 0, 10)),
+            vec!["proof-001", "proof-002", "proof-003", "proof-004"]
+        );
+        assert_eq!(ids(contract.get_proofs_in_range(150, 250, 1, 2)), vec!["proof-002", "proof-003"]);
+        assert_eq!(ids(contract.get_proofs_in_range(200, 200, 0, 10)), vec!["proof-002", "proof-003"]);
+        assert!(contract.get_proofs_in_range(301, 400, 0, 10).is_empty());
+        assert!(contract.get_proofs_in_range(0, 99, 0, 10).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "min_height must not exceed max_height")]
+    fn test_get_proofs_in_range_rejects_inverted_range() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+        let contract = IntelRegistry::new(owner);
+        contract.get_proofs_in_range(200, 100, 0, 10);
+    }
+
+    #[test]
+    fn test_get_attestations_paginated() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+        let mut contract = IntelRegistry::new(owner);
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::LocationProximity,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+
+        for i in 0..150 {
+            testing_env!(get_context(format!("attestor-{}.near", i).parse().unwrap()).build());
+            contract.attest("proof-001".to_string(), 80, None);
+        }
+        assert_eq!(contract.get_proof("proof-001".to_string()).unwrap().attestation_count, 150);
+
+        let first = contract.get_attestations("proof-001".to_string(), 0, 500);
+        assert_eq!(first.len(), 100);
+        assert_eq!(first[0].attestor.as_str(), "attestor-0.near");
+        let rest = contract.get_attestations("proof-001".to_string(), 100, 100);
+        assert_eq!(rest.len(), 50);
+        assert_eq!(rest[49].attestor.as_str(), "attestor-149.near");
+        assert!(contract.get_attestations("proof-001".to_string(), 150, 100).is_empty());
+        assert!(contract.get_attestations("missing".to_string(), 0, 10).is_empty());
+    }
+
+    #[test]
+    fn test_get_attestation_notes_skips_empty_notes() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+        let mut contract = IntelRegistry::new(owner);
+        contract.register_proof(
+            "proof-001".to_string(),
+            test_commitment(),
+            ProofType::LocationProximity,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 80, Some("matches satellite imagery".to_string()));
+        testing_env!(get_context("bob.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 70, None);
+        testing_env!(get_context("carol.near".parse().unwrap()).build());
+        contract.attest("proof-001".to_string(), 90, Some("second source confirms".to_string()));
+
+        assert_eq!(
+            contract.get_attestation_notes("proof-001".to_string()),
+            vec![
+                ("alice.near".parse().unwrap(), "matches satellite imagery".to_string()),
+                ("carol.near".parse().unwrap(), "second source confirms".to_string()),
+            ]
+        );
+        assert!(contract.get_attestation_notes("missing".to_string()).is_empty());
+    }
+
+    #[test]
+    fn test_verify_commitment_parts() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context(owner.clone()).build());
+        let mut contract = IntelRegistry::new(owner);
+
+        let commitment: String = env::sha256(b"proofinputssource")
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        contract.register_proof(
+            "proof-001".to_string(),
+            commitment.clone(),
+            ProofType::LocationProximity,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+
+        let check = contract.verify_commitment_parts(
+            "proof-001".to_string(),
+            "proof".to_string(),
+            "inputs".to_string(),
+            "source".to_string(),
+        );
+        assert!(check.matches);
+        assert_eq!(check.computed_commitment, commitment);
+
+        let check = contract.verify_commitment_parts(
+            "proof-001".to_string(),
+            "proof".to_string(),
+            "inputs".to_string(),
+            "other-source".to_string(),
+        );
+        assert!(!check.matches);
+        assert_ne!(check.computed_commitment, commitment);
+
+        let check = contract.verify_commitment_parts(
+            "missing".to_string(),
+            "proof".to_string(),
+            "inputs".to_string(),
+            "source".to_string(),
+        );
+        assert!(!check.matches);
+        assert_eq!(check.computed_commitment, commitment);
+    }
+
+    #[test]
+    fn test_migrate_from_original_layout() {
+        let owner: AccountId = "owner.near".parse().unwrap();
+        testing_env!(get_context("registry.near".parse().unwrap()).build());
+
+        let mut old = OldIntelRegistry {
+            proofs: UnorderedMap::new(StorageKey::Proofs),
+            attestations: LookupMap::new(StorageKey::Attestations),
+            source_stats: LookupMap::new(StorageKey::SourceStats),
+            intel_proofs: LookupMap::new(StorageKey::IntelProofs),
+            total_proofs: 2,
+            total_attestations: 0,
+            owner: owner.clone(),
+        };
+        for (proof_id, proof_type, status) in [
+            ("proof-001", ProofType::LocationProximity, VerificationStatus::Verified),
+            ("proof-002", ProofType::TimestampRange, VerificationStatus::Pending),
+        ] {
+            old.proofs.insert(&proof_id.to_string(), &OldProofCommitment {
+                proof_id: proof_id.to_string(),
+                commitment: test_commitment(),
+                proof_type,
+                source_hash: test_commitment(),
+                intel_hash: test_commitment(),
+                public_inputs_hash: test_commitment(),
+                block_height: U64(50),
+                timestamp_ns: U64(500),
+                metadata: None,
+                status,
+                attestation_count: 0,
+                avg_confidence: 80,
+            });
+        }
+        env::state_write(&old);
+
+        let mut contract = IntelRegistry::migrate();
+
+        assert_eq!(contract.get_stats(), (2, 0));
+        let proof = contract.get_proof("proof-001".to_string()).unwrap();
+        assert_eq!(proof.avg_confidence, 80);
+        assert_eq!(proof.expires_at, None);
+        assert_eq!(proof.corroboration_count, 0);
+
+        let by_type = contract.get_proofs_by_type(ProofType::TimestampRange, 0, 10);
+        assert_eq!(by_type.len(), 1);
+        assert_eq!(by_type[0].proof_id, "proof-002");
+        assert_eq!(contract.get_proofs_by_status(VerificationStatus::Verified, 0, 10).len(), 1);
+        assert_eq!(contract.get_source_proofs(test_commitment(), 0, 10).len(), 2);
+        assert_eq!(contract.get_recent_proofs(10).len(), 2);
+        assert_eq!(contract.get_thresholds(), (70, 30));
+
+        // Registering after the upgrade does not claim the existing source
+        testing_env!(get_context("mallory.near".parse().unwrap()).build());
+        contract.register_proof(
+            "proof-003".to_string(),
+            test_commitment(),
+            ProofType::LocationProximity,
+            test_commitment(),
+            test_commitment(),
+            test_commitment(),
+            None,
+            None,
+        );
+        assert_eq!(contract.get_source_controller(test_commitment()), None);
     }
 }
@@ -6,6 +6,11 @@
 /// 1. Storing AES decryption keys securely in TEE
 /// 2. Verifying NEAR NFT ownership via RPC
 /// 3. Decrypting content only for verified holders
+///
+/// Decryption runs as a query, and queries can't commit contract storage, so
+/// per-request state lives outside it: challenge nonces and rate limit counters
+/// are kept in the worker's local cache, and successful decryptions are reported
+/// to the cluster log server rather than kept in an on-chain audit log.
 
 #[pink::contract]
 mod argus_content_gate {
@@ -19,6 +24,7 @@ mod argus_content_gate {
         aead::{Aead, KeyInit},
         Aes256Gcm, Nonce,
     };
+    use chacha20poly1305::ChaCha20Poly1305;
 
     #[ink(storage)]
     pub struct ArgusContentGate {
@@ -26,10 +32,58 @@ mod argus_content_gate {
         admin: AccountId,
         /// Map of listId -> AES-256 key (32 bytes)
         list_keys: ink::storage::Mapping<String, [u8; 32]>,
+        /// Registered list IDs in registration order (`list_keys` can't be enumerated)
+        list_ids: Vec<String>,
         /// NEAR RPC endpoint
         near_rpc: String,
-        /// NFT contract on NEAR
+        /// Access oracle contract on NEAR
         nft_contract: String,
+        /// Argument layout used when calling `has_access` on the oracle
+        access_mode: AccessMode,
+        /// Maximum age of a signed challenge in milliseconds
+        max_challenge_age: u64,
+        /// IPFS gateway base URLs, tried in order
+        ipfs_gateways: Vec<String>,
+        /// Accounts allowed to encrypt with stored list keys, besides the admin
+        producers: ink::storage::Mapping<AccountId, ()>,
+        /// Length of a rate limit window in milliseconds
+        rate_limit_window: u64,
+        /// Maximum decryption requests per account per window
+        max_requests_per_window: u32,
+    }
+
+    /// Which access contract the gate fronts, and so which `has_access` signature to call
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum AccessMode {
+        /// source-list-nft: `has_access(account_id, token_id)`
+        SourceList,
+        /// humint-feed: `has_access(account_id, source_hash)`
+        HumintFeed,
+    }
+
+    /// Leading byte of encrypted content selecting the cipher
+    const ALGORITHM_AES_256_GCM: u8 = 0;
+    const ALGORITHM_CHACHA20_POLY1305: u8 = 1;
+
+    /// Default maximum challenge age (5 minutes)
+    const DEFAULT_MAX_CHALLENGE_AGE_MS: u64 = 5 * 60 * 1000;
+
+    /// Default rate limit: 60 decryptions per account per minute
+    const DEFAULT_RATE_LIMIT_WINDOW_MS: u64 = 60 * 1000;
+    const DEFAULT_MAX_REQUESTS_PER_WINDOW: u32 = 60;
+
+    /// NEP-413 prefix tag (2^31 + 413) that keeps signed messages from being valid transactions
+    const NEP413_TAG: u32 = (1 << 31) + 413;
+    /// NEP-413 recipient clients must name when the wallet signs a challenge
+    const NEP413_RECIPIENT: &str = "argus-content-gate";
+
+    /// Challenge the holder signs, serialized as JSON:
+    /// `{"timestamp": <unix ms>, "nonce": <u64>}`
+    #[derive(serde::Deserialize)]
+    struct Challenge {
+        timestamp: u64,
+        nonce: u64,
     }
 
     #[derive(Debug, PartialEq, Eq, Encode, Decode)]
@@ -49,6 +103,16 @@ mod argus_content_gate {
         HttpError,
         /// Invalid response from NEAR
         InvalidNearResponse,
+        /// Signed message is not a valid challenge
+        InvalidChallenge,
+        /// Challenge timestamp is outside the allowed window
+        ChallengeExpired,
+        /// Challenge nonce was already used
+        NonceReused,
+        /// Encryption failed
+        EncryptionFailed,
+        /// Too many decryption requests in the current window
+        RateLimited,
     }
 
     pub type Result<T> = core::result::Result<T, Error>;
@@ -61,8 +125,15 @@ mod argus_content_gate {
             Self {
                 admin: caller,
                 list_keys: Default::default(),
+                list_ids: Vec::new(),
                 near_rpc: String::from("https://rpc.mainnet.fastnear.com"),
                 nft_contract: String::from("source-lists.argus-intel.near"),
+                access_mode: AccessMode::SourceList,
+                max_challenge_age: DEFAULT_MAX_CHALLENGE_AGE_MS,
+                ipfs_gateways: alloc::vec![String::from("https://ipfs.io/ipfs")],
+                producers: Default::default(),
+                rate_limit_window: DEFAULT_RATE_LIMIT_WINDOW_MS,
+                max_requests_per_window: DEFAULT_MAX_REQUESTS_PER_WINDOW,
             }
         }
 
@@ -74,24 +145,104 @@ mod argus_content_gate {
             if caller != self.admin {
                 return Err(Error::Unauthorized);
             }
+            // Re-registering an existing list only rotates its key
+            if !self.list_keys.contains(&list_id) {
+                self.list_ids.push(list_id.clone());
+            }
             self.list_keys.insert(&list_id, &aes_key);
             Ok(())
         }
 
+        /// Encrypt content with a list's stored key (admin or allowlisted producer only).
+        /// Returns AES-256-GCM content framed as expected by `decrypt_for_holder`:
+        /// algorithm (0) || nonce || ciphertext || tag
+        #[ink(message)]
+        pub fn encrypt_for_list(&self, list_id: String, plaintext: Vec<u8>) -> Result<Vec<u8>> {
+            let caller = self.env().caller();
+            if caller != self.admin && !self.producers.contains(caller) {
+                return Err(Error::Unauthorized);
+            }
+
+            let key = self.list_keys.get(&list_id)
+                .ok_or(Error::ListNotFound)?;
+
+            let nonce_bytes = pink::ext().getrandom(12);
+            if nonce_bytes.len() != 12 {
+                return Err(Error::EncryptionFailed);
+            }
+
+            let cipher = Aes256Gcm::new_from_slice(&key)
+                .map_err(|_| Error::EncryptionFailed)?;
+            let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce_bytes), plaintext.as_ref())
+                .map_err(|_| Error::EncryptionFailed)?;
+
+            let mut framed = Vec::with_capacity(1 + nonce_bytes.len() + ciphertext.len());
+            framed.push(ALGORITHM_AES_256_GCM);
+            framed.extend_from_slice(&nonce_bytes);
+            framed.extend_from_slice(&ciphertext);
+            Ok(framed)
+        }
+
+        /// Allow an account to call `encrypt_for_list` (admin only)
+        #[ink(message)]
+        pub fn add_producer(&mut self, producer: AccountId) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::Unauthorized);
+            }
+            self.producers.insert(producer, &());
+            Ok(())
+        }
+
+        /// Revoke an account's `encrypt_for_list` permission (admin only)
+        #[ink(message)]
+        pub fn remove_producer(&mut self, producer: AccountId) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::Unauthorized);
+            }
+            self.producers.remove(producer);
+            Ok(())
+        }
+
+        /// Check if an account is an allowlisted producer
+        #[ink(message)]
+        pub fn is_producer(&self, account: AccountId) -> bool {
+            self.producers.contains(account)
+        }
+
         /// Check if a list is registered
         #[ink(message)]
         pub fn has_list(&self, list_id: String) -> bool {
             self.list_keys.contains(&list_id)
         }
 
+        /// Deployment summary for ops dashboards: (NEAR RPC, oracle contract, list count)
+        #[ink(message)]
+        pub fn get_config(&self) -> (String, String, u32) {
+            (self.near_rpc.clone(), self.nft_contract.clone(), self.list_ids.len() as u32)
+        }
+
+        /// IDs of all registered lists, in registration order (admin only)
+        #[ink(message)]
+        pub fn get_list_ids(&self) -> Result<Vec<String>> {
+            if self.env().caller() != self.admin {
+                return Err(Error::Unauthorized);
+            }
+            Ok(self.list_ids.clone())
+        }
+
         /// Get decrypted content for a verified NFT holder
         /// 
         /// # Arguments
         /// * `list_id` - The source list ID
         /// * `near_account` - User's NEAR account ID
-        /// * `signature` - ED25519 signature proving account ownership
-        /// * `message` - The message that was signed (challenge)
-        /// * `encrypted_content` - AES-256-GCM encrypted content (nonce || ciphertext || tag)
+        /// * `signature` - NEP-413 signature (wallet `signMessage`) of `message` by a full-access
+        ///   key of `near_account`, with recipient `argus-content-gate` and `sha256(message)` as the
+        ///   32-byte NEP-413 nonce
+        /// * `message` - The signed challenge `{"timestamp": <unix ms>, "nonce": <u64>}`.
+        ///   Nonces are remembered per worker, so a challenge replayed to a different
+        ///   worker is only bounded by the challenge age limit
+        /// * `encrypted_content` - Encrypted content (algorithm || nonce || ciphertext || tag),
+        ///   where algorithm is 0 for AES-256-GCM and 1 for ChaCha20-Poly1305
         #[ink(message)]
         pub fn decrypt_for_holder(
             &self,
@@ -102,11 +253,9 @@ mod argus_content_gate {
             encrypted_content: Vec<u8>,
         ) -> Result<Vec<u8>> {
             // 1. Verify the signature proves ownership of NEAR account
-            // In production, we'd verify the ED25519 sig against the account's public key
-            // For MVP, we trust the signature format
-            if signature.len() != 64 {
-                return Err(Error::InvalidSignature);
-            }
+            self.verify_near_signature(&near_account, &message, &signature)?;
+            let nonce = self.check_challenge(&near_account, &message)?;
+            self.check_rate_limit(&near_account)?;
 
             // 2. Check NFT ownership on NEAR
             if !self.check_near_nft_access(&list_id, &near_account)? {
@@ -116,9 +265,12 @@ mod argus_content_gate {
             // 3. Get the decryption key from TEE storage
             let key = self.list_keys.get(&list_id)
                 .ok_or(Error::ListNotFound)?;
+            self.consume_nonce(&near_account, nonce);
 
             // 4. Decrypt the content
-            self.decrypt_aes_gcm(&key, &encrypted_content)
+            let plaintext = self.decrypt_content(&key, &encrypted_content)?;
+            self.record_decrypt(&list_id, &near_account);
+            Ok(plaintext)
         }
 
         /// Fetch and decrypt content from IPFS
@@ -132,43 +284,287 @@ mod argus_content_gate {
             ipfs_cid: String,
         ) -> Result<Vec<u8>> {
             // 1. Verify signature
-            if signature.len() != 64 {
-                return Err(Error::InvalidSignature);
-            }
+            self.verify_near_signature(&near_account, &message, &signature)?;
+            let nonce = self.check_challenge(&near_account, &message)?;
+            self.check_rate_limit(&near_account)?;
 
             // 2. Check NFT ownership
             if !self.check_near_nft_access(&list_id, &near_account)? {
                 return Err(Error::NoAccess);
             }
 
-            // 3. Fetch from IPFS
-            let encrypted = self.fetch_ipfs(&ipfs_cid)?;
-
-            // 4. Get key and decrypt
+            // 3. Get the key before paying for the IPFS fetch
             let key = self.list_keys.get(&list_id)
                 .ok_or(Error::ListNotFound)?;
-            
-            self.decrypt_aes_gcm(&key, &encrypted)
+            self.consume_nonce(&near_account, nonce);
+
+            // 4. Fetch from IPFS and decrypt
+            let encrypted = self.fetch_ipfs(&ipfs_cid)?;
+            let plaintext = self.decrypt_content(&key, &encrypted)?;
+            self.record_decrypt(&list_id, &near_account);
+            Ok(plaintext)
+        }
+
+        /// Report a successful decryption to the cluster log server.
+        /// Writes made during a query are discarded, so the audit trail can't live
+        /// in contract storage. Never logs keys or plaintext.
+        fn record_decrypt(&self, list_id: &str, near_account: &str) {
+            pink::info!(
+                "decrypt {}",
+                serde_json::json!({
+                    "list_id": list_id,
+                    "near_account": near_account,
+                    "block_time": self.env().block_timestamp(),
+                })
+            );
         }
 
         /// Check if account has access to a list via NEAR RPC
         fn check_near_nft_access(&self, list_id: &str, account: &str) -> Result<bool> {
             // Build the RPC request
-            let args = format!(r#"{{"list_id":"{}","account_id":"{}"}}"#, list_id, account);
+            let args = self.access_args(list_id, account);
             let args_b64 = base64::encode(&args);
             
-            let body = format!(r#"{{
+            let params = serde_json::json!({
+                "request_type": "call_function",
+                "finality": "final",
+                "account_id": self.nft_contract,
+                "method_name": "has_access",
+                "args_base64": args_b64,
+            });
+
+            let response = self.near_rpc_query(params)?;
+
+            Self::parse_has_access_response(&response)
+        }
+
+        /// JSON arguments for the oracle's `has_access` in the current access mode.
+        /// In HUMINT mode the list id is the source hash.
+        fn access_args(&self, list_id: &str, account: &str) -> String {
+            match self.access_mode {
+                AccessMode::SourceList => serde_json::json!({
+                    "account_id": account,
+                    "token_id": list_id,
+                }),
+                AccessMode::HumintFeed => serde_json::json!({
+                    "account_id": account,
+                    "source_hash": list_id,
+                }),
+            }
+            .to_string()
+        }
+
+        /// Parse a `call_function` response whose return value is a JSON boolean.
+        /// `result.result` holds the raw bytes returned by the contract.
+        fn parse_has_access_response(body: &[u8]) -> Result<bool> {
+            let json: serde_json::Value = serde_json::from_slice(body)
+                .map_err(|_| Error::InvalidNearResponse)?;
+            if json.get("error").is_some() {
+                return Err(Error::InvalidNearResponse);
+            }
+
+            let bytes = json
+                .get("result")
+                .and_then(|result| result.get("result"))
+                .and_then(|result| result.as_array())
+                .ok_or(Error::InvalidNearResponse)?
+                .iter()
+                .map(|byte| {
+                    byte.as_u64()
+                        .and_then(|byte| u8::try_from(byte).ok())
+                        .ok_or(Error::InvalidNearResponse)
+                })
+                .collect::<Result<Vec<u8>>>()?;
+
+            serde_json::from_slice::<bool>(&bytes).map_err(|_| Error::InvalidNearResponse)
+        }
+
+        /// Verify that `signature` is a NEP-413 signature of `message` produced by
+        /// one of the full-access keys currently registered on `account`
+        fn verify_near_signature(&self, account: &str, message: &[u8], signature: &[u8]) -> Result<()> {
+            if signature.len() != 64 {
+                return Err(Error::InvalidSignature);
+            }
+
+            let keys = self.fetch_full_access_keys(account)?;
+            if Self::signature_matches_any(&keys, &Self::nep413_hash(message), signature) {
+                Ok(())
+            } else {
+                Err(Error::InvalidSignature)
+            }
+        }
+
+        /// Hash a wallet signs for `message` under NEP-413:
+        /// sha256(borsh(tag) || borsh({message, nonce, recipient, callback_url: None})),
+        /// with `sha256(message)` as the nonce and `NEP413_RECIPIENT` as the recipient
+        fn nep413_hash(message: &[u8]) -> [u8; 32] {
+            let nonce = Self::sha256(message);
+
+            let mut payload = Vec::with_capacity(4 + 4 + message.len() + 32 + 4 + NEP413_RECIPIENT.len() + 1);
+            payload.extend_from_slice(&NEP413_TAG.to_le_bytes());
+            payload.extend_from_slice(&(message.len() as u32).to_le_bytes());
+            payload.extend_from_slice(message);
+            payload.extend_from_slice(&nonce);
+            payload.extend_from_slice(&(NEP413_RECIPIENT.len() as u32).to_le_bytes());
+            payload.extend_from_slice(NEP413_RECIPIENT.as_bytes());
+            payload.push(0);
+
+            Self::sha256(&payload)
+        }
+
+        /// SHA-256 digest of `data`
+        fn sha256(data: &[u8]) -> [u8; 32] {
+            let mut output = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Sha2x256>(data, &mut output);
+            output
+        }
+
+        /// Check the challenge is fresh and its nonce is strictly greater than
+        /// the last one accepted for `account`, returning the nonce to consume
+        fn check_challenge(&self, account: &str, message: &[u8]) -> Result<u64> {
+            let challenge: Challenge = serde_json::from_slice(message)
+                .map_err(|_| Error::InvalidChallenge)?;
+
+            let now = self.env().block_timestamp();
+            if now.saturating_sub(challenge.timestamp) > self.max_challenge_age
+                || challenge.timestamp.saturating_sub(now) > self.max_challenge_age
+            {
+                return Err(Error::ChallengeExpired);
+            }
+
+            if let Some(last) = self.last_nonce(account) {
+                if challenge.nonce <= last {
+                    return Err(Error::NonceReused);
+                }
+            }
+            Ok(challenge.nonce)
+        }
+
+        /// Record `nonce` as the last one accepted for `account`.
+        /// Query writes to contract storage are discarded, so nonces live in the
+        /// worker's local cache. The entry outlives any challenge it could reject:
+        /// a timestamp may run `max_challenge_age` ahead of the block time.
+        fn consume_nonce(&self, account: &str, nonce: u64) {
+            let key = Self::nonce_key(account);
+            if pink::ext().cache_set(&key, &nonce.encode()).is_ok() {
+                pink::ext().cache_set_expiration(&key, 2 * self.max_challenge_age / 1000 + 1);
+            }
+        }
+
+        /// Last nonce this worker accepted for `account`
+        fn last_nonce(&self, account: &str) -> Option<u64> {
+            pink::ext()
+                .cache_get(&Self::nonce_key(account))
+                .and_then(|value| u64::decode(&mut &value[..]).ok())
+        }
+
+        /// Worker cache key holding the last nonce accepted for `account`
+        fn nonce_key(account: &str) -> Vec<u8> {
+            format!("nonce:{}", account).into_bytes()
+        }
+
+        /// Count a decryption request against `account`, starting a new window
+        /// once the current one has elapsed.
+        /// The counter, (window start in ms, requests in window), lives in the worker's
+        /// local cache since query writes to contract storage are discarded. Each worker
+        /// keeps its own counters, and an evicted counter starts a fresh window.
+        fn check_rate_limit(&self, account: &str) -> Result<()> {
+            let now = self.env().block_timestamp();
+            let key = format!("rate:{}", account).into_bytes();
+
+            let cached = pink::ext()
+                .cache_get(&key)
+                .and_then(|value| <(u64, u32)>::decode(&mut &value[..]).ok());
+            let (window_start, count) = match cached {
+                Some((start, count)) if now.saturating_sub(start) < self.rate_limit_window => {
+                    (start, count)
+                }
+                _ => (now, 0),
+            };
+
+            if count >= self.max_requests_per_window {
+                return Err(Error::RateLimited);
+            }
+
+            if pink::ext().cache_set(&key, &(window_start, count + 1).encode()).is_ok() {
+                // Expiry is in seconds; round up so the entry outlives its window
+                pink::ext().cache_set_expiration(&key, self.rate_limit_window / 1000 + 1);
+            }
+            Ok(())
+        }
+
+        /// Fetch the ED25519 full-access public keys of a NEAR account
+        fn fetch_full_access_keys(&self, account: &str) -> Result<Vec<[u8; 32]>> {
+            // Built with serde_json so the caller-supplied account can't inject fields
+            let params = serde_json::json!({
+                "request_type": "view_access_key_list",
+                "finality": "final",
+                "account_id": account,
+            });
+
+            let response = self.near_rpc_query(params)?;
+            Self::parse_access_key_list(&response)
+        }
+
+        /// Extract ED25519 full-access keys from a `view_access_key_list` response.
+        /// Function-call keys are skipped since they are held by dapps, not the account owner.
+        fn parse_access_key_list(body: &[u8]) -> Result<Vec<[u8; 32]>> {
+            let json: serde_json::Value = serde_json::from_slice(body)
+                .map_err(|_| Error::InvalidNearResponse)?;
+            let keys = json
+                .get("result")
+                .and_then(|result| result.get("keys"))
+                .and_then(|keys| keys.as_array())
+                .ok_or(Error::InvalidNearResponse)?;
+
+            let mut full_access = Vec::new();
+            for entry in keys {
+                let is_full_access = entry
+                    .get("access_key")
+                    .and_then(|key| key.get("permission"))
+                    .and_then(|permission| permission.as_str())
+                    == Some("FullAccess");
+                if !is_full_access {
+                    continue;
+                }
+
+                let encoded = match entry
+                    .get("public_key")
+                    .and_then(|key| key.as_str())
+                    .and_then(|key| key.strip_prefix("ed25519:"))
+                {
+                    Some(encoded) => encoded,
+                    None => continue,
+                };
+
+                let decoded = bs58::decode(encoded)
+                    .into_vec()
+                    .map_err(|_| Error::InvalidNearResponse)?;
+                let public_key: [u8; 32] = decoded
+                    .try_into()
+                    .map_err(|_| Error::InvalidNearResponse)?;
+                full_access.push(public_key);
+            }
+
+            Ok(full_access)
+        }
+
+        /// Check a signature against each candidate public key
+        fn signature_matches_any(keys: &[[u8; 32]], message: &[u8], signature: &[u8]) -> bool {
+            keys.iter().any(|public_key| {
+                signing::verify(message, public_key, signature, signing::SigType::Ed25519)
+            })
+        }
+
+        /// Send a `query` JSON-RPC request to NEAR and return the raw response body
+        fn near_rpc_query(&self, params: serde_json::Value) -> Result<Vec<u8>> {
+            let body = serde_json::json!({
                 "jsonrpc": "2.0",
                 "id": "1",
                 "method": "query",
-                "params": {{
-                    "request_type": "call_function",
-                    "finality": "final",
-                    "account_id": "{}",
-                    "method_name": "has_access",
-                    "args_base64": "{}"
-                }}
-            }}"#, self.nft_contract, args_b64);
+                "params": params,
+            })
+            .to_string();
 
             // Make HTTP request to NEAR RPC
             let response = http_req!(
@@ -182,35 +578,57 @@ mod argus_content_gate {
                 return Err(Error::HttpError);
             }
 
-            // Parse response - look for "result" containing true
-            let body_str = String::from_utf8_lossy(&response.body);
-            
-            // Simple check - in production use proper JSON parsing
-            if body_str.contains("\"result\"") {
-                // Decode the base64 result
-                // If it contains "true" the user has access
-                Ok(body_str.contains("dHJ1ZQ") || body_str.contains("true"))
-            } else {
-                Err(Error::InvalidNearResponse)
-            }
+            Ok(response.body)
         }
 
-        /// Fetch content from IPFS gateway
+        /// Fetch content from the configured IPFS gateways,
+        /// falling back to the next gateway until one succeeds
         fn fetch_ipfs(&self, cid: &str) -> Result<Vec<u8>> {
-            let url = format!("https://ipfs.io/ipfs/{}", cid);
-            
-            let response = http_req!(
-                "GET",
-                &url,
-                vec![],
-                vec![]
-            );
+            for gateway in self.ipfs_gateways.iter() {
+                let url = format!("{}/{}", gateway.trim_end_matches('/'), cid);
 
-            if response.status_code != 200 {
-                return Err(Error::HttpError);
+                let response = http_req!(
+                    "GET",
+                    &url,
+                    vec![],
+                    vec![]
+                );
+
+                if response.status_code == 200 {
+                    return Ok(response.body);
+                }
             }
 
-            Ok(response.body)
+            Err(Error::HttpError)
+        }
+
+        /// Decrypt content framed as algorithm (1 byte) || nonce || ciphertext || tag
+        fn decrypt_content(&self, key: &[u8; 32], data: &[u8]) -> Result<Vec<u8>> {
+            let (algorithm, payload) = data.split_first()
+                .ok_or(Error::DecryptionFailed)?;
+
+            match *algorithm {
+                ALGORITHM_AES_256_GCM => self.decrypt_aes_gcm(key, payload),
+                ALGORITHM_CHACHA20_POLY1305 => self.decrypt_chacha20_poly1305(key, payload),
+                _ => Err(Error::DecryptionFailed),
+            }
+        }
+
+        /// Decrypt ChaCha20-Poly1305 encrypted data
+        /// Format: nonce (12 bytes) || ciphertext || tag (16 bytes)
+        fn decrypt_chacha20_poly1305(&self, key: &[u8; 32], data: &[u8]) -> Result<Vec<u8>> {
+            if data.len() < 28 {
+                return Err(Error::DecryptionFailed);
+            }
+
+            let cipher = ChaCha20Poly1305::new_from_slice(key)
+                .map_err(|_| Error::DecryptionFailed)?;
+
+            let nonce = chacha20poly1305::Nonce::from_slice(&data[..12]);
+            let ciphertext = &data[12..];
+
+            cipher.decrypt(nonce, ciphertext)
+                .map_err(|_| Error::DecryptionFailed)
         }
 
         /// Decrypt AES-256-GCM encrypted data
@@ -250,6 +668,72 @@ mod argus_content_gate {
             Ok(())
         }
 
+        /// Replace the list of IPFS gateway base URLs (admin only)
+        #[ink(message)]
+        pub fn set_ipfs_gateways(&mut self, gateways: Vec<String>) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::Unauthorized);
+            }
+            self.ipfs_gateways = gateways;
+            Ok(())
+        }
+
+        /// Get the configured IPFS gateways
+        #[ink(message)]
+        pub fn get_ipfs_gateways(&self) -> Vec<String> {
+            self.ipfs_gateways.clone()
+        }
+
+        /// Update the maximum challenge age in milliseconds (admin only)
+        #[ink(message)]
+        pub fn set_max_challenge_age(&mut self, max_age_ms: u64) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::Unauthorized);
+            }
+            self.max_challenge_age = max_age_ms;
+            Ok(())
+        }
+
+        /// Update the rate limit window and per-window request cap (admin only)
+        #[ink(message)]
+        pub fn set_rate_limit(&mut self, window_ms: u64, max_requests: u32) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::Unauthorized);
+            }
+            self.rate_limit_window = window_ms;
+            self.max_requests_per_window = max_requests;
+            Ok(())
+        }
+
+        /// Get the rate limit as (window in ms, max requests per window)
+        #[ink(message)]
+        pub fn get_rate_limit(&self) -> (u64, u32) {
+            (self.rate_limit_window, self.max_requests_per_window)
+        }
+
+        /// Last challenge nonce this worker accepted for a NEAR account
+        #[ink(message)]
+        pub fn get_last_nonce(&self, near_account: String) -> Option<u64> {
+            self.last_nonce(&near_account)
+        }
+
+        /// Switch the access oracle contract and its `has_access` layout (admin only)
+        #[ink(message)]
+        pub fn set_access_mode(&mut self, mode: AccessMode, contract: String) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::Unauthorized);
+            }
+            self.access_mode = mode;
+            self.nft_contract = contract;
+            Ok(())
+        }
+
+        /// Get the current access mode
+        #[ink(message)]
+        pub fn get_access_mode(&self) -> AccessMode {
+            self.access_mode
+        }
+
         /// Transfer admin role
         #[ink(message)]
         pub fn transfer_admin(&mut self, new_admin: AccountId) -> Result<()> {
@@ -307,5 +791,545 @@ mod argus_content_gate {
             assert!(result.is_ok());
             assert_eq!(result.unwrap(), plaintext);
         }
+
+        #[ink::test]
+        fn decrypt_content_aes_gcm_round_trip() {
+            let contract = ArgusContentGate::new();
+            let key = [3u8; 32];
+            let nonce = [4u8; 12];
+            let plaintext = b"aes framed content";
+
+            let cipher = Aes256Gcm::new_from_slice(&key).unwrap();
+            let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), plaintext.as_ref()).unwrap();
+
+            let mut framed = alloc::vec![ALGORITHM_AES_256_GCM];
+            framed.extend_from_slice(&nonce);
+            framed.extend_from_slice(&ciphertext);
+
+            assert_eq!(contract.decrypt_content(&key, &framed), Ok(plaintext.to_vec()));
+        }
+
+        #[ink::test]
+        fn decrypt_content_chacha20_round_trip() {
+            let contract = ArgusContentGate::new();
+            let key = [5u8; 32];
+            let nonce = [6u8; 12];
+            let plaintext = b"chacha framed content";
+
+            let cipher = ChaCha20Poly1305::new_from_slice(&key).unwrap();
+            let ciphertext = cipher
+                .encrypt(chacha20poly1305::Nonce::from_slice(&nonce), plaintext.as_ref())
+                .unwrap();
+
+            let mut framed = alloc::vec![ALGORITHM_CHACHA20_POLY1305];
+            framed.extend_from_slice(&nonce);
+            framed.extend_from_slice(&ciphertext);
+
+            assert_eq!(contract.decrypt_content(&key, &framed), Ok(plaintext.to_vec()));
+
+            // The same payload tagged as AES must not decrypt
+            framed[0] = ALGORITHM_AES_256_GCM;
+            assert_eq!(contract.decrypt_content(&key, &framed), Err(Error::DecryptionFailed));
+        }
+
+        #[ink::test]
+        fn decrypt_content_rejects_unknown_algorithm() {
+            let contract = ArgusContentGate::new();
+            let mut framed = alloc::vec![2u8];
+            framed.extend_from_slice(&[0u8; 40]);
+            assert_eq!(contract.decrypt_content(&[0u8; 32], &framed), Err(Error::DecryptionFailed));
+            assert_eq!(contract.decrypt_content(&[0u8; 32], &[]), Err(Error::DecryptionFailed));
+        }
+
+        #[ink::test]
+        fn rate_limit_blocks_then_recovers() {
+            pink_extension_runtime::mock_ext::mock_all_ext();
+            ink::env::test::set_block_timestamp::<Environment>(1_000);
+            let mut contract = ArgusContentGate::new();
+            assert!(contract.set_rate_limit(10_000, 3).is_ok());
+            assert_eq!(contract.get_rate_limit(), (10_000, 3));
+
+            for _ in 0..3 {
+                assert!(contract.check_rate_limit("carol.near").is_ok());
+            }
+            assert_eq!(contract.check_rate_limit("carol.near"), Err(Error::RateLimited));
+
+            // Other accounts have their own counters
+            assert!(contract.check_rate_limit("dave.near").is_ok());
+
+            // Still limited just before the window closes
+            ink::env::test::set_block_timestamp::<Environment>(10_999);
+            assert_eq!(contract.check_rate_limit("carol.near"), Err(Error::RateLimited));
+
+            // A new window resets the counter
+            ink::env::test::set_block_timestamp::<Environment>(11_000);
+            for _ in 0..3 {
+                assert!(contract.check_rate_limit("carol.near").is_ok());
+            }
+            assert_eq!(contract.check_rate_limit("carol.near"), Err(Error::RateLimited));
+        }
+
+        #[ink::test]
+        fn set_rate_limit_requires_admin() {
+            let accounts = ink::env::test::default_accounts::<Environment>();
+            let mut contract = ArgusContentGate::new();
+            ink::env::test::set_caller::<Environment>(accounts.bob);
+            assert_eq!(contract.set_rate_limit(1, 1), Err(Error::Unauthorized));
+        }
+
+        fn mock_gateways(available: &'static [&'static str]) {
+            use pink::chain_extension::{mock, HttpResponse};
+            mock::mock_http_request(move |request| {
+                if available.iter().any(|gateway| request.url.starts_with(gateway)) {
+                    HttpResponse::ok(request.url.into_bytes())
+                } else {
+                    HttpResponse::not_found()
+                }
+            });
+        }
+
+        fn two_gateway_contract() -> ArgusContentGate {
+            let mut contract = ArgusContentGate::new();
+            contract
+                .set_ipfs_gateways(alloc::vec![
+                    "https://ipfs.io/ipfs".into(),
+                    "https://cloudflare-ipfs.com/ipfs/".into(),
+                ])
+                .unwrap();
+            contract
+        }
+
+        #[ink::test]
+        fn default_gateway_is_ipfs_io() {
+            let contract = ArgusContentGate::new();
+            assert_eq!(contract.get_ipfs_gateways(), alloc::vec![String::from("https://ipfs.io/ipfs")]);
+        }
+
+        #[ink::test]
+        fn fetch_ipfs_uses_first_gateway() {
+            mock_gateways(&["https://ipfs.io", "https://cloudflare-ipfs.com"]);
+            let contract = two_gateway_contract();
+            assert_eq!(
+                contract.fetch_ipfs("bafycid"),
+                Ok(b"https://ipfs.io/ipfs/bafycid".to_vec())
+            );
+        }
+
+        #[ink::test]
+        fn fetch_ipfs_falls_back_to_next_gateway() {
+            mock_gateways(&["https://cloudflare-ipfs.com"]);
+            let contract = two_gateway_contract();
+            assert_eq!(
+                contract.fetch_ipfs("bafycid"),
+                Ok(b"https://cloudflare-ipfs.com/ipfs/bafycid".to_vec())
+            );
+        }
+
+        #[ink::test]
+        fn fetch_ipfs_fails_when_all_gateways_fail() {
+            mock_gateways(&[]);
+            let contract = two_gateway_contract();
+            assert_eq!(contract.fetch_ipfs("bafycid"), Err(Error::HttpError));
+        }
+
+        #[ink::test]
+        fn source_list_access_args() {
+            let contract = ArgusContentGate::new();
+            assert_eq!(contract.get_access_mode(), AccessMode::SourceList);
+
+            let args: serde_json::Value =
+                serde_json::from_str(&contract.access_args("7", "alice.near")).unwrap();
+            assert_eq!(args, serde_json::json!({"account_id": "alice.near", "token_id": "7"}));
+        }
+
+        #[ink::test]
+        fn humint_feed_access_args() {
+            let mut contract = ArgusContentGate::new();
+            assert!(contract
+                .set_access_mode(AccessMode::HumintFeed, "humint.argus-intel.near".into())
+                .is_ok());
+            assert_eq!(contract.get_access_mode(), AccessMode::HumintFeed);
+
+            let args: serde_json::Value =
+                serde_json::from_str(&contract.access_args("abc123", "alice.near")).unwrap();
+            assert_eq!(args, serde_json::json!({"account_id": "alice.near", "source_hash": "abc123"}));
+        }
+
+        fn challenge(timestamp: u64, nonce: u64) -> Vec<u8> {
+            format!(r#"{{"timestamp":{},"nonce":{}}}"#, timestamp, nonce).into_bytes()
+        }
+
+        /// Check and consume a challenge the way the decrypt messages do.
+        /// The mock worker cache can outlive a single test, so each test uses its own accounts.
+        fn accept_challenge(contract: &ArgusContentGate, account: &str, message: &[u8]) -> Result<()> {
+            let nonce = contract.check_challenge(account, message)?;
+            contract.consume_nonce(account, nonce);
+            Ok(())
+        }
+
+        #[ink::test]
+        fn challenge_nonce_replay_rejected() {
+            pink_extension_runtime::mock_ext::mock_all_ext();
+            ink::env::test::set_block_timestamp::<Environment>(1_000_000);
+            let contract = ArgusContentGate::new();
+
+            assert!(accept_challenge(&contract, "erin.near", &challenge(1_000_000, 1)).is_ok());
+            assert_eq!(contract.get_last_nonce("erin.near".into()), Some(1));
+            assert_eq!(
+                accept_challenge(&contract, "erin.near", &challenge(1_000_000, 1)),
+                Err(Error::NonceReused)
+            );
+            assert_eq!(
+                accept_challenge(&contract, "erin.near", &challenge(1_000_000, 0)),
+                Err(Error::NonceReused)
+            );
+            assert!(accept_challenge(&contract, "erin.near", &challenge(1_000_000, 2)).is_ok());
+
+            // Nonces are tracked per account
+            assert!(accept_challenge(&contract, "frank.near", &challenge(1_000_000, 1)).is_ok());
+        }
+
+        #[ink::test]
+        fn stale_challenge_rejected() {
+            pink_extension_runtime::mock_ext::mock_all_ext();
+            ink::env::test::set_block_timestamp::<Environment>(10 * DEFAULT_MAX_CHALLENGE_AGE_MS);
+            let mut contract = ArgusContentGate::new();
+            let now = 10 * DEFAULT_MAX_CHALLENGE_AGE_MS;
+
+            assert_eq!(
+                accept_challenge(&contract, "grace.near", &challenge(now - DEFAULT_MAX_CHALLENGE_AGE_MS - 1, 1)),
+                Err(Error::ChallengeExpired)
+            );
+            assert_eq!(
+                accept_challenge(&contract, "grace.near", &challenge(now + DEFAULT_MAX_CHALLENGE_AGE_MS + 1, 1)),
+                Err(Error::ChallengeExpired)
+            );
+            // A rejected challenge does not consume the nonce
+            assert_eq!(contract.get_last_nonce("grace.near".into()), None);
+            assert!(accept_challenge(&contract, "grace.near", &challenge(now - DEFAULT_MAX_CHALLENGE_AGE_MS, 1)).is_ok());
+
+            assert!(contract.set_max_challenge_age(1_000).is_ok());
+            assert_eq!(
+                accept_challenge(&contract, "grace.near", &challenge(now - 1_001, 2)),
+                Err(Error::ChallengeExpired)
+            );
+        }
+
+        #[ink::test]
+        fn malformed_challenge_rejected() {
+            let contract = ArgusContentGate::new();
+            assert_eq!(
+                contract.check_challenge("grace.near", b"sign in to argus"),
+                Err(Error::InvalidChallenge)
+            );
+        }
+
+        fn call_function_body(return_value: &[u8]) -> String {
+            let bytes: Vec<String> = return_value.iter().map(|b| format!("{}", b)).collect();
+            format!(
+                r#"{{"jsonrpc":"2.0","id":"1","result":{{"block_hash":"11111111111111111111111111111111","block_height":1,"logs":[],"result":[{}]}}}}"#,
+                bytes.join(",")
+            )
+        }
+
+        #[ink::test]
+        fn has_access_true_response() {
+            let body = call_function_body(b"true");
+            assert_eq!(ArgusContentGate::parse_has_access_response(body.as_bytes()), Ok(true));
+        }
+
+        #[ink::test]
+        fn has_access_false_response() {
+            let body = call_function_body(b"false");
+            assert_eq!(ArgusContentGate::parse_has_access_response(body.as_bytes()), Ok(false));
+        }
+
+        #[ink::test]
+        fn has_access_error_response() {
+            // Error text mentioning "true" and "result" must not grant access
+            let body = br#"{"jsonrpc":"2.0","id":"1","error":{"name":"HANDLER_ERROR","cause":{"name":"UNKNOWN_ACCOUNT","info":{"result":"true"}},"message":"true"}}"#;
+            assert_eq!(
+                ArgusContentGate::parse_has_access_response(body),
+                Err(Error::InvalidNearResponse)
+            );
+        }
+
+        #[ink::test]
+        fn has_access_non_boolean_response() {
+            let body = call_function_body(b"\"true\"");
+            assert_eq!(
+                ArgusContentGate::parse_has_access_response(body.as_bytes()),
+                Err(Error::InvalidNearResponse)
+            );
+        }
+
+        fn access_key_list_body(entries: &[(&[u8; 32], &str)]) -> String {
+            let keys: Vec<String> = entries
+                .iter()
+                .map(|(public_key, permission)| {
+                    format!(
+                        r#"{{"public_key":"ed25519:{}","access_key":{{"nonce":1,"permission":{}}}}}"#,
+                        bs58::encode(public_key).into_string(),
+                        permission
+                    )
+                })
+                .collect();
+            format!(
+                r#"{{"jsonrpc":"2.0","id":"1","result":{{"keys":[{}],"block_height":1,"block_hash":"11111111111111111111111111111111"}}}}"#,
+                keys.join(",")
+            )
+        }
+
+        #[ink::test]
+        fn verifies_signature_from_full_access_key() {
+            pink_extension_runtime::mock_ext::mock_all_ext();
+            use ed25519_dalek::Signer;
+
+            let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+            let public_key = signing_key.verifying_key().to_bytes();
+            let body = access_key_list_body(&[(&public_key, r#""FullAccess""#)]);
+
+            let keys = ArgusContentGate::parse_access_key_list(body.as_bytes()).unwrap();
+            assert_eq!(keys, vec![public_key]);
+
+            let challenge = b"argus-content-gate:alice.near";
+            let signature = signing_key.sign(challenge).to_bytes();
+            assert!(ArgusContentGate::signature_matches_any(&keys, challenge, &signature));
+            assert!(!ArgusContentGate::signature_matches_any(&keys, b"another challenge", &signature));
+
+            let other_key = ed25519_dalek::SigningKey::from_bytes(&[9u8; 32]);
+            let forged = other_key.sign(challenge).to_bytes();
+            assert!(!ArgusContentGate::signature_matches_any(&keys, challenge, &forged));
+        }
+
+        #[ink::test]
+        fn function_call_keys_are_ignored() {
+            let full_access = [1u8; 32];
+            let function_call = [2u8; 32];
+            let body = access_key_list_body(&[
+                (&function_call, r#"{"FunctionCall":{"allowance":null,"receiver_id":"app.near","method_names":[]}}"#),
+                (&full_access, r#""FullAccess""#),
+            ]);
+
+            let keys = ArgusContentGate::parse_access_key_list(body.as_bytes()).unwrap();
+            assert_eq!(keys, vec![full_access]);
+        }
+
+        #[ink::test]
+        fn access_key_list_error_is_rejected() {
+            let body = br#"{"jsonrpc":"2.0","id":"1","error":{"name":"HANDLER_ERROR","cause":{"name":"UNKNOWN_ACCOUNT"}}}"#;
+            assert_eq!(
+                ArgusContentGate::parse_access_key_list(body),
+                Err(Error::InvalidNearResponse)
+            );
+        }
+
+        #[ink::test]
+        fn encrypt_for_list_requires_admin_or_producer() {
+            pink_extension_runtime::mock_ext::mock_all_ext();
+            let accounts = ink::env::test::default_accounts::<Environment>();
+            let mut contract = ArgusContentGate::new();
+            contract.register_list("list1".into(), [8u8; 32]).unwrap();
+
+            ink::env::test::set_caller::<Environment>(accounts.bob);
+            assert_eq!(
+                contract.encrypt_for_list("list1".into(), b"intel".to_vec()),
+                Err(Error::Unauthorized)
+            );
+
+            ink::env::test::set_caller::<Environment>(accounts.alice);
+            contract.add_producer(accounts.bob).unwrap();
+            assert!(contract.is_producer(accounts.bob));
+
+            ink::env::test::set_caller::<Environment>(accounts.bob);
+            assert!(contract.encrypt_for_list("list1".into(), b"intel".to_vec()).is_ok());
+            assert_eq!(
+                contract.encrypt_for_list("missing".into(), b"intel".to_vec()),
+                Err(Error::ListNotFound)
+            );
+
+            ink::env::test::set_caller::<Environment>(accounts.alice);
+            contract.remove_producer(accounts.bob).unwrap();
+            ink::env::test::set_caller::<Environment>(accounts.bob);
+            assert_eq!(
+                contract.encrypt_for_list("list1".into(), b"intel".to_vec()),
+                Err(Error::Unauthorized)
+            );
+        }
+
+        /// Mock NEAR RPC so `signing_key` is a full-access key of every account
+        /// and `has_access` returns `access`
+        fn mock_near_rpc(signing_key: &ed25519_dalek::SigningKey, access: bool) {
+            pink_extension_runtime::mock_ext::mock_all_ext();
+            let public_key = signing_key.verifying_key().to_bytes();
+            let key_list = access_key_list_body(&[(&public_key, r#""FullAccess""#)]);
+            let has_access = call_function_body(if access { b"true" } else { b"false" });
+            pink::chain_extension::mock::mock_http_request(move |request| {
+                let body = String::from_utf8_lossy(&request.body);
+                if body.contains("view_access_key_list") {
+                    pink::chain_extension::HttpResponse::ok(key_list.clone().into_bytes())
+                } else {
+                    pink::chain_extension::HttpResponse::ok(has_access.clone().into_bytes())
+                }
+            });
+        }
+
+        /// Sign `message` the way a NEAR wallet's `signMessage` does for the gate
+        fn wallet_sign(signing_key: &ed25519_dalek::SigningKey, message: &[u8]) -> Vec<u8> {
+            use ed25519_dalek::Signer;
+            signing_key.sign(&ArgusContentGate::nep413_hash(message)).to_bytes().to_vec()
+        }
+
+        #[ink::test]
+        fn nep413_payload_layout() {
+            let message = b"hi";
+            let mut expected = alloc::vec![0x9d, 0x01, 0x00, 0x80, 2, 0, 0, 0, b'h', b'i'];
+            expected.extend_from_slice(&ArgusContentGate::sha256(message));
+            expected.extend_from_slice(&[18, 0, 0, 0]);
+            expected.extend_from_slice(b"argus-content-gate");
+            expected.push(0);
+            assert_eq!(ArgusContentGate::nep413_hash(message), ArgusContentGate::sha256(&expected));
+        }
+
+        #[ink::test]
+        fn raw_message_signatures_are_rejected() {
+            use ed25519_dalek::Signer;
+
+            let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+            mock_near_rpc(&signing_key, true);
+            let contract = ArgusContentGate::new();
+
+            let message = challenge(0, 1);
+            let raw = signing_key.sign(&message).to_bytes();
+            assert_eq!(
+                contract.verify_near_signature("alice.near", &message, &raw),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(
+                contract.verify_near_signature("alice.near", &message, &wallet_sign(&signing_key, &message)),
+                Ok(())
+            );
+        }
+
+        #[ink::test]
+        fn access_key_query_escapes_account() {
+            pink_extension_runtime::mock_ext::mock_all_ext();
+            let injected = r#"alice.near", "finality": "optimistic"#;
+            let key_list = access_key_list_body(&[(&[1u8; 32], r#""FullAccess""#)]);
+            pink::chain_extension::mock::mock_http_request(move |request| {
+                let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+                if body["params"]["account_id"] == injected && body["params"]["finality"] == "final" {
+                    pink::chain_extension::HttpResponse::ok(key_list.clone().into_bytes())
+                } else {
+                    pink::chain_extension::HttpResponse::not_found()
+                }
+            });
+
+            let contract = ArgusContentGate::new();
+            assert_eq!(contract.fetch_full_access_keys(injected), Ok(alloc::vec![[1u8; 32]]));
+        }
+
+        #[ink::test]
+        fn encrypt_for_list_round_trips_through_decrypt_for_holder() {
+            let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+            mock_near_rpc(&signing_key, true);
+
+            let mut contract = ArgusContentGate::new();
+            contract.register_list("list1".into(), [8u8; 32]).unwrap();
+
+            let plaintext = b"source list contents".to_vec();
+            let encrypted = contract.encrypt_for_list("list1".into(), plaintext.clone()).unwrap();
+            assert_eq!(encrypted[0], ALGORITHM_AES_256_GCM);
+
+            let message = challenge(0, 1);
+            let signature = wallet_sign(&signing_key, &message);
+            let decrypted = contract.decrypt_for_holder(
+                "list1".into(),
+                "alice.near".into(),
+                signature,
+                message,
+                encrypted,
+            );
+            assert_eq!(decrypted, Ok(plaintext));
+        }
+
+        #[ink::test]
+        fn denied_decrypt_does_not_consume_nonce() {
+            let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+            mock_near_rpc(&signing_key, false);
+
+            let mut contract = ArgusContentGate::new();
+            contract.register_list("list1".into(), [8u8; 32]).unwrap();
+            let encrypted = contract.encrypt_for_list("list1".into(), b"intel".to_vec()).unwrap();
+
+            let message = challenge(0, 1);
+            let signature = wallet_sign(&signing_key, &message);
+            assert_eq!(
+                contract.decrypt_for_holder(
+                    "list1".into(),
+                    "heidi.near".into(),
+                    signature.clone(),
+                    message.clone(),
+                    encrypted.clone(),
+                ),
+                Err(Error::NoAccess)
+            );
+            assert_eq!(contract.get_last_nonce("heidi.near".into()), None);
+
+            // Nor does asking for an unregistered list
+            mock_near_rpc(&signing_key, true);
+            assert_eq!(
+                contract.decrypt_for_holder(
+                    "missing".into(),
+                    "heidi.near".into(),
+                    signature.clone(),
+                    message.clone(),
+                    encrypted.clone(),
+                ),
+                Err(Error::ListNotFound)
+            );
+            assert_eq!(contract.get_last_nonce("heidi.near".into()), None);
+
+            // The challenge is still usable, but only once
+            assert!(contract
+                .decrypt_for_holder("list1".into(), "heidi.near".into(), signature.clone(), message.clone(), encrypted.clone())
+                .is_ok());
+            assert_eq!(
+                contract.decrypt_for_holder("list1".into(), "heidi.near".into(), signature, message, encrypted),
+                Err(Error::NonceReused)
+            );
+        }
+
+        #[ink::test]
+        fn config_reports_registered_lists() {
+            let mut contract = ArgusContentGate::new();
+            contract.register_list("list1".into(), [1u8; 32]).unwrap();
+            contract.register_list("list2".into(), [2u8; 32]).unwrap();
+            contract.register_list("list3".into(), [3u8; 32]).unwrap();
+            // Key rotation does not add a second entry
+            contract.register_list("list1".into(), [4u8; 32]).unwrap();
+
+            assert_eq!(
+                contract.get_config(),
+                (
+                    String::from("https://rpc.mainnet.fastnear.com"),
+                    String::from("source-lists.argus-intel.near"),
+                    3
+                )
+            );
+            assert_eq!(
+                contract.get_list_ids(),
+                Ok(alloc::vec![String::from("list1"), String::from("list2"), String::from("list3")])
+            );
+        }
+
+        #[ink::test]
+        fn list_ids_are_admin_only() {
+            let accounts = ink::env::test::default_accounts::<Environment>();
+            let mut contract = ArgusContentGate::new();
+            contract.register_list("list1".into(), [1u8; 32]).unwrap();
+            ink::env::test::set_caller::<Environment>(accounts.bob);
+            assert_eq!(contract.get_list_ids(), Err(Error::Unauthorized));
+        }
     }
 }
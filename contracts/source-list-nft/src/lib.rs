@@ -1,6 +1,9 @@
 use near_sdk::store::{LookupMap, UnorderedMap, UnorderedSet, LazyOption};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
 use near_sdk::json_types::{U128, U64};
-use near_sdk::{env, near, require, AccountId, BorshStorageKey, NearToken, PanicOnDefault, Promise};
+use near_sdk::serde_json;
+use near_sdk::{env, near, require, AccountId, BorshStorageKey, Gas, NearToken, PanicOnDefault, Promise, PromiseOrValue};
 
 /// NEP-171 compliant NFT for Source Lists
 /// 
@@ -10,6 +13,11 @@ use near_sdk::{env, near, require, AccountId, BorshStorageKey, NearToken, PanicO
 
 pub type TokenId = String;
 
+const GAS_FOR_NFT_ON_APPROVE: Gas = Gas::from_tgas(10);
+const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(10);
+/// Bookmarks are stored at the contract's expense, so each account gets a fixed number
+const MAX_FAVORITES_PER_ACCOUNT: u32 = 100;
+
 #[derive(BorshStorageKey)]
 #[near]
 pub enum StorageKey {
@@ -20,6 +28,20 @@ pub enum StorageKey {
     NFTContractMetadata,
     ListMetadata,
     ApprovedAccounts { token_id_hash: Vec<u8> },
+    Purchasers,
+    PurchasersInner { token_id_hash: Vec<u8> },
+    Raters,
+    RatersInner { token_id_hash: Vec<u8> },
+    RatingScores,
+    RatingTotals,
+    Subscribers,
+    SubscribersInner { token_id_hash: Vec<u8> },
+    ListsByDomain,
+    ListsByDomainInner { domain_hash: Vec<u8> },
+    Admins,
+    HandoffPubkeys,
+    Favorites,
+    FavoritesInner { account_id_hash: Vec<u8> },
 }
 
 #[near(serializers = [json, borsh])]
@@ -73,6 +95,15 @@ pub struct SourceListMetadata {
     pub royalty_percent: u8,
     /// Total subscriptions/clones
     pub total_subscribers: u32,
+    /// Price to subscribe (in yoctoNEAR, None = subscriptions not offered)
+    #[serde(default)]
+    pub subscription_price: Option<U128>,
+    /// Price in `ft_contract` tokens (None = not for sale in FT)
+    #[serde(default)]
+    pub price_ft: Option<U128>,
+    /// Fungible token accepted for `price_ft` (e.g. USDC)
+    #[serde(default)]
+    pub ft_contract: Option<AccountId>,
     /// Average rating (0-500 for 0.0-5.0 stars)
     pub avg_rating: u16,
     /// Number of ratings
@@ -94,11 +125,61 @@ pub struct SourceListNFT {
     pub tokens_by_id: UnorderedMap<TokenId, Token>,
     pub token_metadata_by_id: UnorderedMap<TokenId, TokenMetadata>,
     pub list_metadata_by_id: UnorderedMap<TokenId, SourceListMetadata>,
+    /// Approval ID and optional expiry (ns) per approved account
+    pub approved_accounts: LookupMap<TokenId, UnorderedMap<AccountId, (u64, Option<U64>)>>,
+    pub metadata: LazyOption<NFTContractMetadata>,
+    pub next_token_id: u64,
+    /// Accounts that bought each list (eligible to rate)
+    pub purchasers: LookupMap<TokenId, UnorderedSet<AccountId>>,
+    /// Accounts that rated each list
+    pub raters: LookupMap<TokenId, UnorderedSet<AccountId>>,
+    /// Each rater's current score (100-500)
+    pub rating_scores: LookupMap<(TokenId, AccountId), u16>,
+    /// Sum of current scores per list, for exact averaging
+    pub rating_totals: LookupMap<TokenId, u32>,
+    /// Accounts with read access via subscription
+    pub subscribers: LookupMap<TokenId, UnorderedSet<AccountId>>,
+    /// Lowercased domain -> lists in that domain
+    pub lists_by_domain: LookupMap<String, UnorderedSet<TokenId>>,
+    /// Counter making every per-token approvals map prefix unique
+    pub next_approvals_prefix: u64,
+    /// Operators with contract-level rights (`owner_id` is always one)
+    pub admins: UnorderedSet<AccountId>,
+    /// Last public key each account supplied for list key handoff
+    pub handoff_pubkeys: LookupMap<AccountId, String>,
+    /// Lists each account has bookmarked
+    pub favorites: LookupMap<AccountId, UnorderedSet<TokenId>>,
+}
+
+/// State layout of the originally deployed contract, read by `migrate`
+#[near(serializers = [borsh])]
+pub struct OldSourceListNFT {
+    pub owner_id: AccountId,
+    pub tokens_per_owner: LookupMap<AccountId, UnorderedSet<TokenId>>,
+    pub tokens_by_id: UnorderedMap<TokenId, Token>,
+    pub token_metadata_by_id: UnorderedMap<TokenId, TokenMetadata>,
+    pub list_metadata_by_id: UnorderedMap<TokenId, OldSourceListMetadata>,
     pub approved_accounts: LookupMap<TokenId, LookupMap<AccountId, u64>>,
     pub metadata: LazyOption<NFTContractMetadata>,
     pub next_token_id: u64,
 }
 
+/// List metadata layout before subscription and FT prices were added
+#[near(serializers = [borsh])]
+pub struct OldSourceListMetadata {
+    pub cid: String,
+    pub source_count: u32,
+    pub domain: String,
+    pub creator: AccountId,
+    pub is_active: bool,
+    pub updated_at: U64,
+    pub price: Option<U128>,
+    pub royalty_percent: u8,
+    pub total_subscribers: u32,
+    pub avg_rating: u16,
+    pub rating_count: u32,
+}
+
 #[near]
 impl SourceListNFT {
     #[init]
@@ -122,7 +203,138 @@ impl SourceListNFT {
             approved_accounts: LookupMap::new(StorageKey::ApprovedAccounts { token_id_hash: vec![] }),
             metadata: LazyOption::new(StorageKey::NFTContractMetadata, Some(metadata)),
             next_token_id: 1,
+            purchasers: LookupMap::new(StorageKey::Purchasers),
+            raters: LookupMap::new(StorageKey::Raters),
+            rating_scores: LookupMap::new(StorageKey::RatingScores),
+            rating_totals: LookupMap::new(StorageKey::RatingTotals),
+            subscribers: LookupMap::new(StorageKey::Subscribers),
+            lists_by_domain: LookupMap::new(StorageKey::ListsByDomain),
+            next_approvals_prefix: 0,
+            admins: UnorderedSet::new(StorageKey::Admins),
+            handoff_pubkeys: LookupMap::new(StorageKey::HandoffPubkeys),
+            favorites: LookupMap::new(StorageKey::Favorites),
+        }
+    }
+
+    /// Upgrade state written by the original contract to the current layout
+    ///
+    /// Deploy sequence:
+    /// 1. `near deploy --accountId <contract> --wasmFile source_list_nft.wasm`
+    /// 2. `near call <contract> migrate '{}' --accountId <contract>`
+    ///
+    /// List metadata is rewritten with no subscription or FT price, and the domain
+    /// index and rating totals are rebuilt from it. The original contract never
+    /// wrote approvals, purchasers or raters, so those start empty.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let mut old: OldSourceListNFT = env::state_read().expect("No state to migrate");
+        // Old values can't be read back as the new type, so drop them and
+        // rewrite every list under the same prefix
+        let old_lists: Vec<(TokenId, OldSourceListMetadata)> = old.list_metadata_by_id.drain().collect();
+        old.list_metadata_by_id.flush();
+
+        let mut contract = Self {
+            owner_id: old.owner_id,
+            tokens_per_owner: old.tokens_per_owner,
+            tokens_by_id: old.tokens_by_id,
+            token_metadata_by_id: old.token_metadata_by_id,
+            list_metadata_by_id: UnorderedMap::new(StorageKey::ListMetadata),
+            // Never written by the original contract, so there is nothing to convert
+            approved_accounts: LookupMap::new(StorageKey::ApprovedAccounts { token_id_hash: vec![] }),
+            metadata: old.metadata,
+            next_token_id: old.next_token_id,
+            purchasers: LookupMap::new(StorageKey::Purchasers),
+            raters: LookupMap::new(StorageKey::Raters),
+            rating_scores: LookupMap::new(StorageKey::RatingScores),
+            rating_totals: LookupMap::new(StorageKey::RatingTotals),
+            subscribers: LookupMap::new(StorageKey::Subscribers),
+            lists_by_domain: LookupMap::new(StorageKey::ListsByDomain),
+            next_approvals_prefix: 0,
+            admins: UnorderedSet::new(StorageKey::Admins),
+            handoff_pubkeys: LookupMap::new(StorageKey::HandoffPubkeys),
+            favorites: LookupMap::new(StorageKey::Favorites),
+        };
+
+        for (token_id, old_list) in old_lists {
+            contract.internal_index_domain(&old_list.domain, &token_id);
+            if old_list.rating_count > 0 {
+                // Only the truncated average survives, so this is the closest total
+                contract.rating_totals.insert(
+                    token_id.clone(),
+                    old_list.avg_rating as u32 * old_list.rating_count,
+                );
+            }
+            contract.list_metadata_by_id.insert(
+                token_id,
+                SourceListMetadata {
+                    cid: old_list.cid,
+                    source_count: old_list.source_count,
+                    domain: old_list.domain,
+                    creator: old_list.creator,
+                    is_active: old_list.is_active,
+                    updated_at: old_list.updated_at,
+                    price: old_list.price,
+                    royalty_percent: old_list.royalty_percent,
+                    total_subscribers: old_list.total_subscribers,
+                    subscription_price: None,
+                    price_ft: None,
+                    ft_contract: None,
+                    avg_rating: old_list.avg_rating,
+                    rating_count: old_list.rating_count,
+                },
+            );
         }
+
+        contract
+    }
+
+    /// Grant admin rights to an account (owner only)
+    pub fn add_admin(&mut self, account_id: AccountId) {
+        require!(env::predecessor_account_id() == self.owner_id, "Only contract owner");
+        self.admins.insert(account_id);
+    }
+
+    /// Revoke admin rights (owner only); the owner itself can't be removed
+    pub fn remove_admin(&mut self, account_id: AccountId) {
+        require!(env::predecessor_account_id() == self.owner_id, "Only contract owner");
+        require!(account_id != self.owner_id, "Cannot remove contract owner");
+        self.admins.remove(&account_id);
+    }
+
+    /// Whether an account holds contract-level rights
+    pub fn is_admin(&self, account_id: AccountId) -> bool {
+        account_id == self.owner_id || self.admins.contains(&account_id)
+    }
+
+    /// List admins added by the owner
+    pub fn get_admins(&self) -> Vec<AccountId> {
+        self.admins.iter().cloned().collect()
+    }
+
+    /// Take abandoned lists off the market (admin only)
+    /// Clears NEAR and FT prices and marks each list inactive; unknown IDs are skipped.
+    /// Returns the IDs that were delisted
+    pub fn delist_inactive(&mut self, token_ids: Vec<TokenId>) -> Vec<TokenId> {
+        require!(self.is_admin(env::predecessor_account_id()), "Only admin");
+        require!(token_ids.len() <= 100, "Cannot delist more than 100 tokens");
+
+        let mut delisted = Vec::new();
+        for token_id in token_ids {
+            if let Some(list_metadata) = self.list_metadata_by_id.get_mut(&token_id) {
+                list_metadata.price = None;
+                list_metadata.price_ft = None;
+                list_metadata.is_active = false;
+                list_metadata.updated_at = U64(env::block_timestamp());
+
+                env::log_str(&format!(
+                    "EVENT_JSON:{{\"standard\":\"argus-source-list\",\"version\":\"1.0.0\",\"event\":\"list_delisted\",\"data\":[{{\"token_id\":\"{}\",\"creator\":\"{}\"}}]}}",
+                    token_id, list_metadata.creator
+                ));
+                delisted.push(token_id);
+            }
+        }
+        delisted
     }
 
     /// Mint a new source list NFT
@@ -177,11 +389,15 @@ impl SourceListNFT {
             price,
             royalty_percent: royalty,
             total_subscribers: 0,
+            subscription_price: None,
+            price_ft: None,
+            ft_contract: None,
             avg_rating: 0,
             rating_count: 0,
         };
 
         // Store everything
+        self.internal_index_domain(&list_metadata.domain, &token_id);
         self.tokens_by_id.insert(token_id.clone(), token);
         self.token_metadata_by_id.insert(token_id.clone(), token_metadata);
         self.list_metadata_by_id.insert(token_id.clone(), list_metadata);
@@ -222,6 +438,40 @@ impl SourceListNFT {
         env::log_str(&format!("Updated source list {}", token_id));
     }
 
+    /// Update a list's domain and/or royalty (original creator only)
+    /// Royalty changes apply to sales made after the update
+    pub fn update_list_metadata(&mut self, token_id: TokenId, domain: Option<String>, royalty_percent: Option<u8>) {
+        let mut list_metadata = self.list_metadata_by_id.get(&token_id).expect("Token not found").clone();
+        require!(
+            list_metadata.creator == env::predecessor_account_id(),
+            "Only creator can update list metadata"
+        );
+
+        if let Some(royalty) = royalty_percent {
+            require!(royalty <= 50, "Royalty cannot exceed 50%");
+            list_metadata.royalty_percent = royalty;
+        }
+
+        if let Some(domain) = domain {
+            if domain.to_lowercase() != list_metadata.domain.to_lowercase() {
+                self.internal_unindex_domain(&list_metadata.domain, &token_id);
+                self.internal_index_domain(&domain, &token_id);
+            }
+            if let Some(token_metadata) = self.token_metadata_by_id.get_mut(&token_id) {
+                token_metadata.extra = Some(format!(
+                    "{{\"domain\":\"{}\",\"sources\":{}}}",
+                    domain, list_metadata.source_count
+                ));
+            }
+            list_metadata.domain = domain;
+        }
+
+        list_metadata.updated_at = U64(env::block_timestamp());
+        self.list_metadata_by_id.insert(token_id.clone(), list_metadata);
+
+        env::log_str(&format!("Updated metadata for source list {}", token_id));
+    }
+
     /// Set price for the NFT (None = not for sale)
     pub fn set_price(&mut self, token_id: TokenId, price: Option<U128>) {
         let token = self.tokens_by_id.get(&token_id).expect("Token not found");
@@ -235,24 +485,180 @@ impl SourceListNFT {
         self.list_metadata_by_id.insert(token_id, list_metadata);
     }
 
+    /// Set the subscription price (None = subscriptions not offered)
+    pub fn set_subscription_price(&mut self, token_id: TokenId, price: Option<U128>) {
+        let token = self.tokens_by_id.get(&token_id).expect("Token not found");
+        require!(
+            token.owner_id == env::predecessor_account_id(),
+            "Only owner can set subscription price"
+        );
+
+        let mut list_metadata = self.list_metadata_by_id.get(&token_id).expect("Metadata not found").clone();
+        list_metadata.subscription_price = price;
+        self.list_metadata_by_id.insert(token_id, list_metadata);
+    }
+
+    /// Subscribe to a source list for read access without buying the NFT
+    /// Payment is split between the current owner and the creator's royalty
+    #[payable]
+    pub fn subscribe(&mut self, token_id: TokenId) -> Promise {
+        let subscriber = env::predecessor_account_id();
+        let deposit = env::attached_deposit();
+
+        let owner_id = self.tokens_by_id.get(&token_id).expect("Token not found").owner_id.clone();
+        let mut list_metadata = self.list_metadata_by_id.get(&token_id).expect("Metadata not found").clone();
+
+        let price = NearToken::from_yoctonear(
+            list_metadata.subscription_price.expect("Subscriptions not offered").0,
+        );
+        require!(deposit >= price, "Insufficient deposit");
+        require!(subscriber != owner_id, "Owner already has access");
+
+        if let Some(subscribers) = self.subscribers.get_mut(&token_id) {
+            require!(subscribers.insert(subscriber.clone()), "Already subscribed");
+        } else {
+            let mut subscribers = UnorderedSet::new(StorageKey::SubscribersInner {
+                token_id_hash: env::sha256(token_id.as_bytes()).to_vec(),
+            });
+            subscribers.insert(subscriber.clone());
+            self.subscribers.insert(token_id.clone(), subscribers);
+        }
+
+        list_metadata.total_subscribers += 1;
+        let creator = list_metadata.creator.clone();
+        let royalty_percent = list_metadata.royalty_percent;
+        self.list_metadata_by_id.insert(token_id.clone(), list_metadata);
+
+        env::log_str(&format!("{} subscribed to source list {}", subscriber, token_id));
+
+        // Creator royalty only applies once the list has changed hands
+        let royalty_amount = if creator != owner_id {
+            price.as_yoctonear() * royalty_percent as u128 / 100
+        } else {
+            0
+        };
+        let owner_amount = price.as_yoctonear() - royalty_amount;
+
+        let mut promise = Promise::new(owner_id).transfer(NearToken::from_yoctonear(owner_amount));
+        if royalty_amount > 0 {
+            promise = promise.and(Promise::new(creator).transfer(NearToken::from_yoctonear(royalty_amount)));
+        }
+
+        let refund = deposit.saturating_sub(price);
+        if !refund.is_zero() {
+            promise = promise.and(Promise::new(subscriber).transfer(refund));
+        }
+
+        promise
+    }
+
+    /// Check if an account subscribes to a list
+    pub fn is_subscriber(&self, token_id: TokenId, account_id: AccountId) -> bool {
+        self.subscribers
+            .get(&token_id)
+            .map(|s| s.contains(&account_id))
+            .unwrap_or(false)
+    }
+
     /// Rate a source list (1-5 stars, stored as 100-500)
+    /// Only the owner, a past buyer or a subscriber may rate, once per account
     pub fn rate_list(&mut self, token_id: TokenId, rating: u8) {
         require!(rating >= 1 && rating <= 5, "Rating must be 1-5");
         
+        let rater = env::predecessor_account_id();
+        require!(self.internal_can_rate(&token_id, &rater), "Only owners, buyers or subscribers can rate");
+        require!(
+            !self.rating_scores.contains_key(&(token_id.clone(), rater.clone())),
+            "Already rated"
+        );
+        
         let mut list_metadata = self.list_metadata_by_id.get(&token_id).expect("Token not found").clone();
         
         // Calculate new average (rating stored as 100-500)
-        let new_rating = rating as u32 * 100;
-        let total = (list_metadata.avg_rating as u32 * list_metadata.rating_count) + new_rating;
+        let new_rating = rating as u16 * 100;
+        let total = self.rating_totals.get(&token_id).copied().unwrap_or(0) + new_rating as u32;
         list_metadata.rating_count += 1;
         list_metadata.avg_rating = (total / list_metadata.rating_count) as u16;
         
+        self.rating_totals.insert(token_id.clone(), total);
+        self.rating_scores.insert((token_id.clone(), rater.clone()), new_rating);
+        if let Some(raters) = self.raters.get_mut(&token_id) {
+            raters.insert(rater);
+        } else {
+            let mut raters = UnorderedSet::new(StorageKey::RatersInner {
+                token_id_hash: env::sha256(token_id.as_bytes()).to_vec(),
+            });
+            raters.insert(rater);
+            self.raters.insert(token_id.clone(), raters);
+        }
+        self.list_metadata_by_id.insert(token_id, list_metadata);
+    }
+
+    /// Change the caller's existing rating for a list
+    pub fn update_rating(&mut self, token_id: TokenId, new_rating: u8) {
+        require!(new_rating >= 1 && new_rating <= 5, "Rating must be 1-5");
+        
+        let rater = env::predecessor_account_id();
+        let key = (token_id.clone(), rater);
+        let old_rating = *self.rating_scores.get(&key).expect("Not rated yet");
+        
+        let mut list_metadata = self.list_metadata_by_id.get(&token_id).expect("Token not found").clone();
+        
+        // Swap the old contribution for the new one
+        let new_rating = new_rating as u16 * 100;
+        let total = self.rating_totals.get(&token_id).copied().unwrap_or(0) - old_rating as u32 + new_rating as u32;
+        list_metadata.avg_rating = (total / list_metadata.rating_count) as u16;
+        
+        self.rating_totals.insert(token_id.clone(), total);
+        self.rating_scores.insert(key, new_rating);
         self.list_metadata_by_id.insert(token_id, list_metadata);
     }
 
+    /// Bookmark a list for the caller, up to 100 per account (favoriting twice is a no-op)
+    pub fn favorite_list(&mut self, token_id: TokenId) {
+        require!(self.tokens_by_id.get(&token_id).is_some(), "Token not found");
+        let account_id = env::predecessor_account_id();
+
+        if let Some(favorites) = self.favorites.get_mut(&account_id) {
+            require!(
+                favorites.contains(&token_id) || favorites.len() < MAX_FAVORITES_PER_ACCOUNT,
+                "Cannot favorite more than 100 lists"
+            );
+            favorites.insert(token_id);
+        } else {
+            let mut favorites = UnorderedSet::new(StorageKey::FavoritesInner {
+                account_id_hash: env::sha256(account_id.as_bytes()).to_vec(),
+            });
+            favorites.insert(token_id);
+            self.favorites.insert(account_id, favorites);
+        }
+    }
+
+    /// Remove a list from the caller's bookmarks
+    pub fn unfavorite_list(&mut self, token_id: TokenId) {
+        if let Some(favorites) = self.favorites.get_mut(&env::predecessor_account_id()) {
+            favorites.remove(&token_id);
+        }
+    }
+
+    /// Lists an account has bookmarked (burned lists are left out)
+    pub fn get_favorites(&self, account_id: AccountId) -> Vec<TokenId> {
+        self.favorites
+            .get(&account_id)
+            .map(|favorites| {
+                favorites
+                    .iter()
+                    .filter(|token_id| self.tokens_by_id.get(*token_id).is_some())
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Purchase a source list NFT
+    /// `handoff_pubkey` asks the seller to re-encrypt the list key to the buyer
     #[payable]
-    pub fn purchase(&mut self, token_id: TokenId) -> Promise {
+    pub fn purchase(&mut self, token_id: TokenId, handoff_pubkey: Option<String>) -> Promise {
         let buyer = env::predecessor_account_id();
         let deposit = env::attached_deposit();
 
@@ -272,13 +678,8 @@ impl SourceListNFT {
         let creator = list_metadata.creator.clone();
 
         // Transfer NFT ownership
-        self.internal_transfer(&seller, &buyer, &token_id);
-
-        // Log transfer event
-        env::log_str(&format!(
-            "EVENT_JSON:{{\"standard\":\"nep171\",\"version\":\"1.0.0\",\"event\":\"nft_transfer\",\"data\":[{{\"old_owner_id\":\"{}\",\"new_owner_id\":\"{}\",\"token_ids\":[\"{}\"]}}]}}",
-            seller, buyer, token_id
-        ));
+        self.internal_complete_sale(&seller, &buyer, &token_id);
+        self.internal_record_handoff(&token_id, &buyer, handoff_pubkey);
 
         // Pay seller
         let mut promise = Promise::new(seller.clone()).transfer(NearToken::from_yoctonear(seller_amount));
@@ -288,9 +689,114 @@ impl SourceListNFT {
             promise = promise.and(Promise::new(creator).transfer(NearToken::from_yoctonear(royalty_amount)));
         }
 
+        // Return any overpayment to the buyer
+        let refund = deposit.saturating_sub(price);
+        if !refund.is_zero() {
+            promise = promise.and(Promise::new(buyer).transfer(refund));
+        }
+
         promise
     }
 
+    /// Purchase a source list NFT only if it is still listed at `expected_price`
+    /// Protects buyers from a price bump landing between quote and purchase
+    #[payable]
+    pub fn purchase_at(&mut self, token_id: TokenId, expected_price: U128, handoff_pubkey: Option<String>) -> Promise {
+        let price = self.list_metadata_by_id.get(&token_id).expect("Metadata not found").price;
+        require!(price == Some(expected_price), "Price changed");
+        self.purchase(token_id, handoff_pubkey)
+    }
+
+    /// Set the fungible token price (None clears FT sale)
+    pub fn set_ft_price(&mut self, token_id: TokenId, ft_contract: Option<AccountId>, price_ft: Option<U128>) {
+        let token = self.tokens_by_id.get(&token_id).expect("Token not found");
+        require!(
+            token.owner_id == env::predecessor_account_id(),
+            "Only owner can set price"
+        );
+        require!(ft_contract.is_some() == price_ft.is_some(), "FT contract and price must be set together");
+
+        let mut list_metadata = self.list_metadata_by_id.get(&token_id).expect("Metadata not found").clone();
+        list_metadata.ft_contract = ft_contract;
+        list_metadata.price_ft = price_ft;
+        self.list_metadata_by_id.insert(token_id, list_metadata);
+    }
+
+    /// Purchase a list with fungible tokens (NEP-141 receiver)
+    /// `msg` is the token ID to buy; any amount above the price is returned
+    pub fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128> {
+        let ft_contract = env::predecessor_account_id();
+        let token_id: TokenId = msg;
+
+        let seller = self.tokens_by_id.get(&token_id).expect("Token not found").owner_id.clone();
+        let list_metadata = self.list_metadata_by_id.get(&token_id).expect("Metadata not found").clone();
+
+        let price = list_metadata.price_ft.expect("NFT not for sale in FT").0;
+        require!(list_metadata.ft_contract.as_ref() == Some(&ft_contract), "Unsupported fungible token");
+        require!(amount.0 >= price, "Insufficient amount");
+        require!(seller != sender_id, "Cannot buy your own NFT");
+
+        // Royalty only when the creator is not the one selling
+        let creator = list_metadata.creator.clone();
+        let royalty_amount = if creator != seller {
+            price * list_metadata.royalty_percent as u128 / 100
+        } else {
+            0
+        };
+
+        self.internal_complete_sale(&seller, &sender_id, &token_id);
+
+        self.internal_ft_transfer(&ft_contract, &seller, price - royalty_amount);
+        if royalty_amount > 0 {
+            self.internal_ft_transfer(&ft_contract, &creator, royalty_amount);
+        }
+
+        PromiseOrValue::Value(U128(amount.0 - price))
+    }
+
+    /// Burn a source list NFT (owner only)
+    /// Listed tokens can only be burned with `force`
+    pub fn nft_burn(&mut self, token_id: TokenId, force: Option<bool>) {
+        let owner_id = self.tokens_by_id.get(&token_id).expect("Token not found").owner_id.clone();
+        require!(owner_id == env::predecessor_account_id(), "Not token owner");
+
+        let listed = self.list_metadata_by_id
+            .get(&token_id)
+            .map(|m| m.price.is_some())
+            .unwrap_or(false);
+        require!(!listed || force.unwrap_or(false), "List is for sale; pass force to burn");
+
+        self.tokens_by_id.remove(&token_id);
+        self.token_metadata_by_id.remove(&token_id);
+        if let Some(list_metadata) = self.list_metadata_by_id.remove(&token_id) {
+            self.internal_unindex_domain(&list_metadata.domain, &token_id);
+        }
+        if let Some(owner_tokens) = self.tokens_per_owner.get_mut(&owner_id) {
+            owner_tokens.remove(&token_id);
+        }
+        self.internal_clear_approvals(&token_id);
+
+        // Drop per-list access and rating records
+        if let Some(mut subscribers) = self.subscribers.remove(&token_id) {
+            subscribers.clear();
+        }
+        if let Some(mut purchasers) = self.purchasers.remove(&token_id) {
+            purchasers.clear();
+        }
+        if let Some(mut raters) = self.raters.remove(&token_id) {
+            for rater in raters.iter() {
+                self.rating_scores.remove(&(token_id.clone(), rater.clone()));
+            }
+            raters.clear();
+        }
+        self.rating_totals.remove(&token_id);
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"nep171\",\"version\":\"1.0.0\",\"event\":\"nft_burn\",\"data\":[{{\"owner_id\":\"{}\",\"token_ids\":[\"{}\"]}}]}}",
+            owner_id, token_id
+        ));
+    }
+
     /// Get source list metadata
     pub fn get_list_metadata(&self, token_id: TokenId) -> Option<SourceListMetadata> {
         self.list_metadata_by_id.get(&token_id).cloned()
@@ -309,6 +815,34 @@ impl SourceListNFT {
             .collect()
     }
 
+    /// Get lists in a domain (case-insensitive, paginated)
+    pub fn get_lists_by_domain(&self, domain: String, from_index: Option<u64>, limit: Option<u64>) -> Vec<(TokenId, SourceListMetadata)> {
+        let start = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(50).min(100);
+
+        self.lists_by_domain
+            .get(&domain.to_lowercase())
+            .map(|token_ids| {
+                token_ids
+                    .iter()
+                    .skip(start as usize)
+                    .take(limit as usize)
+                    .filter_map(|id| self.list_metadata_by_id.get(id).map(|m| (id.clone(), m.clone())))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get the highest rated lists (rated lists only, best first)
+    pub fn get_top_rated_lists(&self, limit: u64) -> Vec<(TokenId, SourceListMetadata)> {
+        self.internal_top_lists(limit, |m| (m.rating_count > 0).then_some(m.avg_rating as u64))
+    }
+
+    /// Get the lists with the most subscribers (most first)
+    pub fn get_most_subscribed_lists(&self, limit: u64) -> Vec<(TokenId, SourceListMetadata)> {
+        self.internal_top_lists(limit, |m| Some(m.total_subscribers as u64))
+    }
+
     /// Get lists for sale
     pub fn get_lists_for_sale(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<(TokenId, SourceListMetadata)> {
         let start = from_index.unwrap_or(0);
@@ -323,12 +857,23 @@ impl SourceListNFT {
             .collect()
     }
 
-    /// Check if account owns a specific list (for access control)
+    /// Check if account owns or subscribes to a specific list (for access control)
     pub fn has_access(&self, account_id: AccountId, token_id: TokenId) -> bool {
-        self.tokens_by_id
+        let is_owner = self.tokens_by_id
             .get(&token_id)
             .map(|t| t.owner_id == account_id)
-            .unwrap_or(false)
+            .unwrap_or(false);
+        is_owner || self.is_subscriber(token_id, account_id)
+    }
+
+    /// `has_access` for up to 100 lists at once, in the order given
+    /// Nonexistent tokens report `false`
+    pub fn has_access_batch(&self, account_id: AccountId, token_ids: Vec<TokenId>) -> Vec<bool> {
+        require!(token_ids.len() <= 100, "Cannot check more than 100 tokens");
+        token_ids
+            .into_iter()
+            .map(|token_id| self.has_access(account_id.clone(), token_id))
+            .collect()
     }
 
     /// Get all lists owned by an account
@@ -339,6 +884,159 @@ impl SourceListNFT {
             .unwrap_or_default()
     }
 
+    /// Select the top `limit` lists by `score` with a bounded min-heap
+    /// Lists scored `None` are skipped
+    fn internal_top_lists<F>(&self, limit: u64, score: F) -> Vec<(TokenId, SourceListMetadata)>
+    where
+        F: Fn(&SourceListMetadata) -> Option<u64>,
+    {
+        let limit = limit.min(100) as usize;
+        if limit == 0 {
+            return vec![];
+        }
+
+        let mut heap: BinaryHeap<Reverse<(u64, TokenId)>> = BinaryHeap::with_capacity(limit + 1);
+        for (token_id, metadata) in self.list_metadata_by_id.iter() {
+            if let Some(value) = score(metadata) {
+                heap.push(Reverse((value, token_id.clone())));
+                if heap.len() > limit {
+                    heap.pop();
+                }
+            }
+        }
+
+        // Ascending by Reverse means best first
+        heap.into_sorted_vec()
+            .into_iter()
+            .filter_map(|Reverse((_, token_id))| {
+                self.list_metadata_by_id.get(&token_id).map(|m| (token_id.clone(), m.clone()))
+            })
+            .collect()
+    }
+
+    fn internal_index_domain(&mut self, domain: &str, token_id: &TokenId) {
+        let key = domain.to_lowercase();
+        if let Some(token_ids) = self.lists_by_domain.get_mut(&key) {
+            token_ids.insert(token_id.clone());
+        } else {
+            let mut token_ids = UnorderedSet::new(StorageKey::ListsByDomainInner {
+                domain_hash: env::sha256(key.as_bytes()).to_vec(),
+            });
+            token_ids.insert(token_id.clone());
+            self.lists_by_domain.insert(key, token_ids);
+        }
+    }
+
+    fn internal_unindex_domain(&mut self, domain: &str, token_id: &TokenId) {
+        if let Some(token_ids) = self.lists_by_domain.get_mut(&domain.to_lowercase()) {
+            token_ids.remove(token_id);
+        }
+    }
+
+    /// Owners, past buyers and subscribers may rate a list
+    fn internal_can_rate(&self, token_id: &TokenId, account_id: &AccountId) -> bool {
+        let has_purchased = self.purchasers
+            .get(token_id)
+            .map(|p| p.contains(account_id))
+            .unwrap_or(false);
+        has_purchased || self.has_access(account_id.clone(), token_id.clone())
+    }
+
+    /// Hand a sold token to the buyer and record the purchase
+    fn internal_complete_sale(&mut self, seller: &AccountId, buyer: &AccountId, token_id: &TokenId) {
+        self.internal_transfer(seller, buyer, token_id);
+
+        // The seller's listing ends with the sale; the buyer relists at their own price
+        if let Some(list_metadata) = self.list_metadata_by_id.get_mut(token_id) {
+            list_metadata.price = None;
+            list_metadata.price_ft = None;
+            list_metadata.ft_contract = None;
+            list_metadata.updated_at = U64(env::block_timestamp());
+        }
+
+        // Buyers keep the right to rate after reselling
+        if let Some(purchasers) = self.purchasers.get_mut(token_id) {
+            purchasers.insert(buyer.clone());
+        } else {
+            let mut purchasers = UnorderedSet::new(StorageKey::PurchasersInner {
+                token_id_hash: env::sha256(token_id.as_bytes()).to_vec(),
+            });
+            purchasers.insert(buyer.clone());
+            self.purchasers.insert(token_id.clone(), purchasers);
+        }
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"nep171\",\"version\":\"1.0.0\",\"event\":\"nft_transfer\",\"data\":[{{\"old_owner_id\":\"{}\",\"new_owner_id\":\"{}\",\"token_ids\":[\"{}\"]}}]}}",
+            seller, buyer, token_id
+        ));
+    }
+
+    /// Pay out fungible tokens held by this contract
+    fn internal_ft_transfer(&self, ft_contract: &AccountId, receiver_id: &AccountId, amount: u128) {
+        let args = serde_json::json!({
+            "receiver_id": receiver_id,
+            "amount": U128(amount),
+        });
+        Promise::new(ft_contract.clone()).function_call(
+            "ft_transfer".to_string(),
+            args.to_string().into_bytes(),
+            NearToken::from_yoctonear(1),
+            GAS_FOR_FT_TRANSFER,
+        );
+    }
+
+    /// Transfer on behalf of `sender`, who must own the token or hold an approval
+    fn internal_nft_transfer(
+        &mut self,
+        sender: &AccountId,
+        receiver_id: &AccountId,
+        token_id: &TokenId,
+        approval_id: Option<u64>,
+    ) {
+        let owner_id = self.tokens_by_id.get(token_id).expect("Token not found").owner_id.clone();
+        
+        if &owner_id != sender {
+            let current = self.internal_active_approval(token_id, sender);
+            require!(current.is_some(), "Not token owner or approved");
+            if let Some(id) = approval_id {
+                require!(current == Some(id), "Approval ID mismatch");
+            }
+        }
+        
+        self.internal_transfer(&owner_id, receiver_id, token_id);
+        
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"nep171\",\"version\":\"1.0.0\",\"event\":\"nft_transfer\",\"data\":[{{\"old_owner_id\":\"{}\",\"new_owner_id\":\"{}\",\"token_ids\":[\"{}\"]}}]}}",
+            owner_id, receiver_id, token_id
+        ));
+    }
+
+    /// Remember the new owner's handoff key and signal the seller's client to
+    /// re-encrypt the list key to it
+    fn internal_record_handoff(&mut self, token_id: &TokenId, new_owner: &AccountId, handoff_pubkey: Option<String>) {
+        if let Some(pubkey) = handoff_pubkey {
+            // Keys are `curve:base58`, which keeps the event below valid JSON
+            require!(
+                pubkey.len() <= 128 && pubkey.chars().all(|c| c.is_ascii_alphanumeric() || c == ':'),
+                "Invalid handoff key"
+            );
+            env::log_str(&format!(
+                "EVENT_JSON:{{\"standard\":\"argus-source-list\",\"version\":\"1.0.0\",\"event\":\"list_handoff\",\"data\":[{{\"token_id\":\"{}\",\"new_owner\":\"{}\",\"handoff_pubkey\":\"{}\"}}]}}",
+                token_id, new_owner, pubkey
+            ));
+            self.handoff_pubkeys.insert(new_owner.clone(), pubkey);
+        }
+    }
+
+    /// Current approval ID for an account, ignoring approvals past their expiry
+    fn internal_active_approval(&self, token_id: &TokenId, account_id: &AccountId) -> Option<u64> {
+        let (approval_id, expires_at) = *self.approved_accounts.get(token_id)?.get(account_id)?;
+        match expires_at {
+            Some(expires_at) if env::block_timestamp() > expires_at.0 => None,
+            _ => Some(approval_id),
+        }
+    }
+
     // Internal transfer helper
     fn internal_transfer(&mut self, from: &AccountId, to: &AccountId, token_id: &TokenId) {
         // Remove from old owner using get_mut
@@ -357,9 +1055,18 @@ impl SourceListNFT {
             self.tokens_per_owner.insert(to.clone(), new_set);
         }
 
-        // Update token owner using get_mut
+        // Update token owner using get_mut; a new owner starts with no approvals
         if let Some(token) = self.tokens_by_id.get_mut(token_id) {
             token.owner_id = to.clone();
+            token.next_approval_id = 0;
+        }
+        self.internal_clear_approvals(token_id);
+    }
+
+    /// Drop every approval on a token, entries included
+    fn internal_clear_approvals(&mut self, token_id: &TokenId) {
+        if let Some(mut approvals) = self.approved_accounts.remove(token_id) {
+            approvals.clear();
         }
     }
 
@@ -395,24 +1102,199 @@ impl SourceListNFT {
     }
 
     /// NEP-171: Transfer token
+    /// Sender must own the token or hold an approval for it
+    /// `handoff_pubkey` is the receiver's key for re-sharing the list key
     #[payable]
     pub fn nft_transfer(
         &mut self,
         receiver_id: AccountId,
         token_id: TokenId,
-        _approval_id: Option<u64>,
+        approval_id: Option<u64>,
         _memo: Option<String>,
+        handoff_pubkey: Option<String>,
     ) {
         let sender = env::predecessor_account_id();
+        self.internal_nft_transfer(&sender, &receiver_id, &token_id, approval_id);
+        self.internal_record_handoff(&token_id, &receiver_id, handoff_pubkey);
+    }
+
+    /// Get the last handoff public key an account supplied
+    pub fn get_handoff_pubkey(&self, account_id: AccountId) -> Option<String> {
+        self.handoff_pubkeys.get(&account_id).cloned()
+    }
+
+    // === NEP-199 Royalties and Payouts ===
+
+    /// Split `balance` between the list creator (royalty) and the current owner
+    pub fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: u32) -> Payout {
         let token = self.tokens_by_id.get(&token_id).expect("Token not found");
-        require!(token.owner_id == sender, "Not token owner");
+        let list_metadata = self.list_metadata_by_id.get(&token_id).expect("Metadata not found");
         
-        self.internal_transfer(&sender, &receiver_id, &token_id);
+        let royalty_amount = balance.0 * list_metadata.royalty_percent as u128 / 100;
+        let mut payout = HashMap::new();
+        if royalty_amount > 0 && list_metadata.creator != token.owner_id {
+            payout.insert(list_metadata.creator.clone(), U128(royalty_amount));
+            payout.insert(token.owner_id.clone(), U128(balance.0 - royalty_amount));
+        } else {
+            payout.insert(token.owner_id.clone(), balance);
+        }
+        
+        require!(payout.len() as u32 <= max_len_payout, "Payout exceeds max_len_payout");
+        
+        Payout { payout }
+    }
+
+    /// Transfer a token and return the payout the marketplace should honor
+    #[payable]
+    pub fn nft_transfer_payout(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        _memo: Option<String>,
+        balance: U128,
+        max_len_payout: u32,
+    ) -> Payout {
+        require!(
+            env::attached_deposit() >= NearToken::from_yoctonear(1),
+            "Requires 1 yoctoNEAR"
+        );
+        
+        // Computed against the owner being paid, before ownership moves
+        let payout = self.nft_payout(token_id.clone(), balance, max_len_payout);
+        
+        let sender = env::predecessor_account_id();
+        self.internal_nft_transfer(&sender, &receiver_id, &token_id, approval_id);
+        
+        payout
+    }
+
+    // === NEP-178 Approval Management ===
+
+    /// Approve an account to transfer a token on the owner's behalf
+    /// If `msg` is given, `nft_on_approve` is called on the approved account
+    #[payable]
+    pub fn nft_approve(
+        &mut self,
+        token_id: TokenId,
+        account_id: AccountId,
+        msg: Option<String>,
+    ) -> Option<Promise> {
+        self.internal_approve(token_id, account_id, None, msg)
+    }
+
+    /// Approve an account until `expires_at` (block timestamp, ns)
+    /// After expiry the approval is treated as absent, so stale listings can't transfer
+    #[payable]
+    pub fn nft_approve_until(
+        &mut self,
+        token_id: TokenId,
+        account_id: AccountId,
+        expires_at: U64,
+        msg: Option<String>,
+    ) -> Option<Promise> {
+        require!(expires_at.0 > env::block_timestamp(), "Expiry must be in the future");
+        self.internal_approve(token_id, account_id, Some(expires_at), msg)
+    }
+
+    fn internal_approve(
+        &mut self,
+        token_id: TokenId,
+        account_id: AccountId,
+        expires_at: Option<U64>,
+        msg: Option<String>,
+    ) -> Option<Promise> {
+        require!(
+            env::attached_deposit() >= NearToken::from_yoctonear(1),
+            "Requires 1 yoctoNEAR"
+        );
+        
+        let owner_id = env::predecessor_account_id();
+        let token = self.tokens_by_id.get_mut(&token_id).expect("Token not found");
+        require!(token.owner_id == owner_id, "Not token owner");
+        
+        let approval_id = token.next_approval_id;
+        token.next_approval_id += 1;
+        
+        if let Some(approvals) = self.approved_accounts.get_mut(&token_id) {
+            approvals.insert(account_id.clone(), (approval_id, expires_at));
+        } else {
+            // Approval IDs restart on transfer, so each map gets a fresh prefix
+            // and never sees entries left behind by an earlier one
+            let mut approvals = UnorderedMap::new(StorageKey::ApprovedAccounts {
+                token_id_hash: env::sha256(format!("{}:{}", token_id, self.next_approvals_prefix).as_bytes()).to_vec(),
+            });
+            self.next_approvals_prefix += 1;
+            approvals.insert(account_id.clone(), (approval_id, expires_at));
+            self.approved_accounts.insert(token_id.clone(), approvals);
+        }
         
         env::log_str(&format!(
-            "EVENT_JSON:{{\"standard\":\"nep171\",\"version\":\"1.0.0\",\"event\":\"nft_transfer\",\"data\":[{{\"old_owner_id\":\"{}\",\"new_owner_id\":\"{}\",\"token_ids\":[\"{}\"]}}]}}",
-            sender, receiver_id, token_id
+            "EVENT_JSON:{{\"standard\":\"nep178\",\"version\":\"1.0.0\",\"event\":\"nft_approve\",\"data\":[{{\"token_id\":\"{}\",\"account_id\":\"{}\",\"approval_id\":{}}}]}}",
+            token_id, account_id, approval_id
         ));
+        
+        msg.map(|msg| {
+            let args = serde_json::json!({
+                "token_id": token_id,
+                "owner_id": owner_id,
+                "approval_id": approval_id,
+                "msg": msg,
+            });
+            Promise::new(account_id).function_call(
+                "nft_on_approve".to_string(),
+                args.to_string().into_bytes(),
+                NearToken::from_yoctonear(0),
+                GAS_FOR_NFT_ON_APPROVE,
+            )
+        })
+    }
+
+    /// Revoke a single account's approval
+    #[payable]
+    pub fn nft_revoke(&mut self, token_id: TokenId, account_id: AccountId) {
+        require!(
+            env::attached_deposit() >= NearToken::from_yoctonear(1),
+            "Requires 1 yoctoNEAR"
+        );
+        
+        let token = self.tokens_by_id.get(&token_id).expect("Token not found");
+        require!(token.owner_id == env::predecessor_account_id(), "Not token owner");
+        
+        if let Some(approvals) = self.approved_accounts.get_mut(&token_id) {
+            approvals.remove(&account_id);
+        }
+    }
+
+    /// Revoke all approvals for a token
+    #[payable]
+    pub fn nft_revoke_all(&mut self, token_id: TokenId) {
+        require!(
+            env::attached_deposit() >= NearToken::from_yoctonear(1),
+            "Requires 1 yoctoNEAR"
+        );
+        
+        let token = self.tokens_by_id.get(&token_id).expect("Token not found");
+        require!(token.owner_id == env::predecessor_account_id(), "Not token owner");
+        
+        self.internal_clear_approvals(&token_id);
+    }
+
+    /// Check if an account is approved for a token
+    /// If `approval_id` is given, it must match the current approval
+    /// Expired approvals count as absent
+    pub fn nft_is_approved(
+        &self,
+        token_id: TokenId,
+        approved_account_id: AccountId,
+        approval_id: Option<u64>,
+    ) -> bool {
+        let current = match self.internal_active_approval(&token_id, &approved_account_id) {
+            Some(id) => id,
+            None => return false,
+        };
+        
+        approval_id.map(|id| id == current).unwrap_or(true)
     }
 
     /// Get tokens for owner (paginated)
@@ -439,9 +1321,878 @@ impl SourceListNFT {
     }
 }
 
+/// NEP-199 payout: account -> amount owed from a sale
+#[near(serializers = [json])]
+pub struct Payout {
+    pub payout: HashMap<AccountId, U128>,
+}
+
 #[near(serializers = [json])]
 pub struct JsonToken {
     pub token_id: TokenId,
     pub owner_id: AccountId,
     pub metadata: TokenMetadata,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::mock::MockAction;
+    use near_sdk::test_utils::{get_created_receipts, get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    fn get_context(predecessor: AccountId) -> VMContextBuilder {
+        let mut builder = VMContextBuilder::new();
+        builder.current_account_id("source-lists.near".parse().unwrap());
+        builder.predecessor_account_id(predecessor);
+        builder.attached_deposit(NearToken::from_yoctonear(1));
+        builder.block_timestamp(1_000_000_000);
+        builder
+    }
+
+    fn mint_list(contract: &mut SourceListNFT, domain: &str, price: Option<U128>, royalty_percent: Option<u8>) -> TokenId {
+        contract.mint(
+            "OSINT Core".to_string(),
+            "Curated OSINT sources".to_string(),
+            "bafy-list".to_string(),
+            12,
+            domain.to_string(),
+            price,
+            royalty_percent,
+        )
+    }
+
+    /// Contract with one list minted by alice
+    fn setup() -> (SourceListNFT, TokenId) {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+
+        let mut contract = SourceListNFT::new("owner.near".parse().unwrap());
+        let token_id = mint_list(&mut contract, "cybersecurity", None, None);
+
+        (contract, token_id)
+    }
+
+    #[test]
+    fn test_approved_account_can_transfer() {
+        let (mut contract, token_id) = setup();
+        let market: AccountId = "market.near".parse().unwrap();
+
+        contract.nft_approve(token_id.clone(), market.clone(), None);
+        assert!(contract.nft_is_approved(token_id.clone(), market.clone(), Some(0)));
+        assert!(!contract.nft_is_approved(token_id.clone(), market.clone(), Some(1)));
+
+        testing_env!(get_context(market.clone()).build());
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id.clone(), Some(0), None, None);
+
+        assert!(contract.has_access("bob.near".parse().unwrap(), token_id.clone()));
+        assert!(!contract.nft_is_approved(token_id, market, None));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not token owner or approved")]
+    fn test_revoked_account_cannot_transfer() {
+        let (mut contract, token_id) = setup();
+        let market: AccountId = "market.near".parse().unwrap();
+
+        contract.nft_approve(token_id.clone(), market.clone(), None);
+        contract.nft_revoke(token_id.clone(), market.clone());
+
+        testing_env!(get_context(market).build());
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id, None, None, None);
+    }
+
+    #[test]
+    fn test_payout_splits_royalty() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        let mut contract = SourceListNFT::new("owner.near".parse().unwrap());
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+
+        for (royalty, creator_share) in [(10u8, 100u128), (25, 250), (50, 500)] {
+            testing_env!(get_context(alice.clone()).build());
+            let token_id = mint_list(&mut contract, "finance", None, Some(royalty));
+            contract.nft_transfer(bob.clone(), token_id.clone(), None, None, None);
+
+            let payout = contract.nft_payout(token_id, U128(1_000), 2).payout;
+            assert_eq!(payout.len(), 2);
+            assert_eq!(payout[&alice], U128(creator_share));
+            assert_eq!(payout[&bob], U128(1_000 - creator_share));
+        }
+    }
+
+    #[test]
+    fn test_payout_without_royalty_goes_to_owner() {
+        let (mut contract, _) = setup();
+        let token_id = mint_list(&mut contract, "finance", None, Some(0));
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id.clone(), None, None, None);
+
+        let payout = contract.nft_payout(token_id, U128(1_000), 1).payout;
+        assert_eq!(payout.len(), 1);
+        assert_eq!(payout[&"bob.near".parse::<AccountId>().unwrap()], U128(1_000));
+    }
+
+    #[test]
+    #[should_panic(expected = "Payout exceeds max_len_payout")]
+    fn test_payout_rejects_small_max_len() {
+        let (mut contract, token_id) = setup();
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id.clone(), None, None, None);
+        contract.nft_payout(token_id, U128(1_000), 1);
+    }
+
+    #[test]
+    fn test_transfer_payout_pays_previous_owner() {
+        let (mut contract, token_id) = setup();
+        let market: AccountId = "market.near".parse().unwrap();
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id.clone(), None, None, None);
+
+        testing_env!(get_context("bob.near".parse().unwrap()).build());
+        contract.nft_approve(token_id.clone(), market.clone(), None);
+
+        testing_env!(get_context(market).build());
+        let payout = contract
+            .nft_transfer_payout("carol.near".parse().unwrap(), token_id.clone(), Some(0), None, U128(1_000), 2)
+            .payout;
+
+        assert_eq!(payout[&"bob.near".parse::<AccountId>().unwrap()], U128(900));
+        assert_eq!(payout[&"alice.near".parse::<AccountId>().unwrap()], U128(100));
+        assert!(contract.has_access("carol.near".parse().unwrap(), token_id));
+    }
+
+    /// Transfer amounts created for `receiver`
+    fn transfers_to(receiver: &str) -> Vec<NearToken> {
+        get_created_receipts()
+            .into_iter()
+            .filter(|r| r.receiver_id.as_str() == receiver)
+            .flat_map(|r| r.actions)
+            .filter_map(|a| match a {
+                MockAction::Transfer { deposit, .. } => Some(deposit),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_purchase_refunds_overpayment() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        let mut contract = SourceListNFT::new("owner.near".parse().unwrap());
+        let price = NearToken::from_near(1);
+        let token_id = mint_list(&mut contract, "finance", Some(U128(price.as_yoctonear())), Some(0));
+
+        let mut context = get_context("bob.near".parse().unwrap());
+        testing_env!(context.attached_deposit(NearToken::from_near(3)).build());
+        contract.purchase(token_id.clone(), None);
+
+        assert!(contract.has_access("bob.near".parse().unwrap(), token_id));
+        assert_eq!(transfers_to("bob.near"), vec![NearToken::from_near(2)]);
+        assert_eq!(transfers_to("alice.near"), vec![price]);
+    }
+
+    #[test]
+    fn test_purchase_exact_price_has_no_refund() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        let mut contract = SourceListNFT::new("owner.near".parse().unwrap());
+        let token_id = mint_list(&mut contract, "finance", Some(U128(NearToken::from_near(1).as_yoctonear())), None);
+
+        let mut context = get_context("bob.near".parse().unwrap());
+        testing_env!(context.attached_deposit(NearToken::from_near(1)).build());
+        contract.purchase(token_id, None);
+
+        assert!(transfers_to("bob.near").is_empty());
+    }
+
+    #[test]
+    fn test_purchase_at_matching_price() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        let mut contract = SourceListNFT::new("owner.near".parse().unwrap());
+        let token_id = mint_list(&mut contract, "finance", Some(U128(1_000)), Some(0));
+
+        let mut context = get_context("bob.near".parse().unwrap());
+        testing_env!(context.attached_deposit(NearToken::from_yoctonear(1_500)).build());
+        contract.purchase_at(token_id.clone(), U128(1_000), None);
+
+        assert!(contract.has_access("bob.near".parse().unwrap(), token_id));
+        assert_eq!(transfers_to("alice.near"), vec![NearToken::from_yoctonear(1_000)]);
+        assert_eq!(transfers_to("bob.near"), vec![NearToken::from_yoctonear(500)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Price changed")]
+    fn test_purchase_at_rejects_changed_price() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        let mut contract = SourceListNFT::new("owner.near".parse().unwrap());
+        let token_id = mint_list(&mut contract, "finance", Some(U128(1_000)), None);
+
+        // Seller bumps the price after bob quoted it
+        contract.set_price(token_id.clone(), Some(U128(2_000)));
+
+        let mut context = get_context("bob.near".parse().unwrap());
+        testing_env!(context.attached_deposit(NearToken::from_yoctonear(2_000)).build());
+        contract.purchase_at(token_id, U128(1_000), None);
+    }
+
+    #[test]
+    fn test_purchase_emits_handoff_event() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        let mut contract = SourceListNFT::new("owner.near".parse().unwrap());
+        let token_id = mint_list(&mut contract, "finance", Some(U128(1_000)), None);
+
+        let mut context = get_context("bob.near".parse().unwrap());
+        testing_env!(context.attached_deposit(NearToken::from_yoctonear(1_000)).build());
+        contract.purchase(token_id.clone(), Some("ed25519:bobkey".to_string()));
+
+        let handoff = get_logs()
+            .into_iter()
+            .find(|log| log.contains("list_handoff"))
+            .expect("handoff event not emitted");
+        assert_eq!(
+            handoff,
+            format!(
+                "EVENT_JSON:{{\"standard\":\"argus-source-list\",\"version\":\"1.0.0\",\"event\":\"list_handoff\",\"data\":[{{\"token_id\":\"{}\",\"new_owner\":\"bob.near\",\"handoff_pubkey\":\"ed25519:bobkey\"}}]}}",
+                token_id
+            )
+        );
+        assert_eq!(contract.get_handoff_pubkey("bob.near".parse().unwrap()), Some("ed25519:bobkey".to_string()));
+    }
+
+    #[test]
+    fn test_transfer_handoff_is_optional() {
+        let (mut contract, token_id) = setup();
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id.clone(), None, None, None);
+        assert!(!get_logs().iter().any(|log| log.contains("list_handoff")));
+        assert_eq!(contract.get_handoff_pubkey("bob.near".parse().unwrap()), None);
+
+        testing_env!(get_context("bob.near".parse().unwrap()).build());
+        contract.nft_transfer("carol.near".parse().unwrap(), token_id, None, None, Some("ed25519:carolkey".to_string()));
+        assert!(get_logs().iter().any(|log| log.contains("list_handoff") && log.contains("ed25519:carolkey")));
+        assert_eq!(contract.get_handoff_pubkey("carol.near".parse().unwrap()), Some("ed25519:carolkey".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid handoff key")]
+    fn test_handoff_key_must_be_plain() {
+        let (mut contract, token_id) = setup();
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id, None, None, Some("ed25519:\"},{".to_string()));
+    }
+
+    #[test]
+    fn test_favorite_lists() {
+        let (mut contract, token_id) = setup();
+        let other = mint_list(&mut contract, "finance", None, None);
+
+        testing_env!(get_context("bob.near".parse().unwrap()).build());
+        assert!(contract.get_favorites("bob.near".parse().unwrap()).is_empty());
+
+        contract.favorite_list(token_id.clone());
+        contract.favorite_list(token_id.clone());
+        contract.favorite_list(other.clone());
+        assert_eq!(contract.get_favorites("bob.near".parse().unwrap()), vec![token_id.clone(), other.clone()]);
+        assert!(contract.get_favorites("alice.near".parse().unwrap()).is_empty());
+
+        contract.unfavorite_list(token_id);
+        assert_eq!(contract.get_favorites("bob.near".parse().unwrap()), vec![other]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot favorite more than 100 lists")]
+    fn test_favorites_are_capped() {
+        let (mut contract, first) = setup();
+        let mut token_ids = vec![first];
+        for _ in 1..=MAX_FAVORITES_PER_ACCOUNT {
+            token_ids.push(mint_list(&mut contract, "finance", None, None));
+        }
+
+        testing_env!(get_context("bob.near".parse().unwrap()).build());
+        let extra = token_ids.pop().unwrap();
+        for token_id in &token_ids {
+            contract.favorite_list(token_id.clone());
+        }
+        // Re-favoriting at the cap is still a no-op
+        contract.favorite_list(token_ids[0].clone());
+        assert_eq!(contract.get_favorites("bob.near".parse().unwrap()).len(), 100);
+
+        contract.favorite_list(extra);
+    }
+
+    #[test]
+    #[should_panic(expected = "Token not found")]
+    fn test_favorite_unknown_list() {
+        let (mut contract, _) = setup();
+        contract.favorite_list("missing".to_string());
+    }
+
+    /// List minted by alice and bought by bob, with bob as predecessor
+    fn setup_purchased() -> (SourceListNFT, TokenId) {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        let mut contract = SourceListNFT::new("owner.near".parse().unwrap());
+        let token_id = mint_list(&mut contract, "finance", Some(U128(1_000)), None);
+
+        let mut context = get_context("bob.near".parse().unwrap());
+        testing_env!(context.attached_deposit(NearToken::from_yoctonear(1_000)).build());
+        contract.purchase(token_id.clone(), None);
+
+        (contract, token_id)
+    }
+
+    #[test]
+    #[should_panic(expected = "Already rated")]
+    fn test_rate_list_rejects_duplicate() {
+        let (mut contract, token_id) = setup_purchased();
+        contract.rate_list(token_id.clone(), 5);
+        contract.rate_list(token_id, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owners, buyers or subscribers can rate")]
+    fn test_rate_list_requires_ownership_or_purchase() {
+        let (mut contract, token_id) = setup_purchased();
+        testing_env!(get_context("mallory.near".parse().unwrap()).build());
+        contract.rate_list(token_id, 1);
+    }
+
+    #[test]
+    fn test_update_rating_replaces_contribution() {
+        let (mut contract, token_id) = setup_purchased();
+        contract.rate_list(token_id.clone(), 5);
+
+        // Bob resells to carol; both bob (buyer) and carol (owner) may rate
+        contract.nft_transfer("carol.near".parse().unwrap(), token_id.clone(), None, None, None);
+        testing_env!(get_context("carol.near".parse().unwrap()).build());
+        contract.rate_list(token_id.clone(), 2);
+
+        let metadata = contract.get_list_metadata(token_id.clone()).unwrap();
+        assert_eq!((metadata.avg_rating, metadata.rating_count), (350, 2));
+
+        contract.update_rating(token_id.clone(), 3);
+        let metadata = contract.get_list_metadata(token_id).unwrap();
+        assert_eq!((metadata.avg_rating, metadata.rating_count), (400, 2));
+    }
+
+    #[test]
+    fn test_subscribe_grants_access_and_splits_revenue() {
+        let (mut contract, token_id) = setup();
+        let carol: AccountId = "carol.near".parse().unwrap();
+
+        // Creator alice sells to bob, who offers subscriptions
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id.clone(), None, None, None);
+        testing_env!(get_context("bob.near".parse().unwrap()).build());
+        contract.set_subscription_price(token_id.clone(), Some(U128(1_000)));
+        assert!(!contract.has_access(carol.clone(), token_id.clone()));
+
+        let mut context = get_context(carol.clone());
+        testing_env!(context.attached_deposit(NearToken::from_yoctonear(1_000)).build());
+        contract.subscribe(token_id.clone());
+
+        assert!(contract.has_access(carol.clone(), token_id.clone()));
+        assert!(contract.is_subscriber(token_id.clone(), carol));
+        assert_eq!(contract.get_list_metadata(token_id).unwrap().total_subscribers, 1);
+        assert_eq!(transfers_to("bob.near"), vec![NearToken::from_yoctonear(900)]);
+        assert_eq!(transfers_to("alice.near"), vec![NearToken::from_yoctonear(100)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Already subscribed")]
+    fn test_subscribe_twice_rejected() {
+        let (mut contract, token_id) = setup();
+        contract.set_subscription_price(token_id.clone(), Some(U128(1_000)));
+
+        let mut context = get_context("carol.near".parse().unwrap());
+        testing_env!(context.attached_deposit(NearToken::from_yoctonear(1_000)).build());
+        contract.subscribe(token_id.clone());
+        contract.subscribe(token_id);
+    }
+
+    #[test]
+    fn test_burn_cleans_up_storage() {
+        let (mut contract, token_id) = setup();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let carol: AccountId = "carol.near".parse().unwrap();
+        let kept = mint_list(&mut contract, "finance", None, None);
+
+        contract.nft_approve(token_id.clone(), "market.near".parse().unwrap(), None);
+        contract.set_subscription_price(token_id.clone(), Some(U128(1_000)));
+        let mut context = get_context(carol.clone());
+        testing_env!(context.attached_deposit(NearToken::from_yoctonear(1_000)).build());
+        contract.subscribe(token_id.clone());
+        contract.rate_list(token_id.clone(), 4);
+        assert_eq!(contract.nft_total_supply(), U128(2));
+
+        testing_env!(get_context(alice.clone()).build());
+        contract.nft_burn(token_id.clone(), None);
+
+        assert_eq!(contract.nft_total_supply(), U128(1));
+        assert!(contract.nft_token(token_id.clone()).is_none());
+        assert!(contract.get_list_metadata(token_id.clone()).is_none());
+        assert_eq!(contract.get_lists_for_owner(alice), vec![kept]);
+        assert!(!contract.nft_is_approved(token_id.clone(), "market.near".parse().unwrap(), None));
+        assert!(!contract.has_access(carol.clone(), token_id.clone()));
+        assert!(!contract.rating_scores.contains_key(&(token_id.clone(), carol)));
+        assert!(!contract.rating_totals.contains_key(&token_id));
+    }
+
+    /// Storage used once pending approval writes for `token_id` are flushed
+    fn flushed_approval_storage(contract: &mut SourceListNFT, token_id: &TokenId) -> u64 {
+        if let Some(approvals) = contract.approved_accounts.get_mut(token_id) {
+            approvals.flush();
+        }
+        contract.approved_accounts.flush();
+        env::storage_usage()
+    }
+
+    #[test]
+    fn test_revoke_all_frees_approval_storage() {
+        let (mut contract, token_id) = setup();
+        let before = flushed_approval_storage(&mut contract, &token_id);
+
+        contract.nft_approve(token_id.clone(), "market.near".parse().unwrap(), None);
+        contract.nft_approve(token_id.clone(), "carol.near".parse().unwrap(), None);
+        assert!(flushed_approval_storage(&mut contract, &token_id) > before);
+
+        contract.nft_revoke_all(token_id.clone());
+        assert_eq!(flushed_approval_storage(&mut contract, &token_id), before);
+    }
+
+    #[test]
+    fn test_burn_frees_approval_storage() {
+        let (mut contract, token_id) = setup();
+        // Keep the domain index non-empty so only approvals change storage below
+        mint_list(&mut contract, "cybersecurity", None, None);
+        let before = flushed_approval_storage(&mut contract, &token_id);
+
+        contract.nft_approve(token_id.clone(), "market.near".parse().unwrap(), None);
+        assert!(flushed_approval_storage(&mut contract, &token_id) > before);
+
+        // Other collections removed by the burn are still cached, so only approvals count
+        contract.nft_burn(token_id.clone(), None);
+        assert_eq!(flushed_approval_storage(&mut contract, &token_id), before);
+    }
+
+    #[test]
+    #[should_panic(expected = "List is for sale; pass force to burn")]
+    fn test_burn_listed_requires_force() {
+        let (mut contract, token_id) = setup();
+        contract.set_price(token_id.clone(), Some(U128(1_000)));
+        contract.nft_burn(token_id, None);
+    }
+
+    #[test]
+    fn test_force_burn_listed() {
+        let (mut contract, token_id) = setup();
+        contract.set_price(token_id.clone(), Some(U128(1_000)));
+        contract.nft_burn(token_id.clone(), Some(true));
+        assert!(contract.get_lists_for_sale(None, None).is_empty());
+    }
+
+    #[test]
+    fn test_lists_by_domain() {
+        let (mut contract, cyber) = setup();
+        let finance = mint_list(&mut contract, "Finance", None, None);
+        let cyber_two = mint_list(&mut contract, "CyberSecurity", None, None);
+
+        let ids = |lists: Vec<(TokenId, SourceListMetadata)>| {
+            let mut ids: Vec<TokenId> = lists.into_iter().map(|(id, _)| id).collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(ids(contract.get_lists_by_domain("cybersecurity".to_string(), None, None)), vec![cyber.clone(), cyber_two]);
+        assert_eq!(ids(contract.get_lists_by_domain("FINANCE".to_string(), None, None)), vec![finance]);
+        assert_eq!(contract.get_lists_by_domain("cybersecurity".to_string(), Some(1), Some(5)).len(), 1);
+        assert!(contract.get_lists_by_domain("health".to_string(), None, None).is_empty());
+
+        contract.nft_burn(cyber, None);
+        assert_eq!(contract.get_lists_by_domain("cybersecurity".to_string(), None, None).len(), 1);
+    }
+
+    #[test]
+    fn test_top_rated_lists() {
+        let (mut contract, unrated) = setup();
+        let good = mint_list(&mut contract, "finance", None, None);
+        let best = mint_list(&mut contract, "finance", None, None);
+        let poor = mint_list(&mut contract, "finance", None, None);
+        for (token_id, rating) in [(&good, 4), (&best, 5), (&poor, 2)] {
+            contract.rate_list(token_id.clone(), rating);
+        }
+
+        let top: Vec<TokenId> = contract.get_top_rated_lists(10).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(top, vec![best.clone(), good.clone(), poor]);
+        assert!(!top.contains(&unrated));
+
+        let top: Vec<TokenId> = contract.get_top_rated_lists(2).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(top, vec![best, good]);
+    }
+
+    #[test]
+    fn test_most_subscribed_lists() {
+        let (mut contract, quiet) = setup();
+        let popular = mint_list(&mut contract, "finance", None, None);
+        for token_id in [&quiet, &popular] {
+            contract.set_subscription_price(token_id.clone(), Some(U128(1)));
+        }
+
+        for (subscriber, token_id) in [("bob.near", &popular), ("carol.near", &popular), ("dave.near", &quiet)] {
+            let mut context = get_context(subscriber.parse().unwrap());
+            testing_env!(context.attached_deposit(NearToken::from_yoctonear(1)).build());
+            contract.subscribe(token_id.clone());
+        }
+
+        let ranked: Vec<(TokenId, u32)> = contract
+            .get_most_subscribed_lists(10)
+            .into_iter()
+            .map(|(id, m)| (id, m.total_subscribers))
+            .collect();
+        assert_eq!(ranked, vec![(popular, 2), (quiet, 1)]);
+    }
+
+    #[test]
+    fn test_creator_updates_domain_and_royalty() {
+        let (mut contract, token_id) = setup();
+        contract.update_list_metadata(token_id.clone(), Some("Finance".to_string()), Some(20));
+
+        let metadata = contract.get_list_metadata(token_id.clone()).unwrap();
+        assert_eq!((metadata.domain.as_str(), metadata.royalty_percent), ("Finance", 20));
+        assert!(contract.get_lists_by_domain("cybersecurity".to_string(), None, None).is_empty());
+        assert_eq!(contract.get_lists_by_domain("finance".to_string(), None, None)[0].0, token_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only creator can update list metadata")]
+    fn test_non_creator_owner_cannot_change_royalty() {
+        let (mut contract, token_id) = setup();
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id.clone(), None, None, None);
+
+        testing_env!(get_context("bob.near".parse().unwrap()).build());
+        contract.update_list_metadata(token_id, None, Some(0));
+    }
+
+    /// `ft_transfer` payouts created for `receiver`, as (ft_contract, amount)
+    fn ft_payouts_to(receiver: &str) -> Vec<(String, U128)> {
+        get_created_receipts()
+            .into_iter()
+            .flat_map(|r| {
+                let ft_contract = r.receiver_id.to_string();
+                r.actions.into_iter().filter_map(move |a| match a {
+                    MockAction::FunctionCallWeight { method_name, args, .. } if method_name == b"ft_transfer" => {
+                        Some((ft_contract.clone(), args))
+                    }
+                    _ => None,
+                })
+            })
+            .filter_map(|(ft_contract, args)| {
+                let args: serde_json::Value = serde_json::from_slice(&args).unwrap();
+                (args["receiver_id"] == receiver)
+                    .then(|| (ft_contract, U128(args["amount"].as_str().unwrap().parse().unwrap())))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ft_purchase_pays_seller_and_creator() {
+        let (mut contract, token_id) = setup();
+        let usdc: AccountId = "usdc.near".parse().unwrap();
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id.clone(), None, None, None);
+
+        testing_env!(get_context("bob.near".parse().unwrap()).build());
+        contract.set_ft_price(token_id.clone(), Some(usdc.clone()), Some(U128(5_000_000)));
+
+        // The FT contract calls ft_on_transfer after carol sends 6 USDC
+        testing_env!(get_context(usdc).build());
+        let unused = match contract.ft_on_transfer("carol.near".parse().unwrap(), U128(6_000_000), token_id.clone()) {
+            PromiseOrValue::Value(unused) => unused,
+            PromiseOrValue::Promise(_) => panic!("expected a value"),
+        };
+
+        assert_eq!(unused, U128(1_000_000));
+        assert!(contract.has_access("carol.near".parse().unwrap(), token_id));
+        assert_eq!(ft_payouts_to("bob.near"), vec![("usdc.near".to_string(), U128(4_500_000))]);
+        assert_eq!(ft_payouts_to("alice.near"), vec![("usdc.near".to_string(), U128(500_000))]);
+    }
+
+    /// Assert the list is no longer for sale in NEAR or FT
+    fn assert_unlisted(contract: &SourceListNFT, token_id: &TokenId) {
+        let list_metadata = contract.get_list_metadata(token_id.clone()).unwrap();
+        assert_eq!(list_metadata.price, None);
+        assert_eq!(list_metadata.price_ft, None);
+        assert_eq!(list_metadata.ft_contract, None);
+        assert!(contract.get_lists_for_sale(None, None).is_empty());
+    }
+
+    #[test]
+    fn test_purchase_clears_listing() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        let mut contract = SourceListNFT::new("owner.near".parse().unwrap());
+        let token_id = mint_list(&mut contract, "finance", Some(U128(1_000)), None);
+        contract.set_ft_price(token_id.clone(), Some("usdc.near".parse().unwrap()), Some(U128(5_000_000)));
+
+        let mut context = get_context("carol.near".parse().unwrap());
+        testing_env!(context.attached_deposit(NearToken::from_yoctonear(1_000)).build());
+        contract.purchase(token_id.clone(), None);
+
+        assert_unlisted(&contract, &token_id);
+    }
+
+    #[test]
+    fn test_ft_purchase_clears_listing() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        let mut contract = SourceListNFT::new("owner.near".parse().unwrap());
+        let token_id = mint_list(&mut contract, "finance", Some(U128(1_000)), None);
+        contract.set_ft_price(token_id.clone(), Some("usdc.near".parse().unwrap()), Some(U128(5_000_000)));
+
+        testing_env!(get_context("usdc.near".parse().unwrap()).build());
+        contract.ft_on_transfer("carol.near".parse().unwrap(), U128(5_000_000), token_id.clone());
+
+        assert_unlisted(&contract, &token_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unsupported fungible token")]
+    fn test_ft_purchase_rejects_other_token() {
+        let (mut contract, token_id) = setup();
+        contract.set_ft_price(token_id.clone(), Some("usdc.near".parse().unwrap()), Some(U128(5_000_000)));
+
+        testing_env!(get_context("fake-usdc.near".parse().unwrap()).build());
+        contract.ft_on_transfer("carol.near".parse().unwrap(), U128(5_000_000), token_id);
+    }
+
+    #[test]
+    fn test_transfer_clears_stale_approval() {
+        let (mut contract, token_id) = setup();
+        let stale: AccountId = "stale.near".parse().unwrap();
+        let carol: AccountId = "carol.near".parse().unwrap();
+
+        contract.nft_approve(token_id.clone(), stale.clone(), None);
+        contract.nft_transfer(carol.clone(), token_id.clone(), None, None, None);
+        assert!(!contract.nft_is_approved(token_id.clone(), stale.clone(), None));
+
+        // Carol's first approval reuses ID 0 without reviving the old one
+        testing_env!(get_context(carol).build());
+        contract.nft_approve(token_id.clone(), "market.near".parse().unwrap(), None);
+        assert!(contract.nft_is_approved(token_id.clone(), "market.near".parse().unwrap(), Some(0)));
+        assert!(!contract.nft_is_approved(token_id, stale, Some(0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not token owner or approved")]
+    fn test_purchase_clears_approvals() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        let mut contract = SourceListNFT::new("owner.near".parse().unwrap());
+        let token_id = mint_list(&mut contract, "finance", Some(U128(1_000)), None);
+        contract.nft_approve(token_id.clone(), "stale.near".parse().unwrap(), None);
+
+        let mut context = get_context("carol.near".parse().unwrap());
+        testing_env!(context.attached_deposit(NearToken::from_yoctonear(1_000)).build());
+        contract.purchase(token_id.clone(), None);
+
+        testing_env!(get_context("stale.near".parse().unwrap()).build());
+        contract.nft_transfer("stale.near".parse().unwrap(), token_id, None, None, None);
+    }
+
+    #[test]
+    fn test_add_and_remove_admin() {
+        let (mut contract, _) = setup();
+        let owner: AccountId = "owner.near".parse().unwrap();
+        let ops: AccountId = "ops.near".parse().unwrap();
+        assert!(contract.is_admin(owner.clone()));
+        assert!(!contract.is_admin(ops.clone()));
+
+        testing_env!(get_context(owner).build());
+        contract.add_admin(ops.clone());
+        assert!(contract.is_admin(ops.clone()));
+        assert_eq!(contract.get_admins(), vec![ops.clone()]);
+
+        contract.remove_admin(ops.clone());
+        assert!(!contract.is_admin(ops));
+        assert!(contract.get_admins().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only contract owner")]
+    fn test_admin_cannot_add_admins() {
+        let (mut contract, _) = setup();
+        testing_env!(get_context("owner.near".parse().unwrap()).build());
+        contract.add_admin("ops.near".parse().unwrap());
+
+        testing_env!(get_context("ops.near".parse().unwrap()).build());
+        contract.add_admin("mallory.near".parse().unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot remove contract owner")]
+    fn test_owner_cannot_be_removed() {
+        let (mut contract, _) = setup();
+        testing_env!(get_context("owner.near".parse().unwrap()).build());
+        contract.remove_admin("owner.near".parse().unwrap());
+    }
+
+    #[test]
+    fn test_approval_expires() {
+        let (mut contract, token_id) = setup();
+        let market: AccountId = "market.near".parse().unwrap();
+
+        contract.nft_approve_until(token_id.clone(), market.clone(), U64(2_000_000_000), None);
+        assert!(contract.nft_is_approved(token_id.clone(), market.clone(), Some(0)));
+
+        let mut context = get_context(market.clone());
+        testing_env!(context.block_timestamp(2_000_000_000).build());
+        assert!(contract.nft_is_approved(token_id.clone(), market.clone(), None));
+
+        testing_env!(context.block_timestamp(2_000_000_001).build());
+        assert!(!contract.nft_is_approved(token_id.clone(), market.clone(), None));
+        assert!(!contract.nft_is_approved(token_id, market, Some(0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not token owner or approved")]
+    fn test_expired_approval_cannot_transfer() {
+        let (mut contract, token_id) = setup();
+        let market: AccountId = "market.near".parse().unwrap();
+        contract.nft_approve_until(token_id.clone(), market.clone(), U64(2_000_000_000), None);
+
+        let mut context = get_context(market);
+        testing_env!(context.block_timestamp(3_000_000_000).build());
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id, Some(0), None, None);
+    }
+
+    #[test]
+    fn test_unexpired_approval_can_transfer() {
+        let (mut contract, token_id) = setup();
+        let market: AccountId = "market.near".parse().unwrap();
+        contract.nft_approve_until(token_id.clone(), market.clone(), U64(2_000_000_000), None);
+
+        let mut context = get_context(market);
+        testing_env!(context.block_timestamp(1_500_000_000).build());
+        contract.nft_transfer("bob.near".parse().unwrap(), token_id.clone(), Some(0), None, None);
+        assert!(contract.has_access("bob.near".parse().unwrap(), token_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "Expiry must be in the future")]
+    fn test_approve_until_past_expiry() {
+        let (mut contract, token_id) = setup();
+        contract.nft_approve_until(token_id, "market.near".parse().unwrap(), U64(1_000_000_000), None);
+    }
+
+    #[test]
+    fn test_has_access_batch() {
+        let (mut contract, alices) = setup();
+        testing_env!(get_context("bob.near".parse().unwrap()).build());
+        let bobs = mint_list(&mut contract, "finance", None, None);
+
+        let access = contract.has_access_batch(
+            "alice.near".parse().unwrap(),
+            vec![alices.clone(), bobs, "999".to_string(), alices],
+        );
+        assert_eq!(access, vec![true, false, false, true]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot check more than 100 tokens")]
+    fn test_has_access_batch_limit() {
+        let (contract, token_id) = setup();
+        contract.has_access_batch("alice.near".parse().unwrap(), vec![token_id; 101]);
+    }
+
+    #[test]
+    fn test_admin_delists_inactive_lists() {
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        let mut contract = SourceListNFT::new("owner.near".parse().unwrap());
+        let listed = mint_list(&mut contract, "finance", Some(U128(1_000)), None);
+        let unlisted = mint_list(&mut contract, "cybersecurity", None, None);
+        assert_eq!(contract.get_lists_for_sale(None, None).len(), 1);
+
+        testing_env!(get_context("owner.near".parse().unwrap()).build());
+        contract.add_admin("ops.near".parse().unwrap());
+        testing_env!(get_context("ops.near".parse().unwrap()).build());
+        let delisted = contract.delist_inactive(vec![listed.clone(), "999".to_string(), unlisted.clone()]);
+
+        assert_eq!(delisted, vec![listed.clone(), unlisted]);
+        let metadata = contract.get_list_metadata(listed.clone()).unwrap();
+        assert!(metadata.price.is_none());
+        assert!(!metadata.is_active);
+        assert!(contract.get_lists_for_sale(None, None).is_empty());
+        assert_eq!(get_logs().iter().filter(|log| log.contains("list_delisted")).count(), 2);
+        assert_eq!(
+            get_logs()[0],
+            format!(
+                "EVENT_JSON:{{\"standard\":\"argus-source-list\",\"version\":\"1.0.0\",\"event\":\"list_delisted\",\"data\":[{{\"token_id\":\"{}\",\"creator\":\"alice.near\"}}]}}",
+                listed
+            )
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Only admin")]
+    fn test_delist_inactive_requires_admin() {
+        let (mut contract, token_id) = setup();
+        contract.delist_inactive(vec![token_id]);
+    }
+
+    /// Write the original contract's state with one rated list owned by alice
+    fn write_old_state() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let token_id: TokenId = "srclist-1".to_string();
+
+        let mut tokens_by_id = UnorderedMap::new(StorageKey::TokensById);
+        tokens_by_id.insert(token_id.clone(), Token { owner_id: alice.clone(), next_approval_id: 0 });
+        let mut owned = UnorderedSet::new(StorageKey::TokenPerOwnerInner {
+            account_id_hash: env::sha256(alice.as_bytes()).to_vec(),
+        });
+        owned.insert(token_id.clone());
+        let mut tokens_per_owner = LookupMap::new(StorageKey::TokensPerOwner);
+        tokens_per_owner.insert(alice.clone(), owned);
+
+        let mut list_metadata_by_id = UnorderedMap::new(StorageKey::ListMetadata);
+        list_metadata_by_id.insert(
+            token_id,
+            OldSourceListMetadata {
+                cid: "bafy-old".to_string(),
+                source_count: 7,
+                domain: "Cybersecurity".to_string(),
+                creator: alice,
+                is_active: true,
+                updated_at: U64(1),
+                price: Some(U128(5)),
+                royalty_percent: 10,
+                total_subscribers: 0,
+                avg_rating: 400,
+                rating_count: 2,
+            },
+        );
+
+        env::state_write(&OldSourceListNFT {
+            owner_id: "owner.near".parse().unwrap(),
+            tokens_per_owner,
+            tokens_by_id,
+            token_metadata_by_id: UnorderedMap::new(StorageKey::TokenMetadataById),
+            list_metadata_by_id,
+            approved_accounts: LookupMap::new(StorageKey::ApprovedAccounts { token_id_hash: vec![] }),
+            metadata: LazyOption::new(StorageKey::NFTContractMetadata, None),
+            next_token_id: 2,
+        });
+    }
+
+    #[test]
+    fn test_migrate_from_original_layout() {
+        testing_env!(get_context("source-lists.near".parse().unwrap()).build());
+        write_old_state();
+
+        let mut contract = SourceListNFT::migrate();
+
+        let metadata = contract.get_list_metadata("srclist-1".to_string()).unwrap();
+        assert_eq!(metadata.cid, "bafy-old");
+        assert_eq!(metadata.price, Some(U128(5)));
+        assert!(metadata.subscription_price.is_none());
+        assert!(metadata.price_ft.is_none());
+        assert!(metadata.ft_contract.is_none());
+        assert_eq!(contract.get_lists_for_owner("alice.near".parse().unwrap()), vec!["srclist-1".to_string()]);
+        assert_eq!(contract.get_lists_by_domain("cybersecurity".to_string(), None, None).len(), 1);
+
+        // New lists keep numbering after the migrated ones
+        assert_eq!(mint_list(&mut contract, "finance", None, None), "srclist-2");
+
+        // Ratings continue from the migrated average
+        testing_env!(get_context("alice.near".parse().unwrap()).build());
+        contract.rate_list("srclist-1".to_string(), 5);
+        let metadata = contract.get_list_metadata("srclist-1".to_string()).unwrap();
+        assert_eq!(metadata.rating_count, 3);
+        assert_eq!(metadata.avg_rating, 433);
+    }
+}
@@ -0,0 +1,89 @@
+//! Sandbox test: buy a list with a mock NEP-141 token via `ft_transfer_call`
+
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, Contract};
+use serde_json::{json, Value};
+
+async fn ft_balance(ft: &Contract, account: &Account) -> anyhow::Result<String> {
+    let balance: String = ft
+        .view("ft_balance_of")
+        .args_json(json!({ "account_id": account.id() }))
+        .await?
+        .json()?;
+    Ok(balance)
+}
+
+#[tokio::test]
+async fn test_ft_purchase_through_mock_token() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let nft = worker.dev_deploy(&near_workspaces::compile_project("./").await?).await?;
+    let ft = worker.dev_deploy(&near_workspaces::compile_project("./tests/mock-ft").await?).await?;
+    let alice = worker.dev_create_account().await?;
+    let bob = worker.dev_create_account().await?;
+    let carol = worker.dev_create_account().await?;
+
+    nft.call("new")
+        .args_json(json!({ "owner_id": nft.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+    ft.call("new").transact().await?.into_result()?;
+    ft.call("mint")
+        .args_json(json!({ "account_id": carol.id(), "amount": "6000000" }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // Alice creates the list with a 10% royalty and sells it on to bob
+    let token_id: String = alice
+        .call(nft.id(), "mint")
+        .args_json(json!({
+            "name": "OSINT Core",
+            "description": "Curated OSINT sources",
+            "cid": "bafy-list",
+            "source_count": 12,
+            "domain": "finance",
+            "price": null,
+            "royalty_percent": 10,
+        }))
+        .deposit(NearToken::from_millinear(100))
+        .transact()
+        .await?
+        .json()?;
+    alice
+        .call(nft.id(), "nft_transfer")
+        .args_json(json!({ "receiver_id": bob.id(), "token_id": token_id }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?
+        .into_result()?;
+    bob.call(nft.id(), "set_ft_price")
+        .args_json(json!({ "token_id": token_id, "ft_contract": ft.id(), "price_ft": "5000000" }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // Carol overpays by 1 token; the mock refunds what ft_on_transfer reports unused
+    carol
+        .call(ft.id(), "ft_transfer_call")
+        .args_json(json!({ "receiver_id": nft.id(), "amount": "6000000", "msg": token_id }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    let token: Value = nft.view("nft_token").args_json(json!({ "token_id": token_id })).await?.json()?;
+    assert_eq!(token["owner_id"], carol.id().as_str());
+
+    assert_eq!(ft_balance(&ft, &carol).await?, "1000000");
+    assert_eq!(ft_balance(&ft, &bob).await?, "4500000");
+    assert_eq!(ft_balance(&ft, &alice).await?, "500000");
+    assert_eq!(ft_balance(&ft, nft.as_account()).await?, "0");
+
+    let list: Value = nft.view("get_list_metadata").args_json(json!({ "token_id": token_id })).await?.json()?;
+    assert_eq!(list["price_ft"], Value::Null);
+    assert_eq!(list["ft_contract"], Value::Null);
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//! Minimal NEP-141 token for driving `ft_on_transfer` in workspaces tests
+//!
+//! No storage registration or metadata; anyone can mint.
+
+use near_sdk::json_types::U128;
+use near_sdk::serde_json;
+use near_sdk::store::LookupMap;
+use near_sdk::{env, ext_contract, near, require, AccountId, Gas, PanicOnDefault, Promise, PromiseResult};
+
+const GAS_FOR_FT_ON_TRANSFER: Gas = Gas::from_tgas(50);
+const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas::from_tgas(10);
+
+#[ext_contract(ext_ft_receiver)]
+pub trait FungibleTokenReceiver {
+    fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> U128;
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct MockFungibleToken {
+    balances: LookupMap<AccountId, u128>,
+}
+
+#[near]
+impl MockFungibleToken {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            balances: LookupMap::new(b"b"),
+        }
+    }
+
+    /// Credit `amount` to `account_id`
+    pub fn mint(&mut self, account_id: AccountId, amount: U128) {
+        let balance = self.ft_balance_of(account_id.clone()).0;
+        self.balances.insert(account_id, balance + amount.0);
+    }
+
+    pub fn ft_balance_of(&self, account_id: AccountId) -> U128 {
+        U128(self.balances.get(&account_id).copied().unwrap_or(0))
+    }
+
+    #[payable]
+    pub fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, _memo: Option<String>) {
+        self.internal_transfer(&env::predecessor_account_id(), &receiver_id, amount.0);
+    }
+
+    /// Transfer, then refund whatever the receiver reports as unused
+    #[payable]
+    pub fn ft_transfer_call(&mut self, receiver_id: AccountId, amount: U128, _memo: Option<String>, msg: String) -> Promise {
+        let sender_id = env::predecessor_account_id();
+        self.internal_transfer(&sender_id, &receiver_id, amount.0);
+
+        ext_ft_receiver::ext(receiver_id.clone())
+            .with_static_gas(GAS_FOR_FT_ON_TRANSFER)
+            .ft_on_transfer(sender_id.clone(), amount, msg)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .ft_resolve_transfer(sender_id, receiver_id, amount),
+            )
+    }
+
+    /// Returns the amount the receiver kept
+    #[private]
+    pub fn ft_resolve_transfer(&mut self, sender_id: AccountId, receiver_id: AccountId, amount: U128) -> U128 {
+        let unused = match env::promise_result(0) {
+            PromiseResult::Successful(value) => serde_json::from_slice::<U128>(&value)
+                .map(|unused| unused.0.min(amount.0))
+                .unwrap_or(amount.0),
+            PromiseResult::Failed => amount.0,
+        };
+
+        let refund = unused.min(self.ft_balance_of(receiver_id.clone()).0);
+        if refund > 0 {
+            self.internal_transfer(&receiver_id, &sender_id, refund);
+        }
+        U128(amount.0 - refund)
+    }
+
+    fn internal_transfer(&mut self, sender_id: &AccountId, receiver_id: &AccountId, amount: u128) {
+        let sender_balance = self.ft_balance_of(sender_id.clone()).0;
+        require!(sender_balance >= amount, "Insufficient balance");
+        self.balances.insert(sender_id.clone(), sender_balance - amount);
+        let receiver_balance = self.ft_balance_of(receiver_id.clone()).0;
+        self.balances.insert(receiver_id.clone(), receiver_balance + amount);
+    }
+}